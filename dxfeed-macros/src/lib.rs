@@ -0,0 +1,115 @@
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, ImplItem, ItemImpl};
+
+/// Typed handler method name => `EventData` variant it receives.
+const TYPED_METHODS: [(&str, &str); 14] = [
+    ("on_trade", "Trade"),
+    ("on_quote", "Quote"),
+    ("on_summary", "Summary"),
+    ("on_profile", "Profile"),
+    ("on_order", "Order"),
+    ("on_time_and_sale", "TimeAndSale"),
+    ("on_candle", "Candle"),
+    ("on_trade_eth", "TradeETH"),
+    ("on_spread_order", "SpreadOrder"),
+    ("on_greeks", "Greeks"),
+    ("on_theo_price", "TheoPrice"),
+    ("on_underlying", "Underlying"),
+    ("on_series", "Series"),
+    ("on_configuration", "Configuration"),
+];
+
+/// Generates the `extern "C"` trampoline and `user_data` plumbing for an `impl` block, and
+/// implements `dxfeed::RawListener` for its type.
+///
+/// Events are routed to whichever of these methods the block defines:
+/// - `on_quote(&mut self, sym: &str, data: &Payload)`, `on_trade`, ... one per `EventData` variant
+/// - `on_event(&mut self, event: &dxfeed::Event)` for events without a typed method
+/// - `on_error(&mut self, error: dxfeed::Error)` for events that failed conversion
+///
+/// ```ignore
+/// struct Printer;
+///
+/// #[dxfeed::listener]
+/// impl Printer {
+///     fn on_quote(&mut self, sym: &str, quote: &dxfeed::QuoteData) {
+///         println!("{} {}", sym, quote.bid.price);
+///     }
+/// }
+///
+/// let mut printer = Printer;
+/// unsafe { dxfeed::RawListener::attach_raw(&mut printer, sub) }?;
+/// ```
+#[proc_macro_attribute]
+pub fn listener(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let item_impl = parse_macro_input!(item as ItemImpl);
+    let methods: Vec<String> = item_impl
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            ImplItem::Fn(method) => Some(method.sig.ident.to_string()),
+            _ => None,
+        })
+        .collect();
+    let has = |name: &str| methods.iter().any(|method| method == name);
+
+    let typed_arms =
+        TYPED_METHODS
+            .iter()
+            .filter(|(method, _)| has(method))
+            .map(|(method, variant)| {
+                let method = format_ident!("{}", method);
+                let variant = format_ident!("{}", variant);
+                quote! {
+                    ::dxfeed::EventData::#variant(data) => this.#method(&event.sym, data),
+                }
+            });
+    let fallback_arm = if has("on_event") {
+        quote! { _ => this.on_event(&event), }
+    } else {
+        quote! { _ => {} }
+    };
+    let error_arm = if has("on_error") {
+        quote! { Err(error) => this.on_error(error), }
+    } else {
+        quote! { Err(_) => {} }
+    };
+
+    let (impl_generics, _ty_generics, where_clause) = item_impl.generics.split_for_impl();
+    let self_ty = &item_impl.self_ty;
+    let expanded = quote! {
+        #item_impl
+
+        impl #impl_generics #self_ty #where_clause {
+            #[doc(hidden)]
+            #[allow(unreachable_patterns)]
+            extern "C" fn __dxfeed_listener_trampoline(
+                event_type: ::std::os::raw::c_int,
+                sym: ::dxfeed::dxf_const_string_t,
+                data: *const ::dxfeed::dxf_event_data_t,
+                _data_count: ::std::os::raw::c_int,
+                user_data: *mut ::std::os::raw::c_void,
+            ) {
+                // A panic must not unwind into C; it is handled by `dxfeed::set_panic_policy`
+                ::dxfeed::__private::guard("event listener", (), || {
+                    let this = unsafe { &mut *(user_data as *mut Self) };
+                    match ::dxfeed::Event::try_from_c(event_type, sym, data) {
+                        Ok(event) => match &event.data {
+                            #(#typed_arms)*
+                            #fallback_arm
+                        },
+                        #error_arm
+                    }
+                })
+            }
+        }
+
+        unsafe impl #impl_generics ::dxfeed::RawListener for #self_ty #where_clause {
+            fn trampoline() -> ::dxfeed::dxf_event_listener_t {
+                Some(Self::__dxfeed_listener_trampoline)
+            }
+        }
+    };
+    expanded.into()
+}
@@ -0,0 +1,128 @@
+//! Python bindings for the `dxfeed` crate. Events are converted to dicts via their serde
+//! serialization.
+
+// Triggered by `#[pymethods]` expansions returning `PyResult` (fixed in pyo3 0.23)
+#![allow(clippy::useless_conversion)]
+
+use dxfeed::{EventStream, EventType, FeedBackend};
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use std::str::FromStr;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long to wait for an event before checking for Python signals (e.g. Ctrl-C).
+const SIGNAL_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+fn to_py_err(error: dxfeed::Error) -> PyErr {
+    PyRuntimeError::new_err(error.to_string())
+}
+
+#[pyclass(module = "dxfeed")]
+struct Connection {
+    inner: dxfeed::Connection,
+}
+
+#[pymethods]
+impl Connection {
+    /// Connect to `address`, e.g. "demo.dxfeed.com:7300".
+    #[new]
+    fn new(address: &str) -> PyResult<Self> {
+        let inner = dxfeed::Connection::new(address).map_err(to_py_err)?;
+        Ok(Connection { inner })
+    }
+
+    /// Subscribe to `event_types`, e.g. ["Quote", "Trade"].
+    fn subscribe(&self, event_types: Vec<String>) -> PyResult<Subscription> {
+        let event_types = event_types
+            .iter()
+            .map(|name| {
+                EventType::from_str(name)
+                    .map_err(|_| PyValueError::new_err(format!("Unknown event type: {}", name)))
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        let (inner, events) = self
+            .inner
+            .subscribe_stream(&event_types)
+            .map_err(to_py_err)?;
+        Ok(Subscription {
+            _inner: inner,
+            events: Mutex::new(events),
+        })
+    }
+}
+
+#[pyclass(module = "dxfeed")]
+struct Subscription {
+    // Declared first so it's closed before `events` is dropped, as fields drop in order.
+    _inner: dxfeed::Subscription,
+    events: Mutex<EventStream>,
+}
+
+#[pymethods]
+impl Subscription {
+    fn add_symbols(&self, symbols: Vec<String>) -> PyResult<()> {
+        let symbols: Vec<&str> = symbols.iter().map(String::as_str).collect();
+        self._inner.add_symbols(&symbols).map_err(to_py_err)
+    }
+
+    fn remove_symbols(&self, symbols: Vec<String>) -> PyResult<()> {
+        let symbols: Vec<&str> = symbols.iter().map(String::as_str).collect();
+        self._inner.remove_symbols(&symbols).map_err(to_py_err)
+    }
+
+    /// The next event, or None if none arrives within `timeout` seconds. Blocks indefinitely
+    /// without a timeout, or with one reaching past the clock's range. Raises ValueError for a
+    /// negative, NaN, infinite or too large timeout.
+    #[pyo3(signature = (timeout=None))]
+    fn get(&self, py: Python<'_>, timeout: Option<f64>) -> PyResult<Option<PyObject>> {
+        let events = &self.events;
+        let deadline = match timeout {
+            Some(secs) => {
+                let timeout = Duration::try_from_secs_f64(secs)
+                    .map_err(|_| PyValueError::new_err(format!("Invalid timeout: {}", secs)))?;
+                Instant::now().checked_add(timeout)
+            }
+            None => None,
+        };
+        loop {
+            let wait = match deadline {
+                Some(deadline) => deadline
+                    .saturating_duration_since(Instant::now())
+                    .min(SIGNAL_CHECK_INTERVAL),
+                None => SIGNAL_CHECK_INTERVAL,
+            };
+            let received = py.allow_threads(|| events.lock().unwrap().recv_timeout(wait));
+            match received {
+                Ok(Ok(event)) => return Ok(Some(pythonize::pythonize(py, &event)?.unbind())),
+                Ok(Err(error)) => return Err(to_py_err(error)),
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(PyRuntimeError::new_err("Subscription closed"))
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    py.check_signals()?;
+                    if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                        return Ok(None);
+                    }
+                }
+            }
+        }
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        self.get(py, None)
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "dxfeed")]
+fn dxfeed_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Connection>()?;
+    m.add_class::<Subscription>()?;
+    Ok(())
+}
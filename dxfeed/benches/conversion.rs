@@ -0,0 +1,133 @@
+//! Benchmarks for the path every event takes from the C callback to a consumer: conversion of
+//! the raw struct, decoding of wide strings, serialization, and the channel hand-off.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use dxfeed::{
+    Event, EventData, ProfileEventData, QuoteData, DXF_ET_CANDLE, DXF_ET_ORDER, DXF_ET_PROFILE,
+    DXF_ET_QUOTE, DXF_ET_TIME_AND_SALE, DXF_ET_TRADE,
+};
+use libdxfeed_sys::*;
+use std::os::raw::c_int;
+use std::sync::mpsc::channel;
+use widestring::WideCString;
+
+fn wide(s: &str) -> WideCString {
+    WideCString::from_str(s).unwrap()
+}
+
+fn wide_ptr(s: &WideCString) -> dxf_const_string_t {
+    s.as_ptr() as dxf_const_string_t
+}
+
+fn bench_try_from_c<T>(c: &mut Criterion, name: &str, event_type: c_int, data: &T) {
+    let sym = wide("AAPL");
+    let data = data as *const T as *const dxf_event_data_t;
+    c.bench_function(&format!("try_from_c/{}", name), |b| {
+        b.iter(|| Event::try_from_c(black_box(event_type), wide_ptr(&sym), black_box(data)))
+    });
+}
+
+fn try_from_c(c: &mut Criterion) {
+    // An option symbol, longer than the stock symbols of the other benchmarks
+    let mut option_quote: dxf_quote_t = unsafe { std::mem::zeroed() };
+    option_quote.bid_price = 4.15;
+    option_quote.ask_price = 4.2;
+    let sym = wide(".AAPL240621C190");
+    let data = &option_quote as *const dxf_quote_t as *const dxf_event_data_t;
+    c.bench_function("try_from_c/option_quote", |b| {
+        b.iter(|| Event::try_from_c(black_box(DXF_ET_QUOTE), wide_ptr(&sym), black_box(data)))
+    });
+
+    // The raw structs are plain C data, so all-zero is a valid value for any of them once the
+    // string pointers are filled in
+    let mut quote: dxf_quote_t = unsafe { std::mem::zeroed() };
+    quote.bid_price = 189.5;
+    quote.ask_price = 189.52;
+    bench_try_from_c(c, "quote", DXF_ET_QUOTE, &quote);
+
+    let mut trade: dxf_trade_t = unsafe { std::mem::zeroed() };
+    trade.price = 189.51;
+    trade.size = 100.0;
+    bench_try_from_c(c, "trade", DXF_ET_TRADE, &trade);
+
+    let candle: dxf_candle_t = unsafe { std::mem::zeroed() };
+    bench_try_from_c(c, "candle", DXF_ET_CANDLE, &candle);
+
+    let description = wide("Apple Inc. - Common Stock");
+    let status_reason = wide("");
+    let mut profile: dxf_profile_t = unsafe { std::mem::zeroed() };
+    profile.description = wide_ptr(&description);
+    profile.status_reason = wide_ptr(&status_reason);
+    bench_try_from_c(c, "profile", DXF_ET_PROFILE, &profile);
+
+    let conditions = wide("@ TI");
+    let buyer = wide("NSDQ");
+    let seller = wide("ARCA");
+    let mut time_and_sale: dxf_time_and_sale_t = unsafe { std::mem::zeroed() };
+    time_and_sale.exchange_sale_conditions = wide_ptr(&conditions);
+    time_and_sale.buyer = wide_ptr(&buyer);
+    time_and_sale.seller = wide_ptr(&seller);
+    bench_try_from_c(c, "time_and_sale", DXF_ET_TIME_AND_SALE, &time_and_sale);
+
+    let market_maker = wide("NSDQ");
+    let mut order: dxf_order_t = unsafe { std::mem::zeroed() };
+    order.__bindgen_anon_1.market_maker = wide_ptr(&market_maker);
+    for (i, c) in "NTV".chars().enumerate() {
+        order.source[i] = c as dxf_char_t;
+    }
+    bench_try_from_c(c, "order", DXF_ET_ORDER, &order);
+}
+
+fn wide_string(c: &mut Criterion) {
+    let sym = wide(".AAPL240621C190");
+    let ptr = wide_ptr(&sym);
+    c.bench_function("wide_string/symbol", |b| {
+        b.iter(|| unsafe { WideCString::from_ptr_str(black_box(ptr) as *const _) }.to_string())
+    });
+}
+
+fn sample_events() -> Vec<(&'static str, Event)> {
+    let quote = QuoteData::default();
+    let profile = ProfileEventData {
+        description: "Apple Inc. - Common Stock".to_string(),
+        ..Default::default()
+    };
+    vec![
+        (
+            "quote",
+            Event::new("AAPL".to_string(), EventData::Quote(quote)),
+        ),
+        (
+            "profile",
+            Event::new("AAPL".to_string(), EventData::Profile(profile)),
+        ),
+    ]
+}
+
+fn serialize(c: &mut Criterion) {
+    for (name, event) in sample_events() {
+        c.bench_function(&format!("serialize/{}", name), |b| {
+            b.iter(|| serde_json::to_vec(black_box(&event)).unwrap())
+        });
+    }
+}
+
+fn channel_hand_off(c: &mut Criterion) {
+    let (sender, receiver) = channel();
+    let (_, event) = sample_events().remove(0);
+    c.bench_function("channel/send_recv", |b| {
+        b.iter(|| {
+            sender.send(Ok::<_, dxfeed::Error>(event.clone())).unwrap();
+            receiver.recv().unwrap()
+        })
+    });
+}
+
+criterion_group!(
+    benches,
+    try_from_c,
+    wide_string,
+    serialize,
+    channel_hand_off
+);
+criterion_main!(benches);
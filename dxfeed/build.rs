@@ -0,0 +1,35 @@
+fn main() {
+    #[cfg(feature = "proto")]
+    {
+        println!("cargo:rerun-if-changed=proto");
+        compile_protos();
+    }
+}
+
+// `protox` parses the protos in-process, so building the `proto` and `grpc` features doesn't
+// require `protoc`. feed.proto imports events.proto, so the gRPC build generates both.
+#[cfg(all(feature = "proto", feature = "grpc"))]
+fn compile_protos() {
+    use prost::Message;
+    use std::path::PathBuf;
+
+    const PROTO: &str = "proto/feed.proto";
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    let fds_path = out_dir.join("feed.fds");
+    let fds = protox::compile([PROTO], ["proto"]).expect("Unable to parse protos");
+    std::fs::write(&fds_path, fds.encode_to_vec()).expect("Couldn't write file descriptors!");
+    tonic_build::configure()
+        .file_descriptor_set_path(&fds_path)
+        .skip_protoc_run()
+        .compile(&[PROTO], &["proto"])
+        .expect("Unable to generate gRPC code");
+}
+
+#[cfg(all(feature = "proto", not(feature = "grpc")))]
+fn compile_protos() {
+    const PROTO: &str = "proto/events.proto";
+    let fds = protox::compile([PROTO], ["proto"]).expect("Unable to parse protos");
+    prost_build::Config::new()
+        .compile_fds(fds)
+        .expect("Unable to generate protobuf code");
+}
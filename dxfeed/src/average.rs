@@ -0,0 +1,292 @@
+use crate::{Event, EventData, EventFlags};
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+/// The trades a `VwapCalculator` or `TwapCalculator` averages over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AverageWindow {
+    /// Every trade since the first, or since the last `reset`
+    Cumulative,
+    /// The trades within this long of the latest one
+    Rolling(Duration),
+    /// The trades of the latest one's epoch-aligned interval, e.g. a day, resetting as each
+    /// interval begins
+    Interval(Duration),
+}
+
+/// (time, price, size) of a trade.
+type Point = (i64, f64, f64);
+
+/// The trades of one symbol within its window.
+#[derive(Default)]
+struct Samples {
+    points: VecDeque<Point>,
+    // Start of the interval or rolling window; trades before it don't count
+    start: i64,
+    notional: f64,
+    volume: f64,
+}
+
+impl Samples {
+    fn push(&mut self, window: AverageWindow, time: i64, price: f64, size: f64) {
+        if let Some(&(last, _, _)) = self.points.back() {
+            if time < last {
+                // Out of order; dropped so times stay sorted
+                return;
+            }
+        }
+        match window {
+            AverageWindow::Cumulative => {
+                if self.points.is_empty() {
+                    self.start = time;
+                }
+            }
+            AverageWindow::Rolling(length) => self.start = time - length.as_millis() as i64,
+            AverageWindow::Interval(length) => {
+                let length = (length.as_millis() as i64).max(1);
+                let start = time.div_euclid(length) * length;
+                if start != self.start {
+                    *self = Samples {
+                        start,
+                        ..Samples::default()
+                    };
+                }
+            }
+        }
+        self.points.push_back((time, price, size));
+        self.notional += price * size;
+        self.volume += size;
+        self.evict();
+    }
+
+    /// Drop the trades before `start`, keeping the latest of them for the TWAP, as its price
+    /// held from `start` until the next trade.
+    fn evict(&mut self) {
+        while let Some(&(time, price, size)) = self.points.front() {
+            if time >= self.start {
+                break;
+            }
+            if self.points.get(1).is_none_or(|next| next.0 > self.start) {
+                // The price at `start`, no longer part of the VWAP
+                if size != 0.0 {
+                    self.notional -= price * size;
+                    self.volume -= size;
+                    self.points[0].2 = 0.0;
+                }
+                break;
+            }
+            self.points.pop_front();
+            self.notional -= price * size;
+            self.volume -= size;
+        }
+    }
+
+    fn vwap(&self) -> Option<f64> {
+        (self.volume > 0.0).then(|| self.notional / self.volume)
+    }
+
+    fn twap(&self, now: i64) -> Option<f64> {
+        let (mut weighted, mut elapsed) = (0.0, 0.0);
+        for (i, &(time, price, _)) in self.points.iter().enumerate() {
+            let from = time.max(self.start);
+            let to = self.points.get(i + 1).map_or(now, |next| next.0).max(from);
+            weighted += price * (to - from) as f64;
+            elapsed += (to - from) as f64;
+        }
+        if elapsed > 0.0 {
+            Some(weighted / elapsed)
+        } else {
+            // No time has passed since the only trade
+            self.points.back().map(|&(_, price, _)| price)
+        }
+    }
+}
+
+/// (symbol, time, price, size) of a Trade or TimeAndSale event, other than a removal.
+fn trade_of(event: &Event) -> Option<(&str, i64, f64, f64)> {
+    match &event.data {
+        EventData::Trade(trade) => Some((&event.sym, trade.time, trade.price, trade.size)),
+        EventData::TimeAndSale(time_and_sale)
+            if !EventFlags::from(time_and_sale.event_flags).is_remove_event() =>
+        {
+            Some((
+                &event.sym,
+                time_and_sale.time,
+                time_and_sale.price,
+                time_and_sale.size,
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// The trades per symbol, for either calculator.
+struct Averages {
+    window: AverageWindow,
+    symbols: HashMap<String, Samples>,
+}
+
+impl Averages {
+    fn new(window: AverageWindow) -> Self {
+        Averages {
+            window,
+            symbols: HashMap::new(),
+        }
+    }
+
+    fn on_trade(&mut self, symbol: &str, time: i64, price: f64, size: f64) {
+        if !price.is_finite() {
+            return;
+        }
+        let size = if size.is_finite() { size.max(0.0) } else { 0.0 };
+        if let Some(samples) = self.symbols.get_mut(symbol) {
+            samples.push(self.window, time, price, size);
+        } else {
+            let mut samples = Samples::default();
+            samples.push(self.window, time, price, size);
+            self.symbols.insert(symbol.to_string(), samples);
+        }
+    }
+
+    fn on_event(&mut self, event: &Event) {
+        if let Some((symbol, time, price, size)) = trade_of(event) {
+            self.on_trade(symbol, time, price, size);
+        }
+    }
+
+    fn reset(&mut self, symbol: &str) {
+        self.symbols.remove(symbol);
+    }
+
+    fn reset_all(&mut self) {
+        self.symbols.clear();
+    }
+}
+
+/// Volume-weighted average price per symbol, from Trade or TimeAndSale events over an
+/// `AverageWindow`. Prefer TimeAndSale as the source: Trade events are conflated, so some
+/// trades may be missed.
+pub struct VwapCalculator {
+    averages: Averages,
+}
+
+impl VwapCalculator {
+    pub fn new(window: AverageWindow) -> Self {
+        VwapCalculator {
+            averages: Averages::new(window),
+        }
+    }
+
+    /// Add a trade of `size` at `price` for `symbol` at `time` (milliseconds since the epoch).
+    /// Trades older than the symbol's latest are ignored.
+    pub fn on_trade(&mut self, symbol: &str, time: i64, price: f64, size: f64) {
+        self.averages.on_trade(symbol, time, price, size)
+    }
+
+    /// `on_trade` with a Trade or TimeAndSale event; other events, and removals, are ignored.
+    pub fn on_event(&mut self, event: &Event) {
+        self.averages.on_event(event)
+    }
+
+    /// The VWAP of `symbol`, or `None` before any volume has traded in the window.
+    pub fn vwap(&self, symbol: &str) -> Option<f64> {
+        self.averages.symbols.get(symbol)?.vwap()
+    }
+
+    /// The volume of `symbol` traded in the window.
+    pub fn volume(&self, symbol: &str) -> f64 {
+        self.averages
+            .symbols
+            .get(symbol)
+            .map_or(0.0, |samples| samples.volume)
+    }
+
+    pub fn reset(&mut self, symbol: &str) {
+        self.averages.reset(symbol)
+    }
+
+    pub fn reset_all(&mut self) {
+        self.averages.reset_all()
+    }
+}
+
+/// Time-weighted average price per symbol, from Trade or TimeAndSale events over an
+/// `AverageWindow`, weighting each price by how long it stood until the next trade.
+pub struct TwapCalculator {
+    averages: Averages,
+}
+
+impl TwapCalculator {
+    pub fn new(window: AverageWindow) -> Self {
+        TwapCalculator {
+            averages: Averages::new(window),
+        }
+    }
+
+    /// Add a trade at `price` for `symbol` at `time` (milliseconds since the epoch). Trades
+    /// older than the symbol's latest are ignored.
+    pub fn on_trade(&mut self, symbol: &str, time: i64, price: f64) {
+        self.averages.on_trade(symbol, time, price, 0.0)
+    }
+
+    /// `on_trade` with a Trade or TimeAndSale event; other events, and removals, are ignored.
+    pub fn on_event(&mut self, event: &Event) {
+        self.averages.on_event(event)
+    }
+
+    /// The TWAP of `symbol` up to `now` (milliseconds since the epoch), with the latest price
+    /// standing until then, or `None` before any trade.
+    pub fn twap(&self, symbol: &str, now: i64) -> Option<f64> {
+        self.averages.symbols.get(symbol)?.twap(now)
+    }
+
+    pub fn reset(&mut self, symbol: &str) {
+        self.averages.reset(symbol)
+    }
+
+    pub fn reset_all(&mut self) {
+        self.averages.reset_all()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vwap_windows() {
+        let mut cumulative = VwapCalculator::new(AverageWindow::Cumulative);
+        let mut rolling = VwapCalculator::new(AverageWindow::Rolling(Duration::from_secs(10)));
+        let mut interval = VwapCalculator::new(AverageWindow::Interval(Duration::from_secs(60)));
+        for vwap in [&mut cumulative, &mut rolling, &mut interval] {
+            vwap.on_trade("SPY", 50_000, 10.0, 100.0);
+            vwap.on_trade("SPY", 55_000, 20.0, 300.0);
+            vwap.on_trade("SPY", 62_000, 30.0, 100.0);
+        }
+        assert_eq!(cumulative.vwap("SPY"), Some(20.0));
+        assert_eq!(cumulative.volume("SPY"), 500.0);
+        // The first trade fell out of the last 10 seconds
+        assert_eq!(rolling.vwap("SPY"), Some(22.5));
+        // A new minute began
+        assert_eq!(interval.vwap("SPY"), Some(30.0));
+
+        cumulative.reset("SPY");
+        assert_eq!(cumulative.vwap("SPY"), None);
+        assert_eq!(interval.vwap("QQQ"), None);
+    }
+
+    #[test]
+    fn twap_weights_by_time() {
+        let mut twap = TwapCalculator::new(AverageWindow::Cumulative);
+        twap.on_trade("SPY", 0, 10.0);
+        assert_eq!(twap.twap("SPY", 0), Some(10.0));
+        twap.on_trade("SPY", 3_000, 20.0);
+        assert_eq!(twap.twap("SPY", 4_000), Some(12.5));
+
+        let mut rolling = TwapCalculator::new(AverageWindow::Rolling(Duration::from_secs(2)));
+        rolling.on_trade("SPY", 0, 10.0);
+        rolling.on_trade("SPY", 3_000, 20.0);
+        // 10 stood from the window's start at 1s until 3s, then 20 until 4s
+        assert!((rolling.twap("SPY", 4_000).unwrap() - 40.0 / 3.0).abs() < 1e-9);
+    }
+}
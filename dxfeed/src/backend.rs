@@ -0,0 +1,277 @@
+//! Traits abstracting over feed backends, so application code can switch between the C API
+//! (`Connection`, or `ReconnectingConnection`), dxLink (`DxLinkConnection`), or a mock/replay
+//! implementation. Every backend delivers the same `Event` model, and every delivery mode here,
+//! from channels to typed listeners, works with any of them.
+//!
+//! `Connection` and `Subscription` stay concrete wrappers over the C API, the default backend,
+//! rather than being generic over one: code meant to run on any backend is written against
+//! `FeedBackend` and `FeedSubscription` instead.
+
+use crate::backpressure::bounded;
+use crate::batch::Batcher;
+use crate::chunk::add_in_chunks;
+use crate::ring::ring;
+use crate::{
+    Backpressure, BatchOptions, ChunkOptions, ChunkProgress, Connection, Error, Event,
+    EventBatches, EventQueue, EventRing, EventType, ReconnectingConnection,
+    ReconnectingSubscription, Subscription, TypedDispatcher,
+};
+use std::sync::mpsc::{channel, Receiver};
+
+/// Receives a subscription's events, as returned by `FeedBackend::subscribe_stream`.
+pub type EventStream = Receiver<Result<Event, Error>>;
+
+/// A source of events for subscribed symbols.
+pub trait FeedBackend: Sized {
+    type Subscription: FeedSubscription;
+
+    fn connect(address: &str) -> Result<Self, Error>;
+
+    /// Subscribe to `event_types`, delivering every event (or conversion error) to `listener`.
+    /// Events are delivered only while the returned subscription is alive.
+    fn subscribe<F>(
+        &self,
+        event_types: &[EventType],
+        listener: F,
+    ) -> Result<Self::Subscription, Error>
+    where
+        F: FnMut(Result<Event, Error>) + Send + 'static;
+
+    /// Like `subscribe`, but delivers events to the returned channel instead.
+    fn subscribe_stream(
+        &self,
+        event_types: &[EventType],
+    ) -> Result<(Self::Subscription, EventStream), Error> {
+        let (sender, receiver) = channel();
+        let subscription = self.subscribe(event_types, move |event| {
+            let _ = sender.send(event);
+        })?;
+        Ok((subscription, receiver))
+    }
+
+    /// Like `subscribe`, but routes each event to the listener `dispatcher` has for its type.
+    /// Events that fail conversion, or have no registered listener, are dropped.
+    fn subscribe_typed(
+        &self,
+        event_types: &[EventType],
+        mut dispatcher: TypedDispatcher,
+    ) -> Result<Self::Subscription, Error> {
+        self.subscribe(event_types, move |event| {
+            if let Ok(event) = event {
+                dispatcher.dispatch(&event);
+            }
+        })
+    }
+
+    /// Like `subscribe_stream`, but delivers events in batches as `options` allows. Events that
+    /// fail conversion are dropped.
+    fn subscribe_batched(
+        &self,
+        event_types: &[EventType],
+        options: BatchOptions,
+    ) -> Result<(Self::Subscription, EventBatches), Error> {
+        let (mut batcher, receiver) = Batcher::new(options)?;
+        let subscription = self.subscribe(event_types, move |event| batcher.push(event))?;
+        Ok((subscription, receiver))
+    }
+
+    /// Like `subscribe_stream`, but delivers events to a queue holding at most `capacity`,
+    /// handling events that arrive while it is full as `policy` says. Events that fail
+    /// conversion are dropped.
+    fn subscribe_bounded(
+        &self,
+        event_types: &[EventType],
+        capacity: usize,
+        policy: Backpressure,
+    ) -> Result<(Self::Subscription, EventQueue), Error> {
+        let (sender, queue) = bounded(capacity, policy);
+        let subscription = self.subscribe(event_types, move |event| {
+            if let Ok(event) = event {
+                sender.send(event);
+            }
+        })?;
+        Ok((subscription, queue))
+    }
+
+    /// Like `subscribe_stream`, but delivers events through a lock-free ring buffer of at least
+    /// `capacity` events, allocated up front. Events arriving while it is full are dropped, as
+    /// are events that fail conversion.
+    fn subscribe_ring(
+        &self,
+        event_types: &[EventType],
+        capacity: usize,
+    ) -> Result<(Self::Subscription, EventRing), Error> {
+        let (mut producer, ring) = ring(capacity);
+        let subscription = self.subscribe(event_types, move |event| {
+            if let Ok(event) = event {
+                producer.push(event);
+            }
+        })?;
+        Ok((subscription, ring))
+    }
+}
+
+/// Symbol management for a `FeedBackend` subscription.
+pub trait FeedSubscription {
+    fn add_symbols(&self, symbols: &[&str]) -> Result<(), Error>;
+
+    fn remove_symbols(&self, symbols: &[&str]) -> Result<(), Error>;
+
+    /// Add `symbols` in calls of at most `options.chunk_size`, for lists too large for one
+    /// call. A failed chunk is retried as `options` allows, and `progress` is called after
+    /// every attempt. Fails with the error of a chunk out of retries, leaving the earlier
+    /// chunks added.
+    fn add_symbols_chunked<F>(
+        &self,
+        symbols: &[&str],
+        options: ChunkOptions,
+        progress: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(&ChunkProgress),
+    {
+        add_in_chunks(self, symbols, options, progress)
+    }
+}
+
+impl FeedBackend for Connection {
+    type Subscription = Subscription;
+
+    fn connect(address: &str) -> Result<Self, Error> {
+        Connection::new(address)
+    }
+
+    fn subscribe<F>(&self, event_types: &[EventType], listener: F) -> Result<Subscription, Error>
+    where
+        F: FnMut(Result<Event, Error>) + Send + 'static,
+    {
+        let mut sub = Subscription::new(self, event_types)?;
+        sub.attach(listener)?;
+        Ok(sub)
+    }
+}
+
+impl FeedSubscription for Subscription {
+    fn add_symbols(&self, symbols: &[&str]) -> Result<(), Error> {
+        Subscription::add_symbols(self, symbols)
+    }
+
+    fn remove_symbols(&self, symbols: &[&str]) -> Result<(), Error> {
+        Subscription::remove_symbols(self, symbols)
+    }
+}
+
+impl FeedBackend for ReconnectingConnection {
+    type Subscription = ReconnectingSubscription;
+
+    /// Connects with the default `Backoff`; use `ReconnectingConnection::connect_failover`
+    /// directly for backup addresses.
+    fn connect(address: &str) -> Result<Self, Error> {
+        ReconnectingConnection::connect(address, Default::default())
+    }
+
+    fn subscribe<F>(
+        &self,
+        event_types: &[EventType],
+        listener: F,
+    ) -> Result<ReconnectingSubscription, Error>
+    where
+        F: FnMut(Result<Event, Error>) + Send + 'static,
+    {
+        ReconnectingConnection::subscribe(self, event_types, listener)
+    }
+}
+
+impl FeedSubscription for ReconnectingSubscription {
+    fn add_symbols(&self, symbols: &[&str]) -> Result<(), Error> {
+        ReconnectingSubscription::add_symbols(self, symbols)
+    }
+
+    fn remove_symbols(&self, symbols: &[&str]) -> Result<(), Error> {
+        ReconnectingSubscription::remove_symbols(self, symbols)
+    }
+}
+
+#[cfg(feature = "dxlink")]
+impl FeedBackend for crate::DxLinkConnection {
+    type Subscription = crate::DxLinkFeed;
+
+    /// Connects without an auth token; use `DxLinkConnection::connect` directly to pass one.
+    fn connect(address: &str) -> Result<Self, Error> {
+        crate::DxLinkConnection::connect(address, None)
+    }
+
+    fn subscribe<F>(
+        &self,
+        event_types: &[EventType],
+        listener: F,
+    ) -> Result<crate::DxLinkFeed, Error>
+    where
+        F: FnMut(Result<Event, Error>) + Send + 'static,
+    {
+        self.open_feed(event_types, listener)
+    }
+}
+
+#[cfg(feature = "dxlink")]
+impl FeedSubscription for crate::DxLinkFeed {
+    fn add_symbols(&self, symbols: &[&str]) -> Result<(), Error> {
+        crate::DxLinkFeed::add_symbols(self, symbols)
+    }
+
+    fn remove_symbols(&self, symbols: &[&str]) -> Result<(), Error> {
+        crate::DxLinkFeed::remove_symbols(self, symbols)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EventData, MockFeed, ProfileEventData};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    /// Subscribe to Profiles of `symbol` on any backend.
+    fn profiles<B: FeedBackend>(
+        backend: &B,
+        symbol: &str,
+    ) -> Result<(B::Subscription, EventStream), Error> {
+        let (sub, events) = backend.subscribe_stream(&[EventType::Profile])?;
+        sub.add_symbols(&[symbol])?;
+        Ok((sub, events))
+    }
+
+    #[test]
+    fn generic_over_backend() {
+        let feed = MockFeed::connect("mock").unwrap();
+        let (_sub, events) = profiles(&feed, "AAPL").unwrap();
+        let data = EventData::Profile(ProfileEventData::default());
+        assert_eq!(feed.push(Event::new("AAPL".to_string(), data)), 1);
+        let event = events.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert_eq!(event.unwrap().sym, "AAPL");
+    }
+
+    #[test]
+    fn typed_listeners_on_any_backend() {
+        let feed = MockFeed::new();
+        let descriptions = Arc::new(Mutex::new(Vec::new()));
+        let seen = descriptions.clone();
+        let dispatcher = crate::TypedDispatcher::new().with_profile(
+            move |sym: &str, profile: &ProfileEventData| {
+                seen.lock()
+                    .unwrap()
+                    .push(format!("{}: {}", sym, profile.description))
+            },
+        );
+        let sub = feed
+            .subscribe_typed(&[EventType::Profile], dispatcher)
+            .unwrap();
+        sub.add_symbols(&["AAPL"]).unwrap();
+        let profile = ProfileEventData {
+            description: "Apple".to_string(),
+            ..Default::default()
+        };
+        feed.push(Event::new("AAPL".to_string(), EventData::Profile(profile)));
+        assert_eq!(*descriptions.lock().unwrap(), ["AAPL: Apple"]);
+    }
+}
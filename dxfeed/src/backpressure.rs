@@ -0,0 +1,299 @@
+//! A bounded queue of events with an explicit policy for when it is full, as delivered by
+//! `FeedBackend::subscribe_bounded`.
+//!
+//! ```ignore
+//! let (subscription, queue) =
+//!     connection.subscribe_bounded(&[EventType::Quote], 10_000, Backpressure::ConflateLatest)?;
+//! subscription.add_symbols(&["AAPL", "MSFT"])?;
+//! for event in queue.iter() {
+//!     slow_consumer(event);
+//! }
+//! eprintln!("{:?}", queue.stats());
+//! ```
+
+use crate::{Event, EventType};
+use std::collections::VecDeque;
+use std::sync::mpsc::{RecvError, RecvTimeoutError, TryRecvError};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// What to do with an event arriving while the queue is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Backpressure {
+    /// Wait for the consumer to make room. This stalls the thread delivering events, for the C
+    /// API the connection's socket thread, so it suits only consumers that keep up on average.
+    Block,
+    /// Drop the arriving event
+    #[default]
+    DropNewest,
+    /// Drop the oldest queued event to make room
+    DropOldest,
+    /// Replace the latest queued event of the same symbol and type, or else drop the arriving
+    /// event
+    ConflateLatest,
+}
+
+/// Counts of events a full queue didn't deliver as they arrived.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BackpressureStats {
+    /// Events dropped, whether arriving or queued
+    pub dropped: u64,
+    /// Queued events replaced by a later one under `ConflateLatest`
+    pub conflated: u64,
+}
+
+struct State {
+    events: VecDeque<Event>,
+    stats: BackpressureStats,
+    sender_alive: bool,
+    receiver_alive: bool,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    capacity: usize,
+    policy: Backpressure,
+}
+
+/// A queue of at most `capacity` events, and the listener side feeding it.
+pub(crate) fn bounded(capacity: usize, policy: Backpressure) -> (QueueSender, EventQueue) {
+    let capacity = capacity.max(1);
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            events: VecDeque::with_capacity(capacity),
+            stats: BackpressureStats::default(),
+            sender_alive: true,
+            receiver_alive: true,
+        }),
+        not_empty: Condvar::new(),
+        not_full: Condvar::new(),
+        capacity,
+        policy,
+    });
+    (QueueSender(shared.clone()), EventQueue(shared))
+}
+
+/// Feeds an `EventQueue`, disconnecting it when dropped.
+pub(crate) struct QueueSender(Arc<Shared>);
+
+impl QueueSender {
+    pub(crate) fn send(&self, event: Event) {
+        let shared = &*self.0;
+        let mut state = shared.state.lock().unwrap();
+        if state.events.len() >= shared.capacity {
+            match shared.policy {
+                Backpressure::Block => {
+                    while state.events.len() >= shared.capacity && state.receiver_alive {
+                        state = shared.not_full.wait(state).unwrap();
+                    }
+                }
+                Backpressure::DropNewest => {
+                    state.stats.dropped += 1;
+                    return;
+                }
+                Backpressure::DropOldest => {
+                    state.events.pop_front();
+                    state.stats.dropped += 1;
+                }
+                Backpressure::ConflateLatest => {
+                    let event_type = EventType::from(&event);
+                    let queued = state.events.iter_mut().rev().find(|queued| {
+                        queued.sym == event.sym && EventType::from(&**queued) == event_type
+                    });
+                    match queued {
+                        Some(queued) => {
+                            *queued = event;
+                            state.stats.conflated += 1;
+                        }
+                        None => state.stats.dropped += 1,
+                    }
+                    return;
+                }
+            }
+        }
+        if !state.receiver_alive {
+            return;
+        }
+        state.events.push_back(event);
+        shared.not_empty.notify_one();
+    }
+}
+
+impl Drop for QueueSender {
+    fn drop(&mut self) {
+        self.0.state.lock().unwrap().sender_alive = false;
+        self.0.not_empty.notify_all();
+    }
+}
+
+/// Receives a subscription's events through a bounded queue. Like a channel receiver, it
+/// disconnects once the subscription is dropped and the queue is drained.
+pub struct EventQueue(Arc<Shared>);
+
+impl EventQueue {
+    /// Wait for the next event.
+    pub fn recv(&self) -> Result<Event, RecvError> {
+        let mut state = self.0.state.lock().unwrap();
+        loop {
+            if let Some(event) = self.pop(&mut state) {
+                return Ok(event);
+            }
+            if !state.sender_alive {
+                return Err(RecvError);
+            }
+            state = self.0.not_empty.wait(state).unwrap();
+        }
+    }
+
+    /// Wait up to `timeout` for the next event.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<Event, RecvTimeoutError> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.0.state.lock().unwrap();
+        loop {
+            if let Some(event) = self.pop(&mut state) {
+                return Ok(event);
+            }
+            if !state.sender_alive {
+                return Err(RecvTimeoutError::Disconnected);
+            }
+            let left = deadline
+                .checked_duration_since(Instant::now())
+                .ok_or(RecvTimeoutError::Timeout)?;
+            state = self.0.not_empty.wait_timeout(state, left).unwrap().0;
+        }
+    }
+
+    pub fn try_recv(&self) -> Result<Event, TryRecvError> {
+        let mut state = self.0.state.lock().unwrap();
+        match self.pop(&mut state) {
+            Some(event) => Ok(event),
+            None if state.sender_alive => Err(TryRecvError::Empty),
+            None => Err(TryRecvError::Disconnected),
+        }
+    }
+
+    /// Events until the queue disconnects.
+    pub fn iter(&self) -> impl Iterator<Item = Event> + '_ {
+        std::iter::from_fn(|| self.recv().ok())
+    }
+
+    /// Number of queued events.
+    pub fn len(&self) -> usize {
+        self.0.state.lock().unwrap().events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.0.capacity
+    }
+
+    pub fn policy(&self) -> Backpressure {
+        self.0.policy
+    }
+
+    pub fn stats(&self) -> BackpressureStats {
+        self.0.state.lock().unwrap().stats
+    }
+
+    fn pop(&self, state: &mut State) -> Option<Event> {
+        let event = state.events.pop_front()?;
+        self.0.not_full.notify_one();
+        Some(event)
+    }
+}
+
+impl Drop for EventQueue {
+    fn drop(&mut self) {
+        // Unblocks a sender waiting under `Block`
+        self.0.state.lock().unwrap().receiver_alive = false;
+        self.0.not_full.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{testing, EventData, TradeData};
+
+    fn sequences(queue: &EventQueue) -> Vec<(String, i32)> {
+        std::iter::from_fn(|| queue.try_recv().ok())
+            .map(|event| {
+                let sequence = event.data.as_quote().map_or(-1, |quote| quote.sequence);
+                (event.sym, sequence)
+            })
+            .collect()
+    }
+
+    fn fill(policy: Backpressure) -> EventQueue {
+        let (sender, queue) = bounded(2, policy);
+        sender.send(testing::quote().sequence(1).event("AAPL"));
+        sender.send(testing::quote().sequence(2).event("MSFT"));
+        sender.send(testing::quote().sequence(3).event("AAPL"));
+        sender.send(testing::quote().sequence(4).event("SPY"));
+        queue
+    }
+
+    #[test]
+    fn full_queue_policies() {
+        let queue = fill(Backpressure::DropNewest);
+        assert_eq!(queue.stats().dropped, 2);
+        assert_eq!(
+            sequences(&queue),
+            [("AAPL".to_string(), 1), ("MSFT".to_string(), 2)]
+        );
+        // The sender is gone with `fill`
+        assert_eq!(queue.try_recv().unwrap_err(), TryRecvError::Disconnected);
+
+        let queue = fill(Backpressure::DropOldest);
+        assert_eq!(queue.stats().dropped, 2);
+        assert_eq!(
+            sequences(&queue),
+            [("AAPL".to_string(), 3), ("SPY".to_string(), 4)]
+        );
+
+        let queue = fill(Backpressure::ConflateLatest);
+        let stats = queue.stats();
+        assert_eq!((stats.dropped, stats.conflated), (1, 1));
+        assert_eq!(
+            sequences(&queue),
+            [("AAPL".to_string(), 3), ("MSFT".to_string(), 2)]
+        );
+
+        // Only events of the same type are conflated
+        let (sender, queue) = bounded(1, Backpressure::ConflateLatest);
+        sender.send(testing::quote().sequence(1).event("AAPL"));
+        sender.send(Event::new(
+            "AAPL".to_string(),
+            EventData::Trade(TradeData::default()),
+        ));
+        assert_eq!(queue.stats().dropped, 1);
+    }
+
+    #[test]
+    fn block_waits_for_room() {
+        let (sender, queue) = bounded(1, Backpressure::Block);
+        let thread = std::thread::spawn(move || {
+            for sequence in 0..3 {
+                sender.send(testing::quote().sequence(sequence).event("AAPL"));
+            }
+        });
+        let received: Vec<i32> = queue
+            .iter()
+            .map(|event| event.data.as_quote().unwrap().sequence)
+            .collect();
+        assert_eq!(received, [0, 1, 2]);
+        assert_eq!(queue.stats(), BackpressureStats::default());
+        thread.join().unwrap();
+
+        // A dropped queue doesn't leave the sender blocked
+        let (sender, queue) = bounded(1, Backpressure::Block);
+        sender.send(testing::quote().sequence(0).event("AAPL"));
+        drop(queue);
+        sender.send(testing::quote().sequence(1).event("AAPL"));
+    }
+}
@@ -0,0 +1,185 @@
+//! Delivery of events in batches, for consumers that write to disk or a network and would
+//! rather pay a channel send and a wakeup per batch than per event.
+//!
+//! ```ignore
+//! let options = BatchOptions::default().max_events(5_000);
+//! let (subscription, batches) = connection.subscribe_batched(&[EventType::Quote], options)?;
+//! subscription.add_symbols(&["AAPL", "MSFT"])?;
+//! for batch in batches {
+//!     writer.write_all(&encode(&batch))?;
+//! }
+//! ```
+
+use crate::{Error, Event};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+/// Receives a subscription's events in batches, as returned by `FeedBackend::subscribe_batched`.
+pub type EventBatches = Receiver<Vec<Event>>;
+
+/// When a batch is delivered: once it holds `max_events`, or once its first event has waited
+/// `max_delay`, whichever comes first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchOptions {
+    pub max_events: usize,
+    pub max_delay: Duration,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        BatchOptions {
+            max_events: 1_000,
+            max_delay: Duration::from_millis(100),
+        }
+    }
+}
+
+impl BatchOptions {
+    pub fn max_events(mut self, max_events: usize) -> Self {
+        self.max_events = max_events.max(1);
+        self
+    }
+
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+}
+
+struct Pending {
+    events: Vec<Event>,
+    /// When the first event of `events` arrived
+    since: Option<Instant>,
+    sender: Sender<Vec<Event>>,
+    options: BatchOptions,
+}
+
+impl Pending {
+    fn push(&mut self, event: Event) {
+        self.events.push(event);
+        let since = *self.since.get_or_insert_with(Instant::now);
+        if self.events.len() >= self.options.max_events || since.elapsed() >= self.options.max_delay
+        {
+            self.flush();
+        }
+    }
+
+    fn flush(&mut self) {
+        self.since = None;
+        if !self.events.is_empty() {
+            let batch = std::mem::replace(
+                &mut self.events,
+                Vec::with_capacity(self.options.max_events),
+            );
+            let _ = self.sender.send(batch);
+        }
+    }
+
+    /// How long until the pending batch is due, if there is one.
+    fn due_in(&self) -> Option<Duration> {
+        self.since
+            .map(|since| self.options.max_delay.saturating_sub(since.elapsed()))
+    }
+}
+
+/// Collects a listener's events into batches, delivering what is left when it is dropped
+/// with its subscription.
+pub(crate) struct Batcher(Arc<Mutex<Pending>>);
+
+impl Batcher {
+    /// A batcher and the channel it delivers to. A thread delivers batches due by time while no
+    /// events arrive; it exits once the batcher is dropped, after which the channel disconnects.
+    pub(crate) fn new(options: BatchOptions) -> Result<(Batcher, EventBatches), Error> {
+        let (sender, receiver) = channel();
+        let pending = Arc::new(Mutex::new(Pending {
+            events: Vec::with_capacity(options.max_events),
+            since: None,
+            sender,
+            options,
+        }));
+        let weak = Arc::downgrade(&pending);
+        std::thread::Builder::new()
+            .name("dxfeed-batch".to_string())
+            .spawn(move || flush_due(weak, options.max_delay))?;
+        Ok((Batcher(pending), receiver))
+    }
+
+    /// Add `event` to the pending batch. Events that failed conversion are dropped.
+    pub(crate) fn push(&mut self, event: Result<Event, Error>) {
+        if let Ok(event) = event {
+            self.0.lock().unwrap().push(event);
+        }
+    }
+}
+
+impl Drop for Batcher {
+    fn drop(&mut self) {
+        self.0.lock().unwrap().flush();
+    }
+}
+
+fn flush_due(pending: Weak<Mutex<Pending>>, max_delay: Duration) {
+    let mut wait = max_delay;
+    loop {
+        std::thread::sleep(wait);
+        let Some(pending) = pending.upgrade() else {
+            return;
+        };
+        let mut pending = pending.lock().unwrap();
+        if pending.due_in() == Some(Duration::ZERO) {
+            pending.flush();
+        }
+        // Poll at least every millisecond, however short the delay
+        wait = pending
+            .due_in()
+            .unwrap_or(max_delay)
+            .max(Duration::from_millis(1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BatchOptions, EventType, FeedBackend, FeedSubscription, MockFeed};
+    use crate::{Event, EventData, QuoteData};
+    use std::time::Duration;
+
+    #[test]
+    fn batches_by_size_and_time() {
+        let feed = MockFeed::new();
+        let options = BatchOptions::default()
+            .max_events(2)
+            .max_delay(Duration::from_millis(100));
+        let (subscription, batches) = feed
+            .subscribe_batched(&[EventType::Quote], options)
+            .unwrap();
+        subscription.add_symbols(&["AAPL"]).unwrap();
+        for sequence in 0..5 {
+            let quote = QuoteData {
+                sequence,
+                ..Default::default()
+            };
+            feed.push(Event::new("AAPL".to_string(), EventData::Quote(quote)));
+        }
+        let sequences = |batch: Vec<Event>| -> Vec<i32> {
+            batch
+                .iter()
+                .map(|event| event.data.as_quote().unwrap().sequence)
+                .collect()
+        };
+        assert_eq!(sequences(batches.try_recv().unwrap()), [0, 1]);
+        assert_eq!(sequences(batches.try_recv().unwrap()), [2, 3]);
+        // The last event is delivered once it has waited `max_delay`
+        assert!(batches.try_recv().is_err());
+        let last = batches.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert_eq!(sequences(last), [4]);
+
+        feed.push(Event::new(
+            "AAPL".to_string(),
+            EventData::Quote(QuoteData::default()),
+        ));
+        drop(subscription);
+        assert_eq!(batches.recv().unwrap().len(), 1);
+        assert!(batches.recv().is_err());
+    }
+}
@@ -0,0 +1,247 @@
+use crate::{Event, EventData, EventFlags, OrderEventData, Side};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// The orders for one symbol, keyed by index, maintained from incremental Order events.
+#[derive(Debug, Clone, Default)]
+pub struct OrderBook {
+    orders: BTreeMap<i64, OrderEventData>,
+}
+
+/// Total size resting at one price on one side of an `OrderBook`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BookLevel {
+    pub price: f64,
+    pub size: f64,
+    /// Number of orders at this price
+    pub orders: usize,
+}
+
+/// An order index whose price, size, or side differs between two books.
+#[derive(Debug, Clone)]
+pub struct OrderMismatch {
+    pub index: i64,
+    pub expected: OrderEventData,
+    pub actual: OrderEventData,
+}
+
+/// A price level whose total size differs between two books. A size of 0 means the level is
+/// absent from that book.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LevelMismatch {
+    pub side: Side,
+    pub price: f64,
+    pub expected_size: f64,
+    pub actual_size: f64,
+}
+
+/// The differences found by `OrderBook::diff`, each sorted by index or price.
+#[derive(Debug, Clone, Default)]
+pub struct BookDiff {
+    /// Order indexes only in the reference book
+    pub missing_orders: Vec<i64>,
+    /// Order indexes only in the compared book
+    pub extra_orders: Vec<i64>,
+    pub mismatched_orders: Vec<OrderMismatch>,
+    pub mismatched_levels: Vec<LevelMismatch>,
+}
+
+impl BookDiff {
+    /// True if the books agree on every order and level.
+    pub fn is_empty(&self) -> bool {
+        self.missing_orders.is_empty()
+            && self.extra_orders.is_empty()
+            && self.mismatched_orders.is_empty()
+            && self.mismatched_levels.is_empty()
+    }
+}
+
+impl OrderBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A book holding the Order events among `events`, e.g. from `Snapshot::collect`.
+    pub fn from_events<'a, I: IntoIterator<Item = &'a Event>>(events: I) -> Self {
+        let mut book = Self::new();
+        for event in events {
+            if let EventData::Order(order) = &event.data {
+                book.apply(order);
+            }
+        }
+        book
+    }
+
+    /// Insert, replace, or remove the order at `order.index`. Orders flagged for removal, or
+    /// with no size left, are removed.
+    pub fn apply(&mut self, order: &OrderEventData) {
+        let removed = EventFlags::from(order.event_flags).is_remove_event();
+        if removed || order.size == 0.0 || order.size.is_nan() {
+            self.orders.remove(&order.index);
+        } else {
+            self.orders.insert(order.index, order.clone());
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    pub fn get(&self, index: i64) -> Option<&OrderEventData> {
+        self.orders.get(&index)
+    }
+
+    /// The book's orders, by index.
+    pub fn orders(&self) -> impl Iterator<Item = &OrderEventData> {
+        self.orders.values()
+    }
+
+    /// The price levels on `side`, best first (highest bid, lowest offer).
+    pub fn levels(&self, side: Side) -> Vec<BookLevel> {
+        let mut levels: Vec<BookLevel> = self
+            .level_map(side)
+            .into_iter()
+            .map(|(bits, (size, orders))| BookLevel {
+                price: f64::from_bits(bits),
+                size,
+                orders,
+            })
+            .collect();
+        levels.sort_by(|a, b| a.price.total_cmp(&b.price));
+        if side == Side::Buy {
+            levels.reverse();
+        }
+        levels
+    }
+
+    // Total size and order count by price, keyed by the price's bits
+    fn level_map(&self, side: Side) -> HashMap<u64, (f64, usize)> {
+        let mut levels: HashMap<u64, (f64, usize)> = HashMap::new();
+        for order in self.orders.values().filter(|order| order.side == side) {
+            let level = levels.entry(order.price.to_bits()).or_default();
+            level.0 += order.size;
+            level.1 += 1;
+        }
+        levels
+    }
+
+    /// Compare this book against `reference`, e.g. one freshly built from a snapshot, reporting
+    /// orders and levels that are missing, extra, or differ in price or size.
+    pub fn diff(&self, reference: &OrderBook) -> BookDiff {
+        let mut diff = BookDiff::default();
+        for (index, expected) in &reference.orders {
+            match self.orders.get(index) {
+                None => diff.missing_orders.push(*index),
+                Some(actual)
+                    if actual.price.to_bits() != expected.price.to_bits()
+                        || actual.size != expected.size
+                        || actual.side != expected.side =>
+                {
+                    diff.mismatched_orders.push(OrderMismatch {
+                        index: *index,
+                        expected: expected.clone(),
+                        actual: actual.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        diff.extra_orders = self
+            .orders
+            .keys()
+            .filter(|index| !reference.orders.contains_key(index))
+            .copied()
+            .collect();
+
+        let sides: BTreeSet<Side> = self
+            .orders
+            .values()
+            .chain(reference.orders.values())
+            .map(|order| order.side)
+            .collect();
+        for side in sides {
+            let expected = reference.level_map(side);
+            let actual = self.level_map(side);
+            let prices: BTreeSet<u64> = expected.keys().chain(actual.keys()).copied().collect();
+            for bits in prices {
+                let expected_size = expected.get(&bits).map_or(0.0, |level| level.0);
+                let actual_size = actual.get(&bits).map_or(0.0, |level| level.0);
+                if expected_size != actual_size {
+                    diff.mismatched_levels.push(LevelMismatch {
+                        side,
+                        price: f64::from_bits(bits),
+                        expected_size,
+                        actual_size,
+                    });
+                }
+            }
+        }
+        diff.mismatched_levels
+            .sort_by(|a, b| a.side.cmp(&b.side).then(a.price.total_cmp(&b.price)));
+        diff
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(index: i64, side: Side, price: f64, size: f64) -> OrderEventData {
+        OrderEventData {
+            index,
+            side,
+            price,
+            size,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn diff_against_snapshot() {
+        let (buy, sell) = (Side::Buy, Side::Sell);
+        let mut book = OrderBook::new();
+        book.apply(&order(1, buy, 100.0, 5.0));
+        book.apply(&order(2, buy, 99.0, 3.0));
+        book.apply(&order(3, sell, 101.0, 2.0));
+        book.apply(&order(4, sell, 102.0, 1.0));
+        book.apply(&order(4, sell, 102.0, 0.0));
+        assert_eq!(book.len(), 3);
+        assert_eq!(book.levels(buy)[0].price, 100.0);
+        assert_eq!(book.levels(sell)[0].price, 101.0);
+        assert!(book.diff(&book.clone()).is_empty());
+
+        let snapshot: Vec<Event> = [
+            order(1, buy, 100.0, 5.0),
+            order(2, buy, 99.5, 3.0),
+            order(5, sell, 101.0, 2.0),
+        ]
+        .into_iter()
+        .map(|order| Event::new("AAPL".to_string(), EventData::Order(order)))
+        .collect();
+        let diff = book.diff(&OrderBook::from_events(&snapshot));
+        assert_eq!(diff.missing_orders, vec![5]);
+        assert_eq!(diff.extra_orders, vec![3]);
+        assert_eq!(diff.mismatched_orders.len(), 1);
+        assert_eq!(diff.mismatched_orders[0].index, 2);
+        // The sell level at 101 holds the same size despite the differing order
+        let prices: Vec<f64> = diff.mismatched_levels.iter().map(|l| l.price).collect();
+        assert_eq!(prices, vec![99.0, 99.5]);
+    }
+
+    #[test]
+    fn diff_with_nan_price() {
+        let mut book = OrderBook::new();
+        book.apply(&order(1, Side::Buy, f64::NAN, 5.0));
+        book.apply(&order(2, Side::Buy, 99.0, 3.0));
+        assert!(book.diff(&book.clone()).is_empty());
+
+        let diff = book.diff(&OrderBook::new());
+        assert_eq!(diff.extra_orders.len(), 2);
+        let prices: Vec<f64> = diff.mismatched_levels.iter().map(|l| l.price).collect();
+        assert_eq!(prices[0], 99.0);
+        assert!(prices[1].is_nan());
+    }
+}
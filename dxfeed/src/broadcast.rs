@@ -0,0 +1,140 @@
+//! Delivery into a `tokio::sync::broadcast` channel, enabled by the `tokio` feature, so that
+//! each async task can receive its own copy of the stream.
+
+use crate::{Error, Event, Subscription};
+use std::sync::Arc;
+use tokio::sync::broadcast::{self, error::RecvError, error::TryRecvError};
+
+/// The sending half: every event passed to its listener is delivered to each `EventReceiver`.
+#[derive(Clone)]
+pub struct EventBroadcast {
+    sender: broadcast::Sender<Arc<Event>>,
+}
+
+impl EventBroadcast {
+    /// A channel buffering up to `capacity` events for its slowest receiver.
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        EventBroadcast { sender }
+    }
+
+    /// A receiver of every event sent from now on.
+    pub fn subscribe(&self) -> EventReceiver {
+        EventReceiver {
+            receiver: self.sender.subscribe(),
+            lagged: 0,
+        }
+    }
+
+    /// Number of receivers currently subscribed.
+    pub fn receiver_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// A listener for `Subscription::attach` that sends each event. Conversion errors are
+    /// dropped, as are events sent while there are no receivers.
+    pub fn listener(&self) -> impl FnMut(Result<Event, Error>) + Send + 'static {
+        let sender = self.sender.clone();
+        move |event| {
+            if let Ok(event) = event {
+                let _ = sender.send(Arc::new(event));
+            }
+        }
+    }
+}
+
+/// The receiving half. A receiver that falls more than the channel's capacity behind skips the
+/// events it missed, which are counted by `lagged`.
+pub struct EventReceiver {
+    receiver: broadcast::Receiver<Arc<Event>>,
+    lagged: u64,
+}
+
+impl EventReceiver {
+    /// Wait for the next event, or None once every `EventBroadcast` (and its listeners) has
+    /// been dropped.
+    pub async fn recv(&mut self) -> Option<Arc<Event>> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(event) => return Some(event),
+                Err(RecvError::Lagged(skipped)) => self.lagged += skipped,
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// The next event if one is buffered, without waiting.
+    pub fn try_recv(&mut self) -> Option<Arc<Event>> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(event) => return Some(event),
+                Err(TryRecvError::Lagged(skipped)) => self.lagged += skipped,
+                Err(TryRecvError::Empty) | Err(TryRecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Total events skipped because this receiver fell behind.
+    pub fn lagged(&self) -> u64 {
+        self.lagged
+    }
+}
+
+impl Clone for EventReceiver {
+    /// A receiver starting from the next event sent, with its own lag count.
+    fn clone(&self) -> Self {
+        EventReceiver {
+            receiver: self.receiver.resubscribe(),
+            lagged: 0,
+        }
+    }
+}
+
+impl Subscription {
+    /// Attach a new `EventBroadcast` of `capacity` events as this subscription's listener,
+    /// replacing any previous listener.
+    pub fn attach_broadcast(&mut self, capacity: usize) -> Result<EventBroadcast, Error> {
+        let broadcast = EventBroadcast::new(capacity);
+        self.attach(broadcast.listener())?;
+        Ok(broadcast)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EventData, ProfileEventData};
+
+    fn profile(sym: &str) -> Result<Event, Error> {
+        Ok(Event::new(
+            sym.to_string(),
+            EventData::Profile(ProfileEventData::default()),
+        ))
+    }
+
+    #[test]
+    fn lagging_receiver() {
+        let broadcast = EventBroadcast::new(2);
+        let mut fast = broadcast.subscribe();
+        let mut slow = broadcast.subscribe();
+        let mut listener = broadcast.listener();
+        assert_eq!(broadcast.receiver_count(), 2);
+
+        listener(profile("A"));
+        assert_eq!(fast.try_recv().unwrap().sym, "A");
+        for sym in ["B", "C", "D"] {
+            listener(profile(sym));
+        }
+        // `slow` missed A and B
+        assert_eq!(slow.try_recv().unwrap().sym, "C");
+        assert_eq!(slow.lagged(), 2);
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        assert_eq!(runtime.block_on(fast.recv()).unwrap().sym, "C");
+        drop((broadcast, listener));
+        assert_eq!(runtime.block_on(slow.recv()).unwrap().sym, "D");
+        assert!(runtime.block_on(slow.recv()).is_none());
+    }
+}
@@ -0,0 +1,343 @@
+use crate::connection::ConnectionHandle;
+use crate::health::watch_heartbeats;
+use crate::last_error::call_failed;
+use crate::panics;
+use crate::{
+    dxf_connection_status_t, dxf_connection_t, dxf_create_connection,
+    dxf_create_connection_auth_basic, dxf_create_connection_auth_bearer,
+    dxf_create_connection_auth_custom, Connection, Error, DXF_SUCCESS,
+};
+use std::ffi::CString;
+use std::os::raw::{c_int, c_void};
+use std::sync::{Arc, Mutex, PoisonError};
+
+type Notifier = Mutex<Box<dyn FnMut() + Send>>;
+type StatusNotifier =
+    Mutex<Box<dyn FnMut(dxf_connection_status_t, dxf_connection_status_t) + Send>>;
+
+/// The closures registered with a `ConnectionBuilder`, passed to the C API as `user_data`.
+#[derive(Default)]
+pub(crate) struct Notifiers {
+    on_termination: Option<Notifier>,
+    on_status_change: Option<StatusNotifier>,
+    on_thread_created: Option<Notifier>,
+    on_thread_destroyed: Option<Notifier>,
+}
+
+/// Configures and opens a `Connection`. Notifiers are called on the connection's socket thread;
+/// any context they need can simply be captured.
+///
+/// ```ignore
+/// let connection = Connection::builder("demo.dxfeed.com:7300")
+///     .on_termination(|| eprintln!("connection terminated"))
+///     .on_status_change(|old, new| eprintln!("status {} => {}", old, new))
+///     .build()?;
+/// ```
+pub struct ConnectionBuilder {
+    address: String,
+    auth: Auth,
+    notifiers: Notifiers,
+    #[cfg(feature = "tls")]
+    pub(crate) tls: Option<crate::TlsConfig>,
+}
+
+/// How a connection authenticates, selecting the `dxf_create_connection*` call.
+enum Auth {
+    None,
+    Basic { user: String, password: String },
+    Bearer { token: String },
+    Custom { scheme: String, data: String },
+}
+
+fn c_string(s: &str) -> Result<CString, Error> {
+    CString::new(s).map_err(|_| Error::ContainsNul)
+}
+
+impl ConnectionBuilder {
+    /// A builder for a connection to `address`, e.g. "demo.dxfeed.com:7300".
+    pub fn new(address: &str) -> Self {
+        ConnectionBuilder {
+            address: address.to_string(),
+            auth: Auth::None,
+            notifiers: Notifiers::default(),
+            #[cfg(feature = "tls")]
+            tls: None,
+        }
+    }
+
+    /// Authenticate with HTTP Basic credentials.
+    pub fn basic_auth(mut self, user: &str, password: &str) -> Self {
+        self.auth = Auth::Basic {
+            user: user.to_string(),
+            password: password.to_string(),
+        };
+        self
+    }
+
+    /// Authenticate with a bearer token.
+    pub fn bearer_auth(mut self, token: &str) -> Self {
+        self.auth = Auth::Bearer {
+            token: token.to_string(),
+        };
+        self
+    }
+
+    /// Authenticate with an arbitrary `scheme` (e.g. "Basic", "Bearer") and its encoded `data`.
+    pub fn custom_auth(mut self, scheme: &str, data: &str) -> Self {
+        self.auth = Auth::Custom {
+            scheme: scheme.to_string(),
+            data: data.to_string(),
+        };
+        self
+    }
+
+    /// Called when the connection is terminated, i.e. has failed and won't reconnect.
+    pub fn on_termination<F: FnMut() + Send + 'static>(mut self, notifier: F) -> Self {
+        self.notifiers.on_termination = Some(Mutex::new(Box::new(notifier)));
+        self
+    }
+
+    /// Called with the old and new status whenever the connection status changes.
+    pub fn on_status_change<F>(mut self, notifier: F) -> Self
+    where
+        F: FnMut(dxf_connection_status_t, dxf_connection_status_t) + Send + 'static,
+    {
+        self.notifiers.on_status_change = Some(Mutex::new(Box::new(notifier)));
+        self
+    }
+
+    /// Called on the socket thread once it has started.
+    pub fn on_socket_thread_created<F: FnMut() + Send + 'static>(mut self, notifier: F) -> Self {
+        self.notifiers.on_thread_created = Some(Mutex::new(Box::new(notifier)));
+        self
+    }
+
+    /// Called on the socket thread just before it exits.
+    pub fn on_socket_thread_destroyed<F: FnMut() + Send + 'static>(mut self, notifier: F) -> Self {
+        self.notifiers.on_thread_destroyed = Some(Mutex::new(Box::new(notifier)));
+        self
+    }
+
+    pub fn build(self) -> Result<Connection, Error> {
+        #[cfg(feature = "tls")]
+        let address = match &self.tls {
+            Some(tls) => tls.address(&self.address),
+            None => self.address,
+        };
+        #[cfg(not(feature = "tls"))]
+        let address = self.address;
+        let c_address = c_string(&address)?;
+        let notifiers = Box::new(self.notifiers);
+        let termination = notifiers
+            .on_termination
+            .as_ref()
+            .and(Some(termination_trampoline as _));
+        let status = notifiers
+            .on_status_change
+            .as_ref()
+            .and(Some(status_trampoline as _));
+        let created = notifiers
+            .on_thread_created
+            .as_ref()
+            .and(Some(thread_created_trampoline as _));
+        let destroyed = notifiers
+            .on_thread_destroyed
+            .as_ref()
+            .and(Some(thread_destroyed_trampoline as _));
+        let user_data = &*notifiers as *const Notifiers as *mut c_void;
+        let mut conn: dxf_connection_t = std::ptr::null_mut();
+        let (result, call) = match &self.auth {
+            Auth::None => (
+                unsafe {
+                    dxf_create_connection(
+                        c_address.as_ptr(),
+                        termination,
+                        status,
+                        created,
+                        destroyed,
+                        user_data,
+                        &mut conn,
+                    )
+                },
+                "dxf_create_connection",
+            ),
+            Auth::Basic { user, password } => {
+                let (user, password) = (c_string(user)?, c_string(password)?);
+                let result = unsafe {
+                    dxf_create_connection_auth_basic(
+                        c_address.as_ptr(),
+                        user.as_ptr(),
+                        password.as_ptr(),
+                        termination,
+                        status,
+                        created,
+                        destroyed,
+                        user_data,
+                        &mut conn,
+                    )
+                };
+                (result, "dxf_create_connection_auth_basic")
+            }
+            Auth::Bearer { token } => {
+                let token = c_string(token)?;
+                let result = unsafe {
+                    dxf_create_connection_auth_bearer(
+                        c_address.as_ptr(),
+                        token.as_ptr(),
+                        termination,
+                        status,
+                        created,
+                        destroyed,
+                        user_data,
+                        &mut conn,
+                    )
+                };
+                (result, "dxf_create_connection_auth_bearer")
+            }
+            Auth::Custom { scheme, data } => {
+                let (scheme, data) = (c_string(scheme)?, c_string(data)?);
+                let result = unsafe {
+                    dxf_create_connection_auth_custom(
+                        c_address.as_ptr(),
+                        scheme.as_ptr(),
+                        data.as_ptr(),
+                        termination,
+                        status,
+                        created,
+                        destroyed,
+                        user_data,
+                        &mut conn,
+                    )
+                };
+                (result, "dxf_create_connection_auth_custom")
+            }
+        };
+        if result != DXF_SUCCESS as c_int {
+            return Err(call_failed(call));
+        }
+        let handle = ConnectionHandle {
+            raw: conn,
+            heartbeats: Box::default(),
+            notifiers,
+            subscriptions: Mutex::default(),
+        };
+        unsafe { watch_heartbeats(conn, &handle.heartbeats) }?;
+        Ok(Connection {
+            handle: Arc::new(handle),
+        })
+    }
+}
+
+impl Connection {
+    /// A `ConnectionBuilder` for `address`, to register notifiers or authenticate.
+    pub fn builder(address: &str) -> ConnectionBuilder {
+        ConnectionBuilder::new(address)
+    }
+
+    /// Connect to `address` with HTTP Basic credentials.
+    pub fn with_basic_auth(address: &str, user: &str, password: &str) -> Result<Self, Error> {
+        ConnectionBuilder::new(address)
+            .basic_auth(user, password)
+            .build()
+    }
+
+    /// Connect to `address` with a bearer token.
+    pub fn with_bearer_auth(address: &str, token: &str) -> Result<Self, Error> {
+        ConnectionBuilder::new(address).bearer_auth(token).build()
+    }
+
+    /// Connect to `address` with an arbitrary authorization `scheme` and its `data`.
+    pub fn with_custom_auth(address: &str, scheme: &str, data: &str) -> Result<Self, Error> {
+        ConnectionBuilder::new(address)
+            .custom_auth(scheme, data)
+            .build()
+    }
+}
+
+unsafe fn notifiers<'a>(user_data: *mut c_void) -> &'a Notifiers {
+    &*(user_data as *const Notifiers)
+}
+
+fn call(notifier: &Option<Notifier>) {
+    if let Some(notifier) = notifier {
+        // Poisoned by an earlier panic, which the guard reported
+        (notifier.lock().unwrap_or_else(PoisonError::into_inner))();
+    }
+}
+
+unsafe extern "C" fn termination_trampoline(_connection: dxf_connection_t, user_data: *mut c_void) {
+    panics::guard("termination notifier", (), || {
+        call(&notifiers(user_data).on_termination)
+    })
+}
+
+unsafe extern "C" fn status_trampoline(
+    _connection: dxf_connection_t,
+    old_status: dxf_connection_status_t,
+    new_status: dxf_connection_status_t,
+    user_data: *mut c_void,
+) {
+    panics::guard("status notifier", (), || {
+        if let Some(notifier) = &notifiers(user_data).on_status_change {
+            (notifier.lock().unwrap_or_else(PoisonError::into_inner))(old_status, new_status);
+        }
+    })
+}
+
+unsafe extern "C" fn thread_created_trampoline(
+    _connection: dxf_connection_t,
+    user_data: *mut c_void,
+) -> c_int {
+    panics::guard("socket thread notifier", (), || {
+        call(&notifiers(user_data).on_thread_created)
+    });
+    // Non-zero lets the socket thread proceed
+    1
+}
+
+unsafe extern "C" fn thread_destroyed_trampoline(
+    _connection: dxf_connection_t,
+    user_data: *mut c_void,
+) {
+    panics::guard("socket thread notifier", (), || {
+        call(&notifiers(user_data).on_thread_destroyed)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn trampolines_call_closures() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let (terminated, status) = (calls.clone(), calls.clone());
+        let builder = ConnectionBuilder::new("demo.dxfeed.com:7300")
+            .on_termination(move || {
+                terminated.fetch_add(1, Ordering::SeqCst);
+            })
+            .on_status_change(move |old, new| {
+                status.fetch_add(old * 10 + new, Ordering::SeqCst);
+            });
+        let user_data = &builder.notifiers as *const Notifiers as *mut c_void;
+        unsafe {
+            termination_trampoline(std::ptr::null_mut(), user_data);
+            status_trampoline(std::ptr::null_mut(), 1, 3, user_data);
+            // Unset notifiers are skipped
+            assert_eq!(
+                thread_created_trampoline(std::ptr::null_mut(), user_data),
+                1
+            );
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1 + 13);
+    }
+
+    #[test]
+    fn auth_rejects_nul() {
+        let result = Connection::with_basic_auth("demo.dxfeed.com:7300", "user", "pass\0word");
+        assert!(matches!(result, Err(Error::ContainsNul)));
+        let result = Connection::with_bearer_auth("demo.dxfeed.com:7300", "tok\0en");
+        assert!(matches!(result, Err(Error::ContainsNul)));
+    }
+}
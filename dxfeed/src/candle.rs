@@ -0,0 +1,266 @@
+use crate::Error;
+use std::fmt;
+use std::time::Duration;
+
+/// The unit of a candle's aggregation period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CandlePeriodType {
+    #[default]
+    Tick,
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    /// Monthly option expiration (third Friday)
+    OptionExpiration,
+    Year,
+    Volume,
+    Price,
+    PriceMomentum,
+    PriceRenko,
+}
+
+impl CandlePeriodType {
+    fn as_str(self) -> &'static str {
+        match self {
+            CandlePeriodType::Tick => "t",
+            CandlePeriodType::Second => "s",
+            CandlePeriodType::Minute => "m",
+            CandlePeriodType::Hour => "h",
+            CandlePeriodType::Day => "d",
+            CandlePeriodType::Week => "w",
+            CandlePeriodType::Month => "mo",
+            CandlePeriodType::OptionExpiration => "o",
+            CandlePeriodType::Year => "y",
+            CandlePeriodType::Volume => "v",
+            CandlePeriodType::Price => "p",
+            CandlePeriodType::PriceMomentum => "pm",
+            CandlePeriodType::PriceRenko => "pr",
+        }
+    }
+}
+
+/// The price candles are built from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CandlePrice {
+    #[default]
+    Last,
+    Bid,
+    Ask,
+    Mark,
+    Settlement,
+}
+
+impl CandlePrice {
+    fn as_str(self) -> &'static str {
+        match self {
+            CandlePrice::Last => "last",
+            CandlePrice::Bid => "bid",
+            CandlePrice::Ask => "ask",
+            CandlePrice::Mark => "mark",
+            CandlePrice::Settlement => "s",
+        }
+    }
+}
+
+/// Which trading sessions candles cover.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CandleSession {
+    #[default]
+    Any,
+    /// Regular trading hours only
+    Regular,
+}
+
+/// Where candle periods are aligned from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CandleAlignment {
+    #[default]
+    Midnight,
+    Session,
+}
+
+/// A candle symbol, rendered as the C API expects, e.g. `AAPL&Q{=5m,price=mark}`.
+///
+/// Attributes left at their defaults (1 tick, last price, any session, midnight alignment) are
+/// omitted, and the rest are written in key order, so equal symbols render identically.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CandleSymbol {
+    base: String,
+    exchange: Option<char>,
+    period_value: f64,
+    period_type: CandlePeriodType,
+    price: CandlePrice,
+    session: CandleSession,
+    alignment: CandleAlignment,
+    price_level: Option<f64>,
+}
+
+impl CandleSymbol {
+    /// Candles of `base`, e.g. "AAPL", with default attributes.
+    pub fn new(base: &str) -> Self {
+        CandleSymbol {
+            base: base.to_string(),
+            exchange: None,
+            period_value: 1.0,
+            period_type: CandlePeriodType::default(),
+            price: CandlePrice::default(),
+            session: CandleSession::default(),
+            alignment: CandleAlignment::default(),
+            price_level: None,
+        }
+    }
+
+    /// Only events from the exchange with this code, e.g. 'Q' for NASDAQ.
+    pub fn exchange(mut self, code: char) -> Self {
+        self.exchange = Some(code);
+        self
+    }
+
+    /// Aggregate over `value` units of `period_type`, e.g. `(5.0, CandlePeriodType::Minute)`.
+    pub fn period(mut self, value: f64, period_type: CandlePeriodType) -> Self {
+        self.period_value = value;
+        self.period_type = period_type;
+        self
+    }
+
+    /// Aggregate over `period`, in the largest of weeks, days, hours, minutes and seconds that
+    /// divides it, e.g. 5 minutes => `{=5m}`. It must be a whole, non-zero number of seconds.
+    pub fn time_period(self, period: Duration) -> Result<Self, Error> {
+        const UNITS: [(u64, CandlePeriodType); 5] = [
+            (7 * 24 * 60 * 60, CandlePeriodType::Week),
+            (24 * 60 * 60, CandlePeriodType::Day),
+            (60 * 60, CandlePeriodType::Hour),
+            (60, CandlePeriodType::Minute),
+            (1, CandlePeriodType::Second),
+        ];
+        let secs = period.as_secs();
+        if secs == 0 || period.subsec_nanos() != 0 {
+            return Err(Error::InvalidPeriod(period));
+        }
+        let (unit_secs, period_type) = UNITS
+            .iter()
+            .find(|(unit_secs, _)| secs.is_multiple_of(*unit_secs))
+            .ok_or(Error::InvalidPeriod(period))?;
+        Ok(self.period((secs / unit_secs) as f64, *period_type))
+    }
+
+    pub fn price(mut self, price: CandlePrice) -> Self {
+        self.price = price;
+        self
+    }
+
+    pub fn session(mut self, session: CandleSession) -> Self {
+        self.session = session;
+        self
+    }
+
+    pub fn alignment(mut self, alignment: CandleAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// The price interval for price-based period types.
+    pub fn price_level(mut self, price_level: f64) -> Self {
+        self.price_level = Some(price_level);
+        self
+    }
+}
+
+/// `value` without a trailing ".0" when it is whole.
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < i64::MAX as f64 {
+        format!("{}", value as i64)
+    } else {
+        format!("{}", value)
+    }
+}
+
+impl fmt::Display for CandleSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.base)?;
+        if let Some(code) = self.exchange {
+            write!(f, "&{}", code)?;
+        }
+        // Keyed attributes, in key order
+        let mut attributes = Vec::new();
+        if self.period_value != 1.0 || self.period_type != CandlePeriodType::Tick {
+            let value = if self.period_value == 1.0 {
+                String::new()
+            } else {
+                format_number(self.period_value)
+            };
+            attributes.push(format!("={}{}", value, self.period_type.as_str()));
+        }
+        if self.alignment == CandleAlignment::Session {
+            attributes.push("a=s".to_string());
+        }
+        if let Some(price_level) = self.price_level {
+            attributes.push(format!("pl={}", format_number(price_level)));
+        }
+        if self.price != CandlePrice::Last {
+            attributes.push(format!("price={}", self.price.as_str()));
+        }
+        if self.session == CandleSession::Regular {
+            attributes.push("tho=true".to_string());
+        }
+        if !attributes.is_empty() {
+            write!(f, "{{{}}}", attributes.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_attributes() {
+        assert_eq!(CandleSymbol::new("AAPL").to_string(), "AAPL");
+        assert_eq!(
+            CandleSymbol::new("AAPL")
+                .period(5.0, CandlePeriodType::Minute)
+                .price(CandlePrice::Mark)
+                .to_string(),
+            "AAPL{=5m,price=mark}"
+        );
+        assert_eq!(
+            CandleSymbol::new("AAPL")
+                .exchange('Q')
+                .period(1.0, CandlePeriodType::Day)
+                .session(CandleSession::Regular)
+                .alignment(CandleAlignment::Session)
+                .to_string(),
+            "AAPL&Q{=d,a=s,tho=true}"
+        );
+        assert_eq!(
+            CandleSymbol::new("/ES")
+                .period(0.5, CandlePeriodType::PriceRenko)
+                .price_level(0.25)
+                .to_string(),
+            "/ES{=0.5pr,pl=0.25}"
+        );
+    }
+
+    #[test]
+    fn time_periods() {
+        let period2expected = [
+            (Duration::from_secs(1), "AAPL{=s}"),
+            (Duration::from_secs(90), "AAPL{=90s}"),
+            (Duration::from_secs(5 * 60), "AAPL{=5m}"),
+            (Duration::from_secs(4 * 60 * 60), "AAPL{=4h}"),
+            (Duration::from_secs(24 * 60 * 60), "AAPL{=d}"),
+            (Duration::from_secs(14 * 24 * 60 * 60), "AAPL{=2w}"),
+        ];
+        for (period, expected) in period2expected {
+            let symbol = CandleSymbol::new("AAPL").time_period(period).unwrap();
+            assert_eq!(symbol.to_string(), expected);
+        }
+        assert!(CandleSymbol::new("AAPL")
+            .time_period(Duration::from_millis(500))
+            .is_err());
+    }
+}
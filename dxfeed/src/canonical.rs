@@ -0,0 +1,87 @@
+//! Canonical JSON for events, enabled by the `canonical` feature, so that archives can be
+//! byte-compared and hashed.
+//!
+//! The encoding is `Event`'s serde serialization with:
+//! - object keys sorted, at every level, independent of struct field order;
+//! - no whitespace;
+//! - numbers in serde_json's locale-independent shortest round-trip form, with `-0.0` written as
+//!   `0.0`, and NaN and infinities written as `null` (as serde_json always does).
+
+use crate::{Error, Event};
+use serde_json::{Number, Value};
+use std::io;
+
+/// The canonical encoding of `event`.
+pub fn to_canonical_json(event: &Event) -> Result<Vec<u8>, Error> {
+    let value = serde_json::to_value(event)
+        .map_err(|e| Error::Io(io::Error::new(io::ErrorKind::InvalidData, e)))?;
+    let mut out = Vec::with_capacity(256);
+    write_value(&value, &mut out);
+    Ok(out)
+}
+
+fn write_value(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Null => out.extend_from_slice(b"null"),
+        Value::Bool(b) => out.extend_from_slice(if *b { b"true" } else { b"false" }),
+        Value::Number(n) => write_number(n, out),
+        Value::String(s) => write_string(s, out),
+        Value::Array(items) => {
+            out.push(b'[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                write_value(item, out);
+            }
+            out.push(b']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            out.push(b'{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                write_string(key, out);
+                out.push(b':');
+                write_value(value, out);
+            }
+            out.push(b'}');
+        }
+    }
+}
+
+fn write_number(n: &Number, out: &mut Vec<u8>) {
+    match n.as_f64() {
+        Some(f) if n.is_f64() && f == 0.0 => out.extend_from_slice(b"0.0"),
+        _ => out.extend_from_slice(n.to_string().as_bytes()),
+    }
+}
+
+fn write_string(s: &str, out: &mut Vec<u8>) {
+    // serde_json's escaping is already deterministic
+    out.extend_from_slice(Value::from(s).to_string().as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EventData, QuoteData};
+
+    #[test]
+    fn stable_bytes() {
+        let mut quote = QuoteData::default();
+        quote.bid.price = -0.0;
+        quote.ask.price = 189.52;
+        quote.bid.size = f64::NAN;
+        let event = Event::new("AAPL".to_string(), EventData::Quote(quote));
+        let json = String::from_utf8(to_canonical_json(&event).unwrap()).unwrap();
+        assert!(json.starts_with("{\"data\":{\"Quote\":{"));
+        assert!(json.contains("\"ask\":{\"exchange\":\"\\u0000\",\"price\":189.52"));
+        assert!(json.contains("\"bid\":{\"exchange\":\"\\u0000\",\"price\":0.0,\"size\":null"));
+        assert!(json.ends_with("},\"sym\":\"AAPL\"}"));
+        assert_eq!(to_canonical_json(&event.clone()).unwrap(), json.as_bytes());
+    }
+}
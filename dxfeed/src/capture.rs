@@ -0,0 +1,290 @@
+//! A binary capture format for recording events with the time they were received, enabled by the
+//! `capture` feature.
+//!
+//! ```ignore
+//! let mut writer = CaptureWriter::create("session.dxcap")?;
+//! subscription.attach(move |event| {
+//!     if let Ok(event) = event {
+//!         let _ = writer.write(&event);
+//!     }
+//! })?;
+//!
+//! for (received, event) in CaptureReader::open("session.dxcap")? {
+//!     // ...
+//! }
+//! ```
+//!
+//! A capture is the magic bytes `DXCAP`, a version byte, and then one record per event: the
+//! receive time as little-endian u64 nanoseconds since the Unix epoch, the length of the event as
+//! a little-endian u32, and the event in `Compact`'s bincode encoding. A record cut short, as by
+//! a crash while writing, ends the capture with an `UnexpectedEof` error after the records
+//! before it.
+
+use crate::{Compact, Error, Event};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const MAGIC: &[u8; 5] = b"DXCAP";
+const VERSION: u8 = 1;
+
+/// The longest event encoding a record may hold, far beyond any event's, so a corrupt length
+/// is rejected rather than allocated.
+const MAX_RECORD_LEN: u32 = 16 << 20;
+
+fn truncated(err: io::Error) -> Error {
+    match err.kind() {
+        io::ErrorKind::UnexpectedEof => Error::Io(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "Capture ends mid-record",
+        )),
+        _ => err.into(),
+    }
+}
+
+fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(err: E) -> Error {
+    Error::Io(io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Writes a capture of events.
+pub struct CaptureWriter<W: Write> {
+    writer: W,
+    buffer: Vec<u8>,
+}
+
+impl CaptureWriter<BufWriter<File>> {
+    /// Create, or truncate, the capture file at `path`.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        CaptureWriter::new(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<W: Write> CaptureWriter<W> {
+    /// Write the capture header to `writer`.
+    pub fn new(mut writer: W) -> Result<Self, Error> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        Ok(CaptureWriter {
+            writer,
+            buffer: Vec::new(),
+        })
+    }
+
+    /// Record `event` as received now.
+    pub fn write(&mut self, event: &Event) -> Result<(), Error> {
+        self.write_at(SystemTime::now(), event)
+    }
+
+    /// Record `event` as received at `received`.
+    pub fn write_at(&mut self, received: SystemTime, event: &Event) -> Result<(), Error> {
+        let nanos = received
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos() as u64;
+        self.buffer.clear();
+        bincode::serialize_into(&mut self.buffer, &Compact(event)).map_err(invalid_data)?;
+        let len = u32::try_from(self.buffer.len())
+            .ok()
+            .filter(|len| *len <= MAX_RECORD_LEN)
+            .ok_or_else(|| invalid_data("Event too long for a capture record"))?;
+        self.writer.write_all(&nanos.to_le_bytes())?;
+        self.writer.write_all(&len.to_le_bytes())?;
+        self.writer.write_all(&self.buffer)?;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<(), Error> {
+        Ok(self.writer.flush()?)
+    }
+
+    /// Flush and return the underlying writer.
+    pub fn into_inner(mut self) -> Result<W, Error> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// An event read from a capture, with the time it was received.
+#[derive(Debug, Clone)]
+pub struct CaptureRecord {
+    pub received: SystemTime,
+    pub event: Event,
+}
+
+/// Reads the records of a capture. As an iterator, yields each event with its receive time as
+/// an `Instant`, keeping the spacing between records: the first record is at the time the
+/// reader was created. Iteration ends at the end of the capture, or at an invalid record, after
+/// which `take_error` returns the error.
+pub struct CaptureReader<R: Read> {
+    reader: R,
+    buffer: Vec<u8>,
+    start: Instant,
+    first_received: Option<SystemTime>,
+    error: Option<Error>,
+}
+
+impl CaptureReader<BufReader<File>> {
+    /// Open the capture file at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        CaptureReader::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> CaptureReader<R> {
+    /// Read and check the capture header from `reader`.
+    pub fn new(mut reader: R) -> Result<Self, Error> {
+        let mut header = [0; 6];
+        reader.read_exact(&mut header)?;
+        if &header[..5] != MAGIC {
+            return Err(invalid_data("Not a dxfeed capture"));
+        }
+        if header[5] != VERSION {
+            return Err(invalid_data(format!(
+                "Unsupported capture version {}",
+                header[5]
+            )));
+        }
+        Ok(CaptureReader {
+            reader,
+            buffer: Vec::new(),
+            start: Instant::now(),
+            first_received: None,
+            error: None,
+        })
+    }
+
+    /// The next record, or None at the end of the capture. A record cut short is an
+    /// `UnexpectedEof` error.
+    pub fn next_record(&mut self) -> Result<Option<CaptureRecord>, Error> {
+        let mut prefix = [0; 12];
+        if !self.read_or_end(&mut prefix)? {
+            return Ok(None);
+        }
+        let (nanos, len) = prefix.split_at(8);
+        let nanos = u64::from_le_bytes(nanos.try_into().expect("8 bytes"));
+        let len = u32::from_le_bytes(len.try_into().expect("4 bytes"));
+        if len > MAX_RECORD_LEN {
+            return Err(invalid_data(format!("Capture record of {} bytes", len)));
+        }
+        self.buffer.resize(len as usize, 0);
+        self.reader
+            .read_exact(&mut self.buffer)
+            .map_err(truncated)?;
+        let Compact(event): Compact<Event> =
+            bincode::deserialize(&self.buffer).map_err(invalid_data)?;
+        Ok(Some(CaptureRecord {
+            received: UNIX_EPOCH + Duration::from_nanos(nanos),
+            event,
+        }))
+    }
+
+    /// The error that ended iteration, if any.
+    pub fn take_error(&mut self) -> Option<Error> {
+        self.error.take()
+    }
+
+    /// Fill `buffer`, returning false if the capture ends before any of it.
+    fn read_or_end(&mut self, buffer: &mut [u8]) -> Result<bool, Error> {
+        let mut filled = 0;
+        while filled < buffer.len() {
+            match self.reader.read(&mut buffer[filled..]) {
+                Ok(0) if filled == 0 => return Ok(false),
+                Ok(0) => return Err(truncated(io::ErrorKind::UnexpectedEof.into())),
+                Ok(n) => filled += n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl<R: Read> Iterator for CaptureReader<R> {
+    type Item = (Instant, Event);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.error.is_some() {
+            return None;
+        }
+        match self.next_record() {
+            Ok(Some(record)) => {
+                let first = *self.first_received.get_or_insert(record.received);
+                let offset = record.received.duration_since(first).unwrap_or_default();
+                Some((self.start + offset, record.event))
+            }
+            Ok(None) => None,
+            Err(err) => {
+                self.error = Some(err);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EventData, QuoteData, TradeData};
+
+    #[test]
+    fn capture_round_trips() {
+        let quote = QuoteData {
+            sequence: 7,
+            ..Default::default()
+        };
+        let trade = TradeData {
+            price: 101.5,
+            ..Default::default()
+        };
+        let received = UNIX_EPOCH + Duration::from_millis(1_700_000_000_000);
+        let mut writer = CaptureWriter::new(Vec::new()).unwrap();
+        writer
+            .write_at(
+                received,
+                &Event::new("AAPL".to_string(), EventData::Quote(quote)),
+            )
+            .unwrap();
+        writer
+            .write_at(
+                received + Duration::from_millis(250),
+                &Event::new("MSFT".to_string(), EventData::Trade(trade)),
+            )
+            .unwrap();
+        let mut bytes = writer.into_inner().unwrap();
+
+        let mut reader = CaptureReader::new(bytes.as_slice()).unwrap();
+        let record = reader.next_record().unwrap().unwrap();
+        assert_eq!(record.received, received);
+        assert_eq!(record.event.data.as_quote(), Some(&quote));
+
+        let events: Vec<(Instant, Event)> = CaptureReader::new(bytes.as_slice()).unwrap().collect();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].0 - events[0].0, Duration::from_millis(250));
+        assert_eq!(events[1].1.sym, "MSFT");
+        assert_eq!(events[1].1.data.as_trade(), Some(&trade));
+
+        // A record cut short, in its event or its prefix, ends the capture with an error
+        let first_len = u32::from_le_bytes(bytes[14..18].try_into().unwrap()) as usize;
+        let second = 6 + 12 + first_len;
+        for end in [bytes.len() - 3, second + 6] {
+            let mut reader = CaptureReader::new(&bytes[..end]).unwrap();
+            assert_eq!(reader.by_ref().count(), 1);
+            assert!(matches!(
+                reader.take_error(),
+                Some(Error::Io(err)) if err.kind() == io::ErrorKind::UnexpectedEof
+            ));
+        }
+
+        // A corrupt length is rejected before allocating for it
+        bytes[14..18].copy_from_slice(&u32::MAX.to_le_bytes());
+        let mut reader = CaptureReader::new(bytes.as_slice()).unwrap();
+        assert!(matches!(
+            reader.next_record(),
+            Err(Error::Io(err)) if err.kind() == io::ErrorKind::InvalidData
+        ));
+
+        assert!(CaptureReader::new(&b"DXCAP\x09"[..]).is_err());
+        assert!(CaptureReader::new(&b"nope!\x01"[..]).is_err());
+    }
+}
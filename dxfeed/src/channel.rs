@@ -0,0 +1,21 @@
+//! Delivery into a `crossbeam_channel`, enabled by the `crossbeam` feature.
+
+use crate::{Error, Event, Subscription};
+use crossbeam_channel::{bounded, Receiver};
+
+impl Subscription {
+    /// Deliver this subscription's events to a channel of `capacity` events, replacing any
+    /// attached listener, for consumers that block on (or `select!` over) the receiver.
+    ///
+    /// The listener runs on the connection's socket thread, which must not block, so events
+    /// arriving while the channel is full are dropped, as are events that fail conversion.
+    pub fn channel(&mut self, capacity: usize) -> Result<Receiver<Event>, Error> {
+        let (sender, receiver) = bounded(capacity);
+        self.attach(move |event| {
+            if let Ok(event) = event {
+                let _ = sender.try_send(event);
+            }
+        })?;
+        Ok(receiver)
+    }
+}
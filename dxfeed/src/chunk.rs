@@ -0,0 +1,157 @@
+//! Adding very large symbol lists, like a full option universe, in chunks, with retries and
+//! progress reports. See `FeedSubscription::add_symbols_chunked`.
+
+use crate::{Error, FeedSubscription};
+use std::time::Duration;
+
+/// How `add_symbols_chunked` splits and retries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkOptions {
+    /// Symbols per call
+    pub chunk_size: usize,
+    /// Further attempts at a failed chunk before giving up
+    pub retries: u32,
+    /// Pause before each retry
+    pub retry_delay: Duration,
+}
+
+impl Default for ChunkOptions {
+    fn default() -> Self {
+        ChunkOptions {
+            chunk_size: 1_000,
+            retries: 3,
+            retry_delay: Duration::from_millis(100),
+        }
+    }
+}
+
+/// Reported after each attempt at a chunk.
+#[derive(Debug)]
+pub struct ChunkProgress<'a> {
+    /// Index of the chunk, from 0
+    pub chunk: usize,
+    pub chunks: usize,
+    /// Attempts at this chunk so far, from 1
+    pub attempt: u32,
+    /// Symbols added so far, including this chunk if this attempt succeeded
+    pub added: usize,
+    pub total: usize,
+    /// The error of this attempt, if it failed
+    pub error: Option<&'a Error>,
+}
+
+pub(crate) fn add_in_chunks<S, F>(
+    subscription: &S,
+    symbols: &[&str],
+    options: ChunkOptions,
+    mut progress: F,
+) -> Result<(), Error>
+where
+    S: FeedSubscription + ?Sized,
+    F: FnMut(&ChunkProgress),
+{
+    let chunk_size = options.chunk_size.max(1);
+    let chunks = symbols.len().div_ceil(chunk_size);
+    let mut added = 0;
+    for (chunk, chunk_symbols) in symbols.chunks(chunk_size).enumerate() {
+        let mut attempt = 1;
+        loop {
+            let result = subscription.add_symbols(chunk_symbols);
+            if result.is_ok() {
+                added += chunk_symbols.len();
+            }
+            progress(&ChunkProgress {
+                chunk,
+                chunks,
+                attempt,
+                added,
+                total: symbols.len(),
+                error: result.as_ref().err(),
+            });
+            match result {
+                Ok(()) => break,
+                Err(e) if attempt > options.retries => return Err(e),
+                Err(_) => {
+                    attempt += 1;
+                    std::thread::sleep(options.retry_delay);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// Fails the first `failures` calls.
+    struct Flaky {
+        failures: RefCell<u32>,
+        added: RefCell<Vec<String>>,
+    }
+
+    impl FeedSubscription for Flaky {
+        fn add_symbols(&self, symbols: &[&str]) -> Result<(), Error> {
+            let mut failures = self.failures.borrow_mut();
+            if *failures > 0 {
+                *failures -= 1;
+                return Err(Error::CallFailed("dxf_add_symbols"));
+            }
+            let symbols = symbols.iter().map(|symbol| symbol.to_string());
+            self.added.borrow_mut().extend(symbols);
+            Ok(())
+        }
+
+        fn remove_symbols(&self, _symbols: &[&str]) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn retries_failed_chunks() {
+        let symbols: Vec<String> = (0..25).map(|i| format!("SYM{}", i)).collect();
+        let symbols: Vec<&str> = symbols.iter().map(String::as_str).collect();
+        let options = ChunkOptions {
+            chunk_size: 10,
+            retries: 2,
+            retry_delay: Duration::ZERO,
+        };
+        let flaky = Flaky {
+            failures: RefCell::new(2),
+            added: RefCell::default(),
+        };
+        let mut reports = Vec::new();
+        flaky
+            .add_symbols_chunked(&symbols, options, |progress| {
+                reports.push((
+                    progress.chunk,
+                    progress.attempt,
+                    progress.added,
+                    progress.error.is_some(),
+                ))
+            })
+            .unwrap();
+        assert_eq!(
+            reports,
+            [
+                (0, 1, 0, true),
+                (0, 2, 0, true),
+                (0, 3, 10, false),
+                (1, 1, 20, false),
+                (2, 1, 25, false)
+            ]
+        );
+        assert_eq!(*flaky.added.borrow(), symbols);
+
+        // Out of retries
+        let flaky = Flaky {
+            failures: RefCell::new(3),
+            added: RefCell::default(),
+        };
+        let result = flaky.add_symbols_chunked(&symbols, options, |_| {});
+        assert!(matches!(result, Err(Error::CallFailed(_))));
+        assert!(flaky.added.borrow().is_empty());
+    }
+}
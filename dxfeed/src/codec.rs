@@ -0,0 +1,149 @@
+//! A `tokio_util::codec` for event streams, enabled by the `codec` feature.
+//!
+//! Frames use the same layout as `UnixSocketPublisher` (the `ipc` feature): a little-endian `u32`
+//! byte length followed by that many bytes of `Event`'s JSON serialization. Recorded streams can
+//! be replayed from any `AsyncRead` with `FramedRead::new(reader, EventCodec::new())`, or served
+//! to a socket with `FramedWrite`.
+
+use crate::{to_canonical_json, Error, Event};
+use bytes::{Buf, BufMut, BytesMut};
+use std::io;
+use tokio_util::codec::{Decoder, Encoder};
+
+const LENGTH_SIZE: usize = 4;
+
+/// Frames larger than this are rejected as corrupt, rather than buffered.
+const DEFAULT_MAX_FRAME: usize = 1 << 20;
+
+#[derive(Debug, Clone)]
+pub struct EventCodec {
+    max_frame: usize,
+    canonical: bool,
+}
+
+impl Default for EventCodec {
+    fn default() -> Self {
+        EventCodec {
+            max_frame: DEFAULT_MAX_FRAME,
+            canonical: false,
+        }
+    }
+}
+
+impl EventCodec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reject frames whose payload exceeds `max_frame` bytes (1 MiB by default).
+    pub fn with_max_frame(mut self, max_frame: usize) -> Self {
+        self.max_frame = max_frame;
+        self
+    }
+
+    /// Encode payloads with `to_canonical_json`, so equal events always produce equal frames.
+    /// Decoding accepts either form.
+    pub fn canonical(mut self) -> Self {
+        self.canonical = true;
+        self
+    }
+
+    fn too_large(&self, len: usize) -> Error {
+        Error::Io(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("frame of {} bytes exceeds {}", len, self.max_frame),
+        ))
+    }
+}
+
+impl Decoder for EventCodec {
+    type Item = Event;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Event>, Error> {
+        if src.len() < LENGTH_SIZE {
+            return Ok(None);
+        }
+        let len = u32::from_le_bytes(src[..LENGTH_SIZE].try_into().unwrap()) as usize;
+        if len > self.max_frame {
+            return Err(self.too_large(len));
+        }
+        if src.len() < LENGTH_SIZE + len {
+            src.reserve(LENGTH_SIZE + len - src.len());
+            return Ok(None);
+        }
+        src.advance(LENGTH_SIZE);
+        let frame = src.split_to(len);
+        serde_json::from_slice(&frame)
+            .map(Some)
+            .map_err(|e| Error::Io(io::Error::new(io::ErrorKind::InvalidData, e)))
+    }
+}
+
+impl Encoder<&Event> for EventCodec {
+    type Error = Error;
+
+    fn encode(&mut self, event: &Event, dst: &mut BytesMut) -> Result<(), Error> {
+        let json = if self.canonical {
+            to_canonical_json(event)?
+        } else {
+            serde_json::to_vec(event)
+                .map_err(|e| Error::Io(io::Error::new(io::ErrorKind::InvalidData, e)))?
+        };
+        if json.len() > self.max_frame {
+            return Err(self.too_large(json.len()));
+        }
+        dst.reserve(LENGTH_SIZE + json.len());
+        dst.put_u32_le(json.len() as u32);
+        dst.put_slice(&json);
+        Ok(())
+    }
+}
+
+impl Encoder<Event> for EventCodec {
+    type Error = Error;
+
+    fn encode(&mut self, event: Event, dst: &mut BytesMut) -> Result<(), Error> {
+        self.encode(&event, dst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EventData, ProfileEventData};
+
+    #[test]
+    fn round_trip_partial_frames() {
+        let mut codec = EventCodec::new();
+        let mut encoded = BytesMut::new();
+        for sym in ["AAPL", "MSFT"] {
+            let data = EventData::Profile(ProfileEventData::default());
+            codec
+                .encode(Event::new(sym.to_string(), data), &mut encoded)
+                .unwrap();
+        }
+
+        // Feed the bytes in a few at a time, as a socket might deliver them
+        let mut src = BytesMut::new();
+        let mut decoded = Vec::new();
+        for chunk in encoded.chunks(7) {
+            src.extend_from_slice(chunk);
+            while let Some(event) = codec.decode(&mut src).unwrap() {
+                decoded.push(event.sym);
+            }
+        }
+        assert_eq!(decoded, vec!["AAPL", "MSFT"]);
+        assert!(src.is_empty());
+
+        let mut canonical = EventCodec::new().canonical();
+        let mut frame = BytesMut::new();
+        let event = Event::new("AAPL".to_string(), EventData::Profile(Default::default()));
+        canonical.encode(&event, &mut frame).unwrap();
+        assert_eq!(&frame[LENGTH_SIZE..], to_canonical_json(&event).unwrap());
+        assert_eq!(canonical.decode(&mut frame).unwrap().unwrap().sym, "AAPL");
+
+        let mut small = EventCodec::new().with_max_frame(8);
+        assert!(small.decode(&mut encoded).is_err());
+    }
+}
@@ -0,0 +1,70 @@
+//! The fields of each event type's payload, for the flat and compact encodings.
+
+// Invokes `$callback!` with the columns of each event type, as payload fields in order. A column
+// of a nested field, e.g. `bid.price`, is named with an underscore, `bid_price`. Every field is
+// listed, which the compact encoding relies on to round-trip.
+macro_rules! event_columns {
+    ($callback:ident) => {
+        $callback! {
+            Trade {
+                time, sequence, time_nanos, exchange_code, price, size, tick, change, day_id,
+                day_volume, day_turnover, raw_flags, direction, is_eth, scope,
+            }
+            Quote {
+                time, sequence, time_nanos, bid.price, bid.size, bid.exchange, bid.time, ask.price,
+                ask.size, ask.exchange, ask.time, scope,
+            }
+            Summary {
+                day_id, day_open_price, day_high_price, day_low_price, day_close_price, prev_day_id,
+                prev_day_close_price, prev_day_volume, open_interest, raw_flags, exchange_code,
+                day_close_price_type, prev_day_close_price_type, scope,
+            }
+            Profile {
+                beta, eps, div_freq, exd_div_amount, exd_div_date, high_52_week_price,
+                low_52_week_price, shares, free_float, high_limit_price, low_limit_price,
+                halt_start_time, halt_end_time, raw_flags, description, status_reason,
+                trading_status, ssr,
+            }
+            Order {
+                source, event_flags, index, time, sequence, time_nanos, action, action_time,
+                order_id, aux_order_id, price, size, executed_size, count, trade_id, trade_price,
+                trade_size, exchange_code, side, scope, mm_or_spread,
+            }
+            TimeAndSale {
+                event_flags, index, time, exchange_code, price, size, bid_price, ask_price,
+                exchange_sale_conditions, raw_flags, buyer, seller, side, kind, is_valid_tick,
+                is_eth_trade, trade_through_exempt, is_spread_leg, scope,
+            }
+            Candle {
+                event_flags, index, time, sequence, count, open, high, low, close, volume, vwap,
+                bid_volume, ask_volume, open_interest, imp_volatility,
+            }
+            TradeETH {
+                time, sequence, time_nanos, exchange_code, price, size, change, day_id, day_volume,
+                day_turnover, raw_flags, direction, is_eth, scope,
+            }
+            SpreadOrder {
+                index, time, time_nanos, sequence, action_time, order_id, aux_order_id, price, size,
+                executed_size, count, flags, trade_id, trade_price, trade_size, spread_symbol, action,
+                exchange_code, side, scope,
+            }
+            Greeks {
+                event_flags, index, time, price, volatility, delta, gamma, theta, rho, vega,
+            }
+            TheoPrice {
+                time, price, underlying_price, delta, gamma, dividend, interest,
+            }
+            Underlying {
+                volatility, front_volatility, back_volatility, call_volume, put_volume,
+                option_volume, put_call_ratio,
+            }
+            Series {
+                event_flags, index, time, sequence, expiration, volatility, call_volume, put_volume,
+                option_volume, put_call_ratio, forward_price, dividend, interest,
+            }
+            Configuration {
+                version, object,
+            }
+        }
+    };
+}
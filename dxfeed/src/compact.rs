@@ -0,0 +1,361 @@
+//! A compact serde representation of events for binary formats such as bincode or MessagePack:
+//! each payload is a tuple of its fields in a fixed order, with no field names, enums as their C
+//! values, characters as code points, event flags as bits and missing values as NaN. An
+//! `EventData` is the tuple `(event id, payload)`, where the event id is `EventType::event_id`,
+//! and an `Event` is `(symbol, data)`.
+//!
+//! ```ignore
+//! let bytes = bincode::serialize(&Compact(&event))?;
+//! let Compact(event): Compact<Event> = bincode::deserialize(&bytes)?;
+//! ```
+//!
+//! Fields are in the order of the payload structs, and the encoding changes with them. NaN is
+//! not valid JSON, so this is not for text formats.
+
+use crate::{
+    dxf_direction_t, dxf_order_action_t, dxf_order_scope_t, dxf_order_side_t, dxf_price_type_t,
+    Action, Direction, Event, EventData, EventFlags, EventType, PriceType, Scope, Side,
+};
+use serde::de::{self, DeserializeSeed, SeqAccess, Visitor};
+use serde::ser::SerializeTuple;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::convert::TryFrom;
+use std::fmt;
+
+/// An `Event` or `EventData`, or a reference to one, in the compact representation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Compact<T>(pub T);
+
+/// A payload field, as the value it is encoded as.
+trait Field: Sized {
+    type Repr: Serialize + for<'de> Deserialize<'de>;
+
+    fn to_repr(&self) -> Self::Repr;
+
+    fn from_repr(repr: Self::Repr) -> Result<Self, String>;
+}
+
+macro_rules! plain_fields {
+    ($($ty:ty),*) => {
+        $(
+            impl Field for $ty {
+                type Repr = $ty;
+
+                fn to_repr(&self) -> $ty {
+                    self.clone()
+                }
+
+                fn from_repr(repr: $ty) -> Result<Self, String> {
+                    Ok(repr)
+                }
+            }
+        )*
+    };
+}
+
+plain_fields!(bool, i32, i64, u16, u32, f64, String);
+
+macro_rules! enum_fields {
+    ($($ty:ident($c:ty)),*) => {
+        $(
+            impl Field for $ty {
+                type Repr = $c;
+
+                fn to_repr(&self) -> $c {
+                    (*self).into()
+                }
+
+                fn from_repr(repr: $c) -> Result<Self, String> {
+                    $ty::try_from(repr).map_err(|err| err.to_string())
+                }
+            }
+        )*
+    };
+}
+
+enum_fields!(
+    Action(dxf_order_action_t),
+    Direction(dxf_direction_t),
+    PriceType(dxf_price_type_t),
+    Scope(dxf_order_scope_t),
+    Side(dxf_order_side_t)
+);
+
+impl Field for Option<f64> {
+    type Repr = f64;
+
+    fn to_repr(&self) -> f64 {
+        self.unwrap_or(f64::NAN)
+    }
+
+    fn from_repr(repr: f64) -> Result<Self, String> {
+        Ok((!repr.is_nan()).then_some(repr))
+    }
+}
+
+impl Field for char {
+    type Repr = u32;
+
+    fn to_repr(&self) -> u32 {
+        *self as u32
+    }
+
+    fn from_repr(repr: u32) -> Result<Self, String> {
+        char::from_u32(repr).ok_or_else(|| format!("invalid character {:#x}", repr))
+    }
+}
+
+impl Field for EventFlags {
+    type Repr = u32;
+
+    fn to_repr(&self) -> u32 {
+        self.bits()
+    }
+
+    fn from_repr(repr: u32) -> Result<Self, String> {
+        Ok(EventFlags(repr))
+    }
+}
+
+/// Read the next element of `seq` into `slot`.
+fn next_field<'de, A: SeqAccess<'de>, T: Field>(
+    seq: &mut A,
+    slot: &mut T,
+    index: usize,
+) -> Result<(), A::Error> {
+    let repr = seq
+        .next_element::<T::Repr>()?
+        .ok_or_else(|| de::Error::invalid_length(index, &"a complete payload"))?;
+    *slot = T::from_repr(repr).map_err(de::Error::custom)?;
+    Ok(())
+}
+
+/// The default payload of the variant `wrap` makes, so its type is inferred from the variant.
+fn payload_default<T: Default>(_wrap: fn(T) -> EventData) -> T {
+    T::default()
+}
+
+// Defines `payload_len`, `serialize_payload` and `PayloadVisitor` from `event_columns!`
+macro_rules! compact_payloads {
+    ($(
+        $variant:ident { $($field:ident $(. $sub:ident)?),* $(,)? }
+    )*) => {
+        fn payload_len(event_type: EventType) -> usize {
+            match event_type {
+                $(EventType::$variant => [$(stringify!($field)),*].len(),)*
+            }
+        }
+
+        fn serialize_payload<S: Serializer>(
+            data: &EventData,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            let mut tuple = serializer.serialize_tuple(payload_len(data.into()))?;
+            match data {
+                $(
+                    EventData::$variant(data) => {
+                        $(tuple.serialize_element(&data.$field$(.$sub)?.to_repr())?;)*
+                    }
+                )*
+            }
+            tuple.end()
+        }
+
+        struct PayloadVisitor(EventType);
+
+        impl<'de> Visitor<'de> for PayloadVisitor {
+            type Value = EventData;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a compact {} payload", self.0)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<EventData, A::Error> {
+                let mut indices = 0..;
+                match self.0 {
+                    $(
+                        EventType::$variant => {
+                            let mut data = payload_default(EventData::$variant);
+                            $(
+                                let index = indices.next().unwrap_or_default();
+                                next_field(&mut seq, &mut data.$field$(.$sub)?, index)?;
+                            )*
+                            Ok(EventData::$variant(data))
+                        }
+                    )*
+                }
+            }
+        }
+    };
+}
+
+event_columns!(compact_payloads);
+
+struct PayloadSeed(EventType);
+
+impl<'de> DeserializeSeed<'de> for PayloadSeed {
+    type Value = EventData;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<EventData, D::Error> {
+        deserializer.deserialize_tuple(payload_len(self.0), PayloadVisitor(self.0))
+    }
+}
+
+struct CompactPayload<'a>(&'a EventData);
+
+impl Serialize for CompactPayload<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_payload(self.0, serializer)
+    }
+}
+
+fn serialize_data<S: Serializer>(data: &EventData, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut tuple = serializer.serialize_tuple(2)?;
+    tuple.serialize_element(&(EventType::from(data).event_id() as u8))?;
+    tuple.serialize_element(&CompactPayload(data))?;
+    tuple.end()
+}
+
+fn serialize_event<S: Serializer>(event: &Event, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut tuple = serializer.serialize_tuple(2)?;
+    tuple.serialize_element(&event.sym)?;
+    tuple.serialize_element(&Compact(&event.data))?;
+    tuple.end()
+}
+
+impl Serialize for Compact<EventData> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_data(&self.0, serializer)
+    }
+}
+
+impl Serialize for Compact<&EventData> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_data(self.0, serializer)
+    }
+}
+
+impl Serialize for Compact<Event> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_event(&self.0, serializer)
+    }
+}
+
+impl Serialize for Compact<&Event> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_event(self.0, serializer)
+    }
+}
+
+struct DataVisitor;
+
+impl<'de> Visitor<'de> for DataVisitor {
+    type Value = EventData;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a compact (event id, payload) pair")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<EventData, A::Error> {
+        let event_id: u8 = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let event_type = 1i32
+            .checked_shl(event_id as u32)
+            .and_then(|mask| EventType::try_from(mask).ok())
+            .ok_or_else(|| de::Error::custom(format!("unknown event id {}", event_id)))?;
+        seq.next_element_seed(PayloadSeed(event_type))?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))
+    }
+}
+
+impl<'de> Deserialize<'de> for Compact<EventData> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_tuple(2, DataVisitor).map(Compact)
+    }
+}
+
+struct EventVisitor;
+
+impl<'de> Visitor<'de> for EventVisitor {
+    type Value = Event;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a compact (symbol, data) pair")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Event, A::Error> {
+        let sym: String = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let Compact(data) = seq
+            .next_element::<Compact<EventData>>()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        Ok(Event::new(sym, data))
+    }
+}
+
+impl<'de> Deserialize<'de> for Compact<Event> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_tuple(2, EventVisitor).map(Compact)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CandleData, QuoteData};
+
+    #[test]
+    fn compact_round_trips() {
+        let mut quote = QuoteData {
+            time: 1_700_000_000_123,
+            scope: Scope::Regional,
+            ..Default::default()
+        };
+        quote.bid.exchange = 'Q';
+        let event = Event::new("AAPL".to_string(), EventData::Quote(quote));
+        let json = serde_json::to_value(Compact(&event)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([
+                "AAPL",
+                [
+                    1,
+                    [
+                        1_700_000_000_123i64,
+                        0,
+                        0,
+                        0.0,
+                        0.0,
+                        81,
+                        0,
+                        0.0,
+                        0.0,
+                        0,
+                        0,
+                        1
+                    ]
+                ]
+            ])
+        );
+        let Compact(back): Compact<Event> = serde_json::from_value(json).unwrap();
+        assert_eq!(back.sym, "AAPL");
+        assert_eq!(back.data.as_quote(), Some(&quote));
+
+        let candle = CandleData {
+            vwap: Some(10.5),
+            bid_volume: Some(1.0),
+            ask_volume: Some(2.0),
+            open_interest: Some(3.0),
+            imp_volatility: Some(0.2),
+            ..Default::default()
+        };
+        let text = serde_json::to_string(&Compact(EventData::Candle(candle))).unwrap();
+        let Compact(back): Compact<EventData> = serde_json::from_str(&text).unwrap();
+        assert_eq!(back.as_candle(), Some(&candle));
+
+        assert!(serde_json::from_str::<Compact<EventData>>("[1, [0]]").is_err());
+        assert!(serde_json::from_str::<Compact<EventData>>("[40, []]").is_err());
+    }
+}
@@ -0,0 +1,126 @@
+//! Conflation of events per symbol, for dashboards and slow consumers that want the current
+//! state at each poll rather than every tick in between.
+//!
+//! ```ignore
+//! let conflator = Conflator::new();
+//! let types = [EventType::Quote, EventType::Trade];
+//! let subscription = connection.subscribe(&types, conflator.listener())?;
+//! subscription.add_symbols(&["AAPL", "MSFT"])?;
+//! loop {
+//!     for event in conflator.poll() {
+//!         dashboard.update(&event);
+//!     }
+//!     std::thread::sleep(Duration::from_millis(250));
+//! }
+//! ```
+
+use crate::{Error, Event, EventType};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+#[derive(Default)]
+struct Latest {
+    /// In the order each symbol and type first arrived since the last poll
+    events: Vec<Event>,
+    /// Index into `events` by type and symbol
+    index: HashMap<EventType, HashMap<String, usize>>,
+    superseded: u64,
+}
+
+/// Keeps the latest event per symbol and event type until the consumer polls. Meant for types
+/// where the latest event is the whole state, like Quote, Trade, or Summary; not Order or
+/// TimeAndSale, where each event matters. Clones share the same events.
+#[derive(Clone, Default)]
+pub struct Conflator {
+    latest: Arc<Mutex<Latest>>,
+}
+
+impl Conflator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep `event`, replacing any event of its symbol and type not yet polled.
+    pub fn push(&self, event: Event) {
+        let mut latest = self.latest.lock().unwrap();
+        let latest = &mut *latest;
+        let symbols = latest.index.entry(EventType::from(&event)).or_default();
+        match symbols.get(&event.sym) {
+            Some(&i) => {
+                latest.events[i] = event;
+                latest.superseded += 1;
+            }
+            None => {
+                symbols.insert(event.sym.clone(), latest.events.len());
+                latest.events.push(event);
+            }
+        }
+    }
+
+    /// A listener keeping events in this conflator, dropping those that failed conversion.
+    pub fn listener(&self) -> impl FnMut(Result<Event, Error>) + Send + 'static {
+        let conflator = self.clone();
+        move |event| {
+            if let Ok(event) = event {
+                conflator.push(event);
+            }
+        }
+    }
+
+    /// The latest event of each symbol and type since the last poll, in the order they first
+    /// arrived.
+    pub fn poll(&self) -> Vec<Event> {
+        let mut latest = self.latest.lock().unwrap();
+        latest.index.values_mut().for_each(HashMap::clear);
+        std::mem::take(&mut latest.events)
+    }
+
+    /// Number of events waiting for the next poll.
+    pub fn pending(&self) -> usize {
+        self.latest.lock().unwrap().events.len()
+    }
+
+    /// Total events replaced by a later one before being polled.
+    pub fn superseded(&self) -> u64 {
+        self.latest.lock().unwrap().superseded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{testing, EventData, TradeData};
+
+    #[test]
+    fn keeps_latest_per_symbol_and_type() {
+        let conflator = Conflator::new();
+        let mut listener = conflator.listener();
+        listener(Ok(testing::quote().sequence(1).event("AAPL")));
+        listener(Ok(testing::quote().sequence(2).event("MSFT")));
+        listener(Ok(testing::quote().sequence(3).event("AAPL")));
+        listener(Err(Error::Unknown));
+        let trade = Event::new("AAPL".to_string(), EventData::Trade(TradeData::default()));
+        conflator.push(trade);
+        assert_eq!(conflator.pending(), 3);
+        assert_eq!(conflator.superseded(), 1);
+
+        let events = conflator.poll();
+        let polled: Vec<_> = events
+            .iter()
+            .map(|event| (event.sym.as_str(), EventType::from(event)))
+            .collect();
+        assert_eq!(
+            polled,
+            [
+                ("AAPL", EventType::Quote),
+                ("MSFT", EventType::Quote),
+                ("AAPL", EventType::Trade)
+            ]
+        );
+        assert_eq!(events[0].data.as_quote().unwrap().sequence, 3);
+        assert!(conflator.poll().is_empty());
+
+        conflator.push(testing::quote().sequence(4).event("MSFT"));
+        assert_eq!(conflator.poll()[0].data.as_quote().unwrap().sequence, 4);
+    }
+}
@@ -0,0 +1,191 @@
+use crate::builder::{ConnectionBuilder, Notifiers};
+use crate::guard::{CStringGuard, PropertiesGuard};
+use crate::health::Heartbeats;
+use crate::last_error::call_failed;
+use crate::{
+    dxf_close_connection, dxf_connection_t, dxf_get_connection_properties_snapshot,
+    dxf_get_current_connected_address, dxf_subscription_t, Error, Event, EventData, EventType,
+    EventTypeMask, ProfileEventData, Snapshot, Subscription, SummaryData, DXF_SUCCESS,
+};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::os::raw::c_int;
+use std::sync::mpsc::{channel, sync_channel, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Owns a `dxf_connection_t` and closes it when the last reference is dropped. The heartbeats
+/// and notifiers passed to the C API as `user_data` are freed only after the connection is
+/// closed.
+pub(crate) struct ConnectionHandle {
+    pub(crate) raw: dxf_connection_t,
+    pub(crate) heartbeats: Box<Heartbeats>,
+    // Only read by the C API, through the trampolines' `user_data`
+    #[allow(dead_code)]
+    pub(crate) notifiers: Box<Notifiers>,
+    /// Open subscriptions and their event type masks, maintained by `Subscription`.
+    pub(crate) subscriptions: Mutex<Vec<(dxf_subscription_t, c_int)>>,
+}
+
+impl fmt::Debug for ConnectionHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ConnectionHandle").field(&self.raw).finish()
+    }
+}
+
+impl Drop for ConnectionHandle {
+    fn drop(&mut self) {
+        if !self.raw.is_null() {
+            unsafe { dxf_close_connection(self.raw) };
+        }
+    }
+}
+
+// The C API synchronizes access to connection handles internally.
+unsafe impl Send for ConnectionHandle {}
+unsafe impl Sync for ConnectionHandle {}
+
+/// The latest Summary and Profile received for a symbol.
+#[derive(Debug, Clone, Default)]
+pub struct SummaryProfile {
+    pub summary: Option<SummaryData>,
+    pub profile: Option<ProfileEventData>,
+}
+
+impl SummaryProfile {
+    fn is_complete(&self) -> bool {
+        self.summary.is_some() && self.profile.is_some()
+    }
+}
+
+/// A safe wrapper around `dxf_connection_t`. Subscriptions created from a `Connection` keep it
+/// alive, so the underlying connection is closed once it and all of its subscriptions are
+/// dropped.
+#[derive(Debug, Clone)]
+pub struct Connection {
+    pub(crate) handle: Arc<ConnectionHandle>,
+}
+
+impl Connection {
+    /// Connect to `address`, e.g. "demo.dxfeed.com:7300". See `Connection::builder` to register
+    /// notifiers.
+    pub fn new(address: &str) -> Result<Self, Error> {
+        ConnectionBuilder::new(address).build()
+    }
+
+    /// The raw handle, for calling into `libdxfeed_sys` directly.
+    pub fn as_raw(&self) -> dxf_connection_t {
+        self.handle.raw
+    }
+
+    /// The connection's properties, e.g. its address and credentials, as reported by the C API.
+    pub fn properties(&self) -> Result<HashMap<String, String>, Error> {
+        let mut properties = PropertiesGuard::default();
+        let result = unsafe {
+            dxf_get_connection_properties_snapshot(
+                self.as_raw(),
+                &mut properties.items,
+                &mut properties.count,
+            )
+        };
+        if result != DXF_SUCCESS as c_int {
+            return Err(call_failed("dxf_get_connection_properties_snapshot"));
+        }
+        Ok(properties.to_map())
+    }
+
+    /// The address currently connected to, or None while not connected.
+    pub fn connected_address(&self) -> Result<Option<String>, Error> {
+        let mut address = CStringGuard::default();
+        let result = unsafe { dxf_get_current_connected_address(self.as_raw(), &mut address.0) };
+        if result != DXF_SUCCESS as c_int {
+            return Err(call_failed("dxf_get_current_connected_address"));
+        }
+        Ok(address.to_string_lossy())
+    }
+
+    /// Create a `Snapshot` of `event_type` for `symbol`. See `Snapshot::new`.
+    pub fn snapshot(
+        &self,
+        event_type: EventType,
+        symbol: &str,
+        source: Option<&str>,
+        time: i64,
+    ) -> Result<Snapshot, Error> {
+        let mut snapshot =
+            unsafe { Snapshot::new(self.as_raw(), event_type, symbol, source, time) }?;
+        snapshot.connection = Some(self.handle.clone());
+        Ok(snapshot)
+    }
+
+    /// Subscribe to `symbol`, wait up to `timeout` for the first `event_type` event, then
+    /// unsubscribe and return it.
+    pub fn get_once(
+        &self,
+        symbol: &str,
+        event_type: EventType,
+        timeout: Duration,
+    ) -> Result<Event, Error> {
+        let (sender, receiver) = sync_channel(1);
+        let mut sub = Subscription::new(self, event_type)?;
+        sub.attach(move |event| {
+            let _ = sender.try_send(event);
+        })?;
+        sub.add_symbol(symbol)?;
+        let received = receiver.recv_timeout(timeout);
+        drop(sub);
+        match received {
+            Ok(event) => event,
+            Err(RecvTimeoutError::Timeout) => Err(Error::Timeout),
+            Err(RecvTimeoutError::Disconnected) => Err(Error::Unknown),
+        }
+    }
+
+    /// Fetch the current Summary and Profile for each of `symbols`, waiting up to `timeout` for
+    /// both to arrive. Symbols still missing data when the timeout expires map to a partially
+    /// filled (or no) entry rather than failing the whole batch.
+    pub fn fetch_summaries(
+        &self,
+        symbols: &[&str],
+        timeout: Duration,
+    ) -> Result<HashMap<String, SummaryProfile>, Error> {
+        let deadline = Instant::now() + timeout;
+        let (sender, receiver) = channel();
+        let mut sub = Subscription::new(self, EventTypeMask::SUMMARY | EventTypeMask::PROFILE)?;
+        sub.attach(move |event| {
+            let _ = sender.send(event);
+        })?;
+        // Counted once each, however often repeated
+        let mut unique: HashSet<&str> = HashSet::new();
+        let symbols: Vec<&str> = symbols
+            .iter()
+            .copied()
+            .filter(|symbol| unique.insert(symbol))
+            .collect();
+        if !symbols.is_empty() {
+            sub.add_symbols(&symbols)?;
+        }
+        let mut sym2data: HashMap<String, SummaryProfile> = HashMap::new();
+        let mut complete = 0;
+        while complete < symbols.len() {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let event = match receiver.recv_timeout(remaining) {
+                Ok(Ok(event)) => event,
+                Ok(Err(_)) => continue,
+                Err(_) => break,
+            };
+            let requested = unique.contains(event.sym.as_str());
+            let entry = sym2data.entry(event.sym).or_default();
+            let was_complete = entry.is_complete();
+            match event.data {
+                EventData::Summary(summary) => entry.summary = Some(summary),
+                EventData::Profile(profile) => entry.profile = Some(profile),
+                _ => {}
+            }
+            if !was_complete && entry.is_complete() && requested {
+                complete += 1;
+            }
+        }
+        Ok(sym2data)
+    }
+}
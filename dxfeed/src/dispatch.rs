@@ -0,0 +1,121 @@
+use crate::{Connection, Error, Event, EventType, EventTypeMask, Subscription};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+type Handler = Box<dyn FnMut(&Event) + Send>;
+
+/// Counts of events a `DispatchMap` could not deliver.
+#[derive(Debug, Clone, Default)]
+pub struct DispatchStats {
+    /// Events of a type with no registered handler
+    pub unhandled: HashMap<EventType, u64>,
+    /// Events that failed conversion from their C representation
+    pub errors: u64,
+    /// Of `errors`, those due to invalid strings under `UtfStrategy::Strict`. See also
+    /// `utf_error_count` for invalid strings converted under any strategy.
+    pub utf_errors: u64,
+}
+
+/// Routes events to a handler registered for their `EventType`.
+#[derive(Default)]
+pub struct DispatchMap {
+    handlers: HashMap<EventType, Handler>,
+    stats: Arc<Mutex<DispatchStats>>,
+}
+
+impl DispatchMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `handler` for `event_type`, replacing any existing handler for it.
+    pub fn on<F>(mut self, event_type: EventType, handler: F) -> Self
+    where
+        F: FnMut(&Event) + Send + 'static,
+    {
+        self.handlers.insert(event_type, Box::new(handler));
+        self
+    }
+
+    /// Shared counters, updated as events are dispatched.
+    pub fn stats(&self) -> Arc<Mutex<DispatchStats>> {
+        self.stats.clone()
+    }
+
+    pub fn dispatch(&mut self, event: Result<Event, Error>) {
+        let event = match event {
+            Ok(event) => event,
+            Err(e) => {
+                let mut stats = self.stats.lock().unwrap();
+                stats.errors += 1;
+                if matches!(e, Error::UtfError(_)) {
+                    stats.utf_errors += 1;
+                }
+                return;
+            }
+        };
+        let event_type = EventType::from(&event);
+        match self.handlers.get_mut(&event_type) {
+            Some(handler) => handler(&event),
+            None => {
+                *self
+                    .stats
+                    .lock()
+                    .unwrap()
+                    .unhandled
+                    .entry(event_type)
+                    .or_default() += 1
+            }
+        }
+    }
+}
+
+impl Connection {
+    /// Subscribe to every event type for `symbols`, routing events through `dispatch`. Keep the
+    /// returned subscription alive for as long as events should be delivered.
+    pub fn subscribe_all(
+        &self,
+        symbols: &[&str],
+        mut dispatch: DispatchMap,
+    ) -> Result<Subscription, Error> {
+        let mut sub = Subscription::new(self, EventTypeMask::all())?;
+        sub.attach(move |event| dispatch.dispatch(event))?;
+        if !symbols.is_empty() {
+            sub.add_symbols(symbols)?;
+        }
+        Ok(sub)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConfigurationData, EventData, ProfileEventData};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn dispatch_counts_unhandled() {
+        let handled = Arc::new(AtomicUsize::new(0));
+        let handled_clone = handled.clone();
+        let mut dispatch = DispatchMap::new().on(EventType::Profile, move |_| {
+            handled_clone.fetch_add(1, Ordering::SeqCst);
+        });
+        let stats = dispatch.stats();
+
+        let profile = EventData::Profile(ProfileEventData::default());
+        let config = EventData::Configuration(ConfigurationData {
+            version: 0,
+            object: String::new(),
+        });
+        dispatch.dispatch(Ok(Event::new("AAPL".to_string(), profile)));
+        dispatch.dispatch(Ok(Event::new("AAPL".to_string(), config.clone())));
+        dispatch.dispatch(Ok(Event::new("MSFT".to_string(), config)));
+        dispatch.dispatch(Err(Error::Unknown));
+
+        assert_eq!(handled.load(Ordering::SeqCst), 1);
+        let stats = stats.lock().unwrap();
+        assert_eq!(stats.unhandled.get(&EventType::Configuration), Some(&2));
+        assert_eq!(stats.errors, 1);
+        assert_eq!(stats.utf_errors, 0);
+    }
+}
@@ -0,0 +1,800 @@
+//! A pure-Rust client for dxFeed's dxLink WebSocket/JSON protocol, producing the same `Event`
+//! model as the C API without linking it. Enabled by the `dxlink` feature.
+//!
+//! Each `DxLinkFeed` is a dxLink FEED channel. A single I/O thread owns the socket: it drains
+//! queued commands, reads with a short timeout, and sends keepalives.
+//!
+//! Supported event types are Trade, TradeETH, Quote, Summary, Profile, Order, TimeAndSale,
+//! Candle, Greeks, TheoPrice, Underlying and Series.
+
+use crate::{
+    dxf_candle_t, dxf_char_t, dxf_greeks_t, dxf_series_t, dxf_theo_price_t, dxf_trade_t,
+    dxf_underlying_t, Action, CandleData, Direction, Error, Event, EventData, EventType,
+    GreeksData, OrderEventData, PriceType, ProfileEventData, QuoteData, QuoteSide, Scope,
+    SeriesData, Side, SummaryData, TheoPriceData, TimeAndSaleData, TnsFlags, TnsType, TradeData,
+    TradeEthData, TradeFlags, UnderlyingData,
+};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::net::TcpStream;
+use std::os::raw::c_uint;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+
+type Listener = Box<dyn FnMut(Result<Event, Error>) + Send>;
+
+const KEEPALIVE_TIMEOUT_SECS: u64 = 60;
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(KEEPALIVE_TIMEOUT_SECS / 2);
+const READ_TIMEOUT: Duration = Duration::from_millis(50);
+
+enum Command {
+    OpenFeed {
+        channel: u64,
+        event_types: Vec<EventType>,
+        listener: Listener,
+    },
+    Send {
+        channel: u64,
+        message: Value,
+    },
+    CloseFeed(u64),
+    Shutdown,
+}
+
+/// A dxLink WebSocket connection, e.g. to "wss://demo.dxfeed.com/dxlink-ws".
+pub struct DxLinkConnection {
+    commands: Sender<Command>,
+    next_channel: AtomicU64,
+    io_thread: Option<JoinHandle<()>>,
+}
+
+impl DxLinkConnection {
+    /// Connect to `url`, authorizing with `token` if the endpoint requires it.
+    pub fn connect(url: &str, token: Option<&str>) -> Result<Self, Error> {
+        let (socket, _response) =
+            tungstenite::connect(url).map_err(|e| Error::DxLink(e.to_string()))?;
+        set_read_timeout(&socket, READ_TIMEOUT)?;
+        let (commands, receiver) = channel();
+        let mut io = IoLoop {
+            socket,
+            commands: receiver,
+            feeds: HashMap::new(),
+            queued: vec![],
+            authorized: false,
+            last_sent: Instant::now(),
+        };
+        io.send(&json!({
+            "type": "SETUP",
+            "channel": 0,
+            "version": concat!("0.1-dxfeed-rust/", env!("CARGO_PKG_VERSION")),
+            "keepaliveTimeout": KEEPALIVE_TIMEOUT_SECS,
+            "acceptKeepaliveTimeout": KEEPALIVE_TIMEOUT_SECS,
+        }))?;
+        if let Some(token) = token {
+            io.send(&json!({"type": "AUTH", "channel": 0, "token": token}))?;
+        }
+        let io_thread = std::thread::Builder::new()
+            .name("dxlink-io".to_string())
+            .spawn(move || io.run())
+            .map_err(|e| Error::DxLink(e.to_string()))?;
+        Ok(DxLinkConnection {
+            commands,
+            next_channel: AtomicU64::new(1),
+            io_thread: Some(io_thread),
+        })
+    }
+
+    /// Open a FEED channel for `event_types`, delivering events to `listener` on the I/O thread.
+    pub fn open_feed<F>(&self, event_types: &[EventType], listener: F) -> Result<DxLinkFeed, Error>
+    where
+        F: FnMut(Result<Event, Error>) + Send + 'static,
+    {
+        if let Some(unsupported) = event_types.iter().find(|t| event_fields(**t).is_none()) {
+            return Err(Error::DxLink(format!("{} is not supported", unsupported)));
+        }
+        let channel = self.next_channel.fetch_add(1, Ordering::Relaxed);
+        self.command(Command::OpenFeed {
+            channel,
+            event_types: event_types.to_vec(),
+            listener: Box::new(listener),
+        })?;
+        Ok(DxLinkFeed {
+            channel,
+            event_types: event_types.to_vec(),
+            commands: self.commands.clone(),
+        })
+    }
+
+    fn command(&self, command: Command) -> Result<(), Error> {
+        self.commands
+            .send(command)
+            .map_err(|_| Error::DxLink("connection closed".to_string()))
+    }
+}
+
+impl Drop for DxLinkConnection {
+    fn drop(&mut self) {
+        let _ = self.commands.send(Command::Shutdown);
+        if let Some(io_thread) = self.io_thread.take() {
+            let _ = io_thread.join();
+        }
+    }
+}
+
+/// A dxLink FEED channel. The channel is cancelled when dropped.
+pub struct DxLinkFeed {
+    channel: u64,
+    event_types: Vec<EventType>,
+    commands: Sender<Command>,
+}
+
+impl DxLinkFeed {
+    pub fn add_symbols(&self, symbols: &[&str]) -> Result<(), Error> {
+        self.subscription("add", symbols)
+    }
+
+    pub fn remove_symbols(&self, symbols: &[&str]) -> Result<(), Error> {
+        self.subscription("remove", symbols)
+    }
+
+    fn subscription(&self, action: &str, symbols: &[&str]) -> Result<(), Error> {
+        let entries: Vec<Value> = symbols
+            .iter()
+            .flat_map(|symbol| {
+                self.event_types.iter().map(
+                    move |event_type| json!({"type": event_type.to_string(), "symbol": symbol}),
+                )
+            })
+            .collect();
+        let message = json!({
+            "type": "FEED_SUBSCRIPTION",
+            "channel": self.channel,
+            action: entries,
+        });
+        self.commands
+            .send(Command::Send {
+                channel: self.channel,
+                message,
+            })
+            .map_err(|_| Error::DxLink("connection closed".to_string()))
+    }
+}
+
+impl Drop for DxLinkFeed {
+    fn drop(&mut self) {
+        let _ = self.commands.send(Command::CloseFeed(self.channel));
+    }
+}
+
+struct Feed {
+    listener: Listener,
+    opened: bool,
+    pending: Vec<Value>,
+}
+
+struct IoLoop {
+    socket: WebSocket<MaybeTlsStream<TcpStream>>,
+    commands: Receiver<Command>,
+    feeds: HashMap<u64, Feed>,
+    // Channel requests held back until the server reports AUTHORIZED
+    queued: Vec<Value>,
+    authorized: bool,
+    last_sent: Instant,
+}
+
+impl IoLoop {
+    fn run(mut self) {
+        loop {
+            match self.commands.try_recv() {
+                Ok(Command::Shutdown) | Err(TryRecvError::Disconnected) => break,
+                Ok(command) => {
+                    if let Err(e) = self.handle_command(command) {
+                        self.fail(e);
+                        break;
+                    }
+                    continue;
+                }
+                Err(TryRecvError::Empty) => {}
+            }
+            if self.last_sent.elapsed() >= KEEPALIVE_INTERVAL {
+                if let Err(e) = self.send(&json!({"type": "KEEPALIVE", "channel": 0})) {
+                    self.fail(e);
+                    break;
+                }
+            }
+            match self.socket.read() {
+                Ok(Message::Text(text)) => self.handle_message(&text),
+                Ok(_) => {}
+                Err(tungstenite::Error::Io(e))
+                    if matches!(
+                        e.kind(),
+                        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                    ) => {}
+                Err(e) => {
+                    self.fail(Error::DxLink(e.to_string()));
+                    break;
+                }
+            }
+        }
+        let _ = self.socket.close(None);
+    }
+
+    /// Deliver `error`, which ends the connection, to every feed.
+    fn fail(&mut self, error: Error) {
+        let message = match error {
+            Error::DxLink(message) => message,
+            error => error.to_string(),
+        };
+        for feed in self.feeds.values_mut() {
+            (feed.listener)(Err(Error::DxLink(message.clone())));
+        }
+    }
+
+    fn send(&mut self, message: &Value) -> Result<(), Error> {
+        self.socket
+            .send(Message::Text(message.to_string()))
+            .map_err(|e| Error::DxLink(e.to_string()))?;
+        self.last_sent = Instant::now();
+        Ok(())
+    }
+
+    fn handle_command(&mut self, command: Command) -> Result<(), Error> {
+        match command {
+            Command::OpenFeed {
+                channel,
+                event_types,
+                listener,
+            } => {
+                let fields: Map<String, Value> = event_types
+                    .iter()
+                    .filter_map(|t| event_fields(*t).map(|fields| (t.to_string(), json!(fields))))
+                    .collect();
+                self.feeds.insert(
+                    channel,
+                    Feed {
+                        listener,
+                        opened: false,
+                        pending: vec![json!({
+                            "type": "FEED_SETUP",
+                            "channel": channel,
+                            "acceptAggregationPeriod": 0,
+                            "acceptDataFormat": "FULL",
+                            "acceptEventFields": fields,
+                        })],
+                    },
+                );
+                let request = json!({
+                    "type": "CHANNEL_REQUEST",
+                    "channel": channel,
+                    "service": "FEED",
+                    "parameters": {"contract": "AUTO"},
+                });
+                if self.authorized {
+                    self.send(&request)?;
+                } else {
+                    self.queued.push(request);
+                }
+            }
+            Command::Send { channel, message } => match self.feeds.get_mut(&channel) {
+                Some(feed) if !feed.opened => feed.pending.push(message),
+                Some(_) => self.send(&message)?,
+                None => {}
+            },
+            Command::CloseFeed(channel) => {
+                let queued = self.queued.len();
+                self.queued.retain(|request| request["channel"] != channel);
+                // A feed whose request was still queued was never opened
+                if self.feeds.remove(&channel).is_some() && self.queued.len() == queued {
+                    self.send(&json!({"type": "CHANNEL_CANCEL", "channel": channel}))?;
+                }
+            }
+            Command::Shutdown => {}
+        }
+        Ok(())
+    }
+
+    fn handle_message(&mut self, text: &str) {
+        let message: Value = match serde_json::from_str(text) {
+            Ok(message) => message,
+            Err(_) => return,
+        };
+        let channel = message["channel"].as_u64().unwrap_or(0);
+        match message["type"].as_str() {
+            Some("AUTH_STATE") if message["state"] == "AUTHORIZED" => {
+                self.authorized = true;
+                for request in std::mem::take(&mut self.queued) {
+                    let _ = self.send(&request);
+                }
+            }
+            Some("CHANNEL_OPENED") => {
+                let pending = match self.feeds.get_mut(&channel) {
+                    Some(feed) => {
+                        feed.opened = true;
+                        std::mem::take(&mut feed.pending)
+                    }
+                    None => return,
+                };
+                for message in pending {
+                    let _ = self.send(&message);
+                }
+            }
+            Some("FEED_DATA") => {
+                if let (Some(feed), Some(data)) =
+                    (self.feeds.get_mut(&channel), message["data"].as_array())
+                {
+                    for event in data {
+                        (feed.listener)(parse_event(event));
+                    }
+                }
+            }
+            Some("ERROR") => {
+                let error = Error::DxLink(format!("{}: {}", message["error"], message["message"]));
+                match self.feeds.get_mut(&channel) {
+                    Some(feed) => (feed.listener)(Err(error)),
+                    None => {
+                        for feed in self.feeds.values_mut() {
+                            (feed.listener)(Err(Error::DxLink(error.to_string())));
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn set_read_timeout(
+    socket: &WebSocket<MaybeTlsStream<TcpStream>>,
+    timeout: Duration,
+) -> Result<(), Error> {
+    let result = match socket.get_ref() {
+        MaybeTlsStream::Plain(stream) => stream.set_read_timeout(Some(timeout)),
+        MaybeTlsStream::Rustls(stream) => stream.get_ref().set_read_timeout(Some(timeout)),
+        _ => Ok(()),
+    };
+    result.map_err(|e| Error::DxLink(e.to_string()))
+}
+
+/// dxLink field names requested for each supported event type.
+#[rustfmt::skip]
+fn event_fields(event_type: EventType) -> Option<&'static [&'static str]> {
+    let fields: &'static [&'static str] = match event_type {
+        EventType::Trade | EventType::TradeETH => &[
+            "eventType", "eventSymbol", "time", "sequence", "timeNanoPart", "exchangeCode",
+            "price", "size", "change", "dayId", "dayVolume", "dayTurnover", "tickDirection",
+            "extendedTradingHours",
+        ],
+        EventType::Quote => &[
+            "eventType", "eventSymbol", "sequence", "timeNanoPart", "bidTime", "bidExchangeCode",
+            "bidPrice", "bidSize", "askTime", "askExchangeCode", "askPrice", "askSize",
+        ],
+        EventType::Summary => &[
+            "eventType", "eventSymbol", "dayId", "dayOpenPrice", "dayHighPrice", "dayLowPrice",
+            "dayClosePrice", "dayClosePriceType", "prevDayId", "prevDayClosePrice",
+            "prevDayClosePriceType", "prevDayVolume", "openInterest",
+        ],
+        EventType::Profile => &[
+            "eventType", "eventSymbol", "description", "statusReason", "tradingStatus",
+            "shortSaleRestriction", "haltStartTime", "haltEndTime", "highLimitPrice",
+            "lowLimitPrice", "high52WeekPrice", "low52WeekPrice", "beta", "earningsPerShare",
+            "dividendFrequency", "exDividendAmount", "exDividendDayId", "shares", "freeFloat",
+        ],
+        EventType::Order => &[
+            "eventType", "eventSymbol", "eventFlags", "index", "time", "sequence", "timeNanoPart",
+            "action", "actionTime", "orderId", "auxOrderId", "price", "size", "executedSize",
+            "count", "tradeId", "tradePrice", "tradeSize", "exchangeCode", "orderSide", "scope",
+            "marketMaker", "source",
+        ],
+        EventType::TimeAndSale => &[
+            "eventType", "eventSymbol", "eventFlags", "index", "time", "exchangeCode", "price",
+            "size", "bidPrice", "askPrice", "exchangeSaleConditions", "buyer", "seller",
+            "aggressorSide", "type", "validTick", "extendedTradingHours", "tradeThroughExempt",
+            "spreadLeg",
+        ],
+        EventType::Candle => &[
+            "eventType", "eventSymbol", "eventFlags", "index", "time", "sequence", "count", "open",
+            "high", "low", "close", "volume", "vwap", "bidVolume", "askVolume", "openInterest",
+            "impVolatility",
+        ],
+        EventType::Greeks => &[
+            "eventType", "eventSymbol", "eventFlags", "index", "time", "price", "volatility",
+            "delta", "gamma", "theta", "rho", "vega",
+        ],
+        EventType::TheoPrice => &[
+            "eventType", "eventSymbol", "time", "price", "underlyingPrice", "delta", "gamma",
+            "dividend", "interest",
+        ],
+        EventType::Underlying => &[
+            "eventType", "eventSymbol", "volatility", "frontVolatility", "backVolatility",
+            "callVolume", "putVolume", "optionVolume", "putCallRatio",
+        ],
+        EventType::Series => &[
+            "eventType", "eventSymbol", "eventFlags", "index", "time", "sequence", "expiration",
+            "volatility", "callVolume", "putVolume", "optionVolume", "putCallRatio",
+            "forwardPrice", "dividend", "interest",
+        ],
+        EventType::SpreadOrder | EventType::Configuration => return None,
+    };
+    Some(fields)
+}
+
+const DIRECTIONS: [&str; 6] = ["UNDEFINED", "DOWN", "ZERO_DOWN", "ZERO", "ZERO_UP", "UP"];
+const SIDES: [&str; 3] = ["UNDEFINED", "BUY", "SELL"];
+const SCOPES: [&str; 4] = ["COMPOSITE", "REGIONAL", "AGGREGATE", "ORDER"];
+const ACTIONS: [&str; 9] = [
+    "UNDEFINED",
+    "NEW",
+    "REPLACE",
+    "MODIFY",
+    "DELETE",
+    "PARTIAL",
+    "EXECUTE",
+    "TRADE",
+    "BUST",
+];
+const TNS_TYPES: [&str; 3] = ["NEW", "CORRECTION", "CANCEL"];
+const PRICE_TYPES: [&str; 4] = ["REGULAR", "INDICATIVE", "PRELIMINARY", "FINAL"];
+const TRADING_STATUSES: [&str; 3] = ["UNDEFINED", "HALTED", "ACTIVE"];
+const SHORT_SALE_RESTRICTIONS: [&str; 3] = ["UNDEFINED", "ACTIVE", "INACTIVE"];
+
+/// Typed access to the fields of a FULL-format event object. Missing values default to NaN,
+/// zero, or empty.
+struct Fields<'a>(&'a Map<String, Value>);
+
+impl Fields<'_> {
+    fn f64(&self, name: &str) -> f64 {
+        match self.0.get(name) {
+            Some(Value::Number(n)) => n.as_f64().unwrap_or(f64::NAN),
+            Some(Value::String(s)) => s.parse().unwrap_or(f64::NAN),
+            _ => f64::NAN,
+        }
+    }
+
+    fn i64(&self, name: &str) -> i64 {
+        self.0.get(name).and_then(Value::as_i64).unwrap_or(0)
+    }
+
+    fn i32(&self, name: &str) -> i32 {
+        self.i64(name) as i32
+    }
+
+    fn bool(&self, name: &str) -> bool {
+        self.0.get(name).and_then(Value::as_bool).unwrap_or(false)
+    }
+
+    fn string(&self, name: &str) -> String {
+        self.0
+            .get(name)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    fn char(&self, name: &str) -> char {
+        self.string(name).chars().next().unwrap_or('\0')
+    }
+
+    /// Index of the field's value in `names`, i.e. the value of the matching C enum.
+    fn enumeration(&self, name: &str, names: &[&str]) -> c_uint {
+        let value = self.string(name);
+        names.iter().position(|n| *n == value).unwrap_or(0) as c_uint
+    }
+}
+
+/// Convert a FULL-format FEED_DATA event object into an `Event`.
+pub(crate) fn parse_event(value: &Value) -> Result<Event, Error> {
+    let object = value
+        .as_object()
+        .ok_or_else(|| Error::DxLink(format!("Unexpected event: {}", value)))?;
+    let f = Fields(object);
+    let event_type = EventType::from_str(&f.string("eventType"))
+        .map_err(|_| Error::DxLink(format!("Unknown eventType: {}", object["eventType"])))?;
+    let data = match event_type {
+        EventType::Trade | EventType::TradeETH => {
+            let direction = f.enumeration("tickDirection", &DIRECTIONS);
+            let is_eth = f.bool("extendedTradingHours");
+            let flags = TradeFlags {
+                direction: Direction::try_from(direction).unwrap_or_default(),
+                is_eth,
+            };
+            let trade = dxf_trade_t {
+                time: f.i64("time"),
+                sequence: f.i32("sequence"),
+                time_nanos: f.i32("timeNanoPart"),
+                exchange_code: f.char("exchangeCode") as dxf_char_t,
+                price: f.f64("price"),
+                size: f.f64("size"),
+                tick: 0,
+                change: f.f64("change"),
+                day_id: f.i32("dayId"),
+                day_volume: f.f64("dayVolume"),
+                day_turnover: f.f64("dayTurnover"),
+                raw_flags: flags.to_raw(),
+                direction,
+                is_eth: is_eth as _,
+                scope: 0,
+            };
+            match event_type {
+                EventType::Trade => EventData::Trade(TradeData::from(&trade)),
+                _ => EventData::TradeETH(TradeEthData::from(&trade)),
+            }
+        }
+        EventType::Quote => {
+            let bid_time = f.i64("bidTime");
+            let ask_time = f.i64("askTime");
+            EventData::Quote(QuoteData {
+                time: bid_time.max(ask_time),
+                sequence: f.i32("sequence"),
+                time_nanos: f.i32("timeNanoPart"),
+                bid: QuoteSide {
+                    price: f.f64("bidPrice"),
+                    size: f.f64("bidSize"),
+                    exchange: f.char("bidExchangeCode"),
+                    time: bid_time,
+                },
+                ask: QuoteSide {
+                    price: f.f64("askPrice"),
+                    size: f.f64("askSize"),
+                    exchange: f.char("askExchangeCode"),
+                    time: ask_time,
+                },
+                scope: Scope::Composite,
+            })
+        }
+        EventType::Summary => EventData::Summary(SummaryData {
+            day_id: f.i32("dayId"),
+            day_open_price: f.f64("dayOpenPrice"),
+            day_high_price: f.f64("dayHighPrice"),
+            day_low_price: f.f64("dayLowPrice"),
+            day_close_price: f.f64("dayClosePrice"),
+            prev_day_id: f.i32("prevDayId"),
+            prev_day_close_price: f.f64("prevDayClosePrice"),
+            prev_day_volume: f.f64("prevDayVolume"),
+            open_interest: f.f64("openInterest"),
+            raw_flags: 0,
+            exchange_code: '\0',
+            day_close_price_type: PriceType::try_from(
+                f.enumeration("dayClosePriceType", &PRICE_TYPES),
+            )
+            .unwrap_or_default(),
+            prev_day_close_price_type: PriceType::try_from(
+                f.enumeration("prevDayClosePriceType", &PRICE_TYPES),
+            )
+            .unwrap_or_default(),
+            scope: Scope::Composite,
+        }),
+        EventType::Profile => {
+            let trading_status = f.enumeration("tradingStatus", &TRADING_STATUSES);
+            let ssr = f.enumeration("shortSaleRestriction", &SHORT_SALE_RESTRICTIONS);
+            EventData::Profile(ProfileEventData {
+                beta: f.f64("beta"),
+                eps: f.f64("earningsPerShare"),
+                div_freq: f.f64("dividendFrequency"),
+                exd_div_amount: f.f64("exDividendAmount"),
+                exd_div_date: f.i32("exDividendDayId"),
+                high_52_week_price: f.f64("high52WeekPrice"),
+                low_52_week_price: f.f64("low52WeekPrice"),
+                shares: f.f64("shares"),
+                free_float: f.f64("freeFloat"),
+                high_limit_price: f.f64("highLimitPrice"),
+                low_limit_price: f.f64("lowLimitPrice"),
+                halt_start_time: f.i64("haltStartTime"),
+                halt_end_time: f.i64("haltEndTime"),
+                // Bits 0-1 the trading status, bits 2-3 the short sale restriction
+                raw_flags: (trading_status & 0x3 | (ssr & 0x3) << 2) as i32,
+                description: f.string("description"),
+                status_reason: f.string("statusReason"),
+                trading_status,
+                ssr,
+            })
+        }
+        EventType::Order => EventData::Order(OrderEventData {
+            source: f.string("source"),
+            event_flags: f.i64("eventFlags") as _,
+            index: f.i64("index"),
+            time: f.i64("time"),
+            sequence: f.i32("sequence"),
+            time_nanos: f.i32("timeNanoPart"),
+            action: Action::try_from(f.enumeration("action", &ACTIONS)).unwrap_or_default(),
+            action_time: f.i64("actionTime"),
+            order_id: f.i64("orderId"),
+            aux_order_id: f.i64("auxOrderId"),
+            price: f.f64("price"),
+            size: f.f64("size"),
+            executed_size: f.f64("executedSize"),
+            count: f.f64("count"),
+            trade_id: f.i64("tradeId"),
+            trade_price: f.f64("tradePrice"),
+            trade_size: f.f64("tradeSize"),
+            exchange_code: f.char("exchangeCode"),
+            side: Side::try_from(f.enumeration("orderSide", &SIDES)).unwrap_or_default(),
+            scope: Scope::try_from(f.enumeration("scope", &SCOPES)).unwrap_or_default(),
+            mm_or_spread: f.string("marketMaker"),
+        }),
+        EventType::TimeAndSale => {
+            let kind = f.enumeration("type", &TNS_TYPES);
+            let flags = TnsFlags {
+                kind: TnsType::try_from(kind).unwrap_or_default(),
+                is_valid_tick: f.bool("validTick"),
+                is_eth_trade: f.bool("extendedTradingHours"),
+                is_spread_leg: f.bool("spreadLeg"),
+                side: Side::try_from(f.enumeration("aggressorSide", &SIDES)).unwrap_or_default(),
+                trade_through_exempt: f.char("tradeThroughExempt"),
+            };
+            EventData::TimeAndSale(TimeAndSaleData {
+                event_flags: f.i64("eventFlags") as _,
+                index: f.i64("index"),
+                time: f.i64("time"),
+                exchange_code: f.char("exchangeCode"),
+                price: f.f64("price"),
+                size: f.f64("size"),
+                bid_price: f.f64("bidPrice"),
+                ask_price: f.f64("askPrice"),
+                exchange_sale_conditions: f.string("exchangeSaleConditions"),
+                raw_flags: flags.to_raw(),
+                buyer: f.string("buyer"),
+                seller: f.string("seller"),
+                side: flags.side,
+                kind,
+                is_valid_tick: flags.is_valid_tick,
+                is_eth_trade: flags.is_eth_trade,
+                trade_through_exempt: flags.trade_through_exempt as dxf_char_t,
+                is_spread_leg: flags.is_spread_leg,
+                scope: Scope::Composite,
+            })
+        }
+        EventType::Candle => EventData::Candle(CandleData::from(&dxf_candle_t {
+            event_flags: f.i64("eventFlags") as _,
+            index: f.i64("index"),
+            time: f.i64("time"),
+            sequence: f.i32("sequence"),
+            count: f.f64("count"),
+            open: f.f64("open"),
+            high: f.f64("high"),
+            low: f.f64("low"),
+            close: f.f64("close"),
+            volume: f.f64("volume"),
+            vwap: f.f64("vwap"),
+            bid_volume: f.f64("bidVolume"),
+            ask_volume: f.f64("askVolume"),
+            open_interest: f.f64("openInterest"),
+            imp_volatility: f.f64("impVolatility"),
+        })),
+        EventType::Greeks => EventData::Greeks(GreeksData::from(&dxf_greeks_t {
+            event_flags: f.i64("eventFlags") as _,
+            index: f.i64("index"),
+            time: f.i64("time"),
+            price: f.f64("price"),
+            volatility: f.f64("volatility"),
+            delta: f.f64("delta"),
+            gamma: f.f64("gamma"),
+            theta: f.f64("theta"),
+            rho: f.f64("rho"),
+            vega: f.f64("vega"),
+        })),
+        EventType::TheoPrice => EventData::TheoPrice(TheoPriceData::from(&dxf_theo_price_t {
+            time: f.i64("time"),
+            price: f.f64("price"),
+            underlying_price: f.f64("underlyingPrice"),
+            delta: f.f64("delta"),
+            gamma: f.f64("gamma"),
+            dividend: f.f64("dividend"),
+            interest: f.f64("interest"),
+        })),
+        EventType::Underlying => EventData::Underlying(UnderlyingData::from(&dxf_underlying_t {
+            volatility: f.f64("volatility"),
+            front_volatility: f.f64("frontVolatility"),
+            back_volatility: f.f64("backVolatility"),
+            call_volume: f.f64("callVolume"),
+            put_volume: f.f64("putVolume"),
+            option_volume: f.f64("optionVolume"),
+            put_call_ratio: f.f64("putCallRatio"),
+        })),
+        EventType::Series => EventData::Series(SeriesData::from(&dxf_series_t {
+            event_flags: f.i64("eventFlags") as _,
+            index: f.i64("index"),
+            time: f.i64("time"),
+            sequence: f.i32("sequence"),
+            expiration: f.i32("expiration"),
+            volatility: f.f64("volatility"),
+            call_volume: f.f64("callVolume"),
+            put_volume: f.f64("putVolume"),
+            option_volume: f.f64("optionVolume"),
+            put_call_ratio: f.f64("putCallRatio"),
+            forward_price: f.f64("forwardPrice"),
+            dividend: f.f64("dividend"),
+            interest: f.f64("interest"),
+        })),
+        EventType::SpreadOrder | EventType::Configuration => {
+            return Err(Error::DxLink(format!("{} is not supported", event_type)))
+        }
+    };
+    Ok(Event::new(f.string("eventSymbol"), data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_full_quote() {
+        let value: Value = serde_json::from_str(
+            r#"{"eventType":"Quote","eventSymbol":"AAPL","sequence":0,"timeNanoPart":0,
+                "bidTime":1700000000000,"bidExchangeCode":"Q","bidPrice":189.5,"bidSize":300,
+                "askTime":1700000000500,"askExchangeCode":"P","askPrice":"NaN","askSize":100}"#,
+        )
+        .unwrap();
+        let event = parse_event(&value).unwrap();
+        assert_eq!(event.sym, "AAPL");
+        match event.data {
+            EventData::Quote(quote) => {
+                assert_eq!(quote.time, 1700000000500);
+                assert_eq!(quote.bid.exchange, 'Q');
+                assert_eq!(quote.bid.price, 189.5);
+                assert!(quote.ask.price.is_nan());
+                assert_eq!(quote.mid(), None);
+            }
+            other => panic!("Unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_full_time_and_sale() {
+        let value: Value = serde_json::from_str(
+            r#"{"eventType":"TimeAndSale","eventSymbol":"AAPL","eventFlags":0,"index":42,
+                "time":1700000000000,"exchangeCode":"D","price":189.5,"size":10,
+                "aggressorSide":"SELL","type":"CORRECTION","validTick":true,"buyer":"X"}"#,
+        )
+        .unwrap();
+        match parse_event(&value).unwrap().data {
+            EventData::TimeAndSale(tns) => {
+                assert_eq!(tns.index, 42);
+                assert_eq!(tns.side, Side::Sell);
+                assert_eq!(tns.kind, 1);
+                assert!(tns.is_valid_tick);
+                assert_eq!(tns.buyer, "X");
+                assert_eq!(tns.seller, "");
+                assert_eq!(tns.flags().side, Side::Sell);
+                assert_eq!(tns.flags().kind, TnsType::Correction);
+                assert!(tns.flags().is_valid_tick);
+            }
+            other => panic!("Unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn unsupported_event_type() {
+        let value: Value =
+            serde_json::from_str(r#"{"eventType":"Configuration","eventSymbol":"AAPL"}"#).unwrap();
+        assert!(parse_event(&value).is_err());
+        assert!(event_fields(EventType::Configuration).is_none());
+    }
+
+    #[test]
+    fn parse_full_trade_flags() {
+        let value: Value = serde_json::from_str(
+            r#"{"eventType":"Trade","eventSymbol":"AAPL","price":189.5,"size":10,
+                "tickDirection":"ZERO_UP","extendedTradingHours":true}"#,
+        )
+        .unwrap();
+        match parse_event(&value).unwrap().data {
+            EventData::Trade(trade) => {
+                assert_eq!(trade.direction, Direction::ZeroUp);
+                assert!(trade.is_eth);
+                assert_eq!(
+                    trade.flags(),
+                    TradeFlags {
+                        direction: Direction::ZeroUp,
+                        is_eth: true
+                    }
+                );
+            }
+            other => panic!("Unexpected {:?}", other),
+        }
+    }
+}
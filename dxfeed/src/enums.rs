@@ -0,0 +1,452 @@
+use crate::{
+    dxf_direction_t, dxf_direction_t_dxf_dir_down, dxf_direction_t_dxf_dir_undefined,
+    dxf_direction_t_dxf_dir_up, dxf_direction_t_dxf_dir_zero, dxf_direction_t_dxf_dir_zero_down,
+    dxf_direction_t_dxf_dir_zero_up, dxf_order_action_t, dxf_order_action_t_dxf_oa_bust,
+    dxf_order_action_t_dxf_oa_delete, dxf_order_action_t_dxf_oa_execute,
+    dxf_order_action_t_dxf_oa_modify, dxf_order_action_t_dxf_oa_new,
+    dxf_order_action_t_dxf_oa_partial, dxf_order_action_t_dxf_oa_replace,
+    dxf_order_action_t_dxf_oa_trade, dxf_order_action_t_dxf_oa_undefined, dxf_order_scope_t,
+    dxf_order_scope_t_dxf_osc_aggregate, dxf_order_scope_t_dxf_osc_composite,
+    dxf_order_scope_t_dxf_osc_order, dxf_order_scope_t_dxf_osc_regional, dxf_order_side_t,
+    dxf_order_side_t_dxf_osd_buy, dxf_order_side_t_dxf_osd_sell,
+    dxf_order_side_t_dxf_osd_undefined, dxf_price_type_t, dxf_price_type_t_dxf_pt_final,
+    dxf_price_type_t_dxf_pt_indicative, dxf_price_type_t_dxf_pt_preliminary,
+    dxf_price_type_t_dxf_pt_regular, dxf_short_sale_restriction_dxf_ssr_active,
+    dxf_short_sale_restriction_dxf_ssr_inactive, dxf_short_sale_restriction_dxf_ssr_undefined,
+    dxf_short_sale_restriction_t, dxf_tns_type_dxf_tnst_cancel, dxf_tns_type_dxf_tnst_correction,
+    dxf_tns_type_dxf_tnst_new, dxf_tns_type_t, dxf_trading_status_dxf_ts_active,
+    dxf_trading_status_dxf_ts_halted, dxf_trading_status_dxf_ts_undefined, dxf_trading_status_t,
+    Error, ProfileEventData, TimeAndSaleData, TradeData, TradeEthData,
+};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+// Defines a Rust enum mirroring a C enum, convertible to its value and fallibly from one. The
+// first variant is the default, used where the C API hands over an out-of-range value.
+macro_rules! c_enum {
+    ($(#[$meta:meta])* $name:ident($c:ty, $what:literal) { $($(#[$vmeta:meta])* $variant:ident = $value:ident,)* }) => {
+        $(#[$meta])*
+        #[derive(
+            Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+        )]
+        #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+        pub enum $name {
+            #[default]
+            $($(#[$vmeta])* $variant,)*
+        }
+
+        impl TryFrom<$c> for $name {
+            type Error = Error;
+
+            fn try_from(value: $c) -> Result<Self, Error> {
+                match value {
+                    $(value if value == $value => Ok($name::$variant),)*
+                    _ => Err(Error::InvalidValue($what, value as _)),
+                }
+            }
+        }
+
+        impl From<$name> for $c {
+            fn from(value: $name) -> $c {
+                match value {
+                    $($name::$variant => $value,)*
+                }
+            }
+        }
+    };
+}
+
+c_enum! {
+    /// The side of an order, or the aggressor side of a trade.
+    Side(dxf_order_side_t, "order side") {
+        Undefined = dxf_order_side_t_dxf_osd_undefined,
+        Buy = dxf_order_side_t_dxf_osd_buy,
+        Sell = dxf_order_side_t_dxf_osd_sell,
+    }
+}
+
+c_enum! {
+    /// What an order or time and sale event covers.
+    Scope(dxf_order_scope_t, "order scope") {
+        /// The best across all exchanges
+        Composite = dxf_order_scope_t_dxf_osc_composite,
+        /// The best of one exchange
+        Regional = dxf_order_scope_t_dxf_osc_regional,
+        /// A price level of one exchange
+        Aggregate = dxf_order_scope_t_dxf_osc_aggregate,
+        /// An individual order
+        Order = dxf_order_scope_t_dxf_osc_order,
+    }
+}
+
+c_enum! {
+    /// The full order book action an order event represents.
+    Action(dxf_order_action_t, "order action") {
+        Undefined = dxf_order_action_t_dxf_oa_undefined,
+        New = dxf_order_action_t_dxf_oa_new,
+        Replace = dxf_order_action_t_dxf_oa_replace,
+        Modify = dxf_order_action_t_dxf_oa_modify,
+        Delete = dxf_order_action_t_dxf_oa_delete,
+        Partial = dxf_order_action_t_dxf_oa_partial,
+        Execute = dxf_order_action_t_dxf_oa_execute,
+        Trade = dxf_order_action_t_dxf_oa_trade,
+        Bust = dxf_order_action_t_dxf_oa_bust,
+    }
+}
+
+c_enum! {
+    /// Whether an instrument is trading.
+    TradingStatus(dxf_trading_status_t, "trading status") {
+        Undefined = dxf_trading_status_dxf_ts_undefined,
+        Halted = dxf_trading_status_dxf_ts_halted,
+        Active = dxf_trading_status_dxf_ts_active,
+    }
+}
+
+c_enum! {
+    /// Whether short sales of an instrument are restricted.
+    ShortSaleRestriction(dxf_short_sale_restriction_t, "short sale restriction") {
+        Undefined = dxf_short_sale_restriction_dxf_ssr_undefined,
+        Active = dxf_short_sale_restriction_dxf_ssr_active,
+        Inactive = dxf_short_sale_restriction_dxf_ssr_inactive,
+    }
+}
+
+c_enum! {
+    /// How final a day's close price is.
+    PriceType(dxf_price_type_t, "price type") {
+        Regular = dxf_price_type_t_dxf_pt_regular,
+        Indicative = dxf_price_type_t_dxf_pt_indicative,
+        Preliminary = dxf_price_type_t_dxf_pt_preliminary,
+        Final = dxf_price_type_t_dxf_pt_final,
+    }
+}
+
+// Profile raw_flags layout: bits 0-1 the trading status, bits 2-3 the short sale restriction
+const PROFILE_STATUS_SHIFT: u32 = 0;
+const PROFILE_SSR_SHIFT: u32 = 2;
+const PROFILE_FIELD_MASK: i32 = 0x3;
+
+impl ProfileEventData {
+    /// The trading status, decoded from `raw_flags`.
+    pub fn status(&self) -> TradingStatus {
+        TradingStatus::try_from(
+            ((self.raw_flags >> PROFILE_STATUS_SHIFT) & PROFILE_FIELD_MASK) as u32,
+        )
+        .unwrap_or_default()
+    }
+
+    /// The short sale restriction, decoded from `raw_flags`.
+    pub fn short_sale_restriction(&self) -> ShortSaleRestriction {
+        ShortSaleRestriction::try_from(
+            ((self.raw_flags >> PROFILE_SSR_SHIFT) & PROFILE_FIELD_MASK) as u32,
+        )
+        .unwrap_or_default()
+    }
+}
+
+c_enum! {
+    /// Whether a time and sale is a new trade or amends an earlier one.
+    TnsType(dxf_tns_type_t, "time and sale type") {
+        New = dxf_tns_type_dxf_tnst_new,
+        Correction = dxf_tns_type_dxf_tnst_correction,
+        Cancel = dxf_tns_type_dxf_tnst_cancel,
+    }
+}
+
+// TimeAndSale raw_flags layout: bits 0-1 the type, 2 valid tick, 3 extended trading hours,
+// 4 spread leg, 5-6 the aggressor side, 8-15 the TradeThroughExempt character
+const TNS_TYPE_MASK: i32 = 0x3;
+const TNS_VALID_TICK: i32 = 0x4;
+const TNS_ETH: i32 = 0x8;
+const TNS_SPREAD_LEG: i32 = 0x10;
+const TNS_SIDE_SHIFT: u32 = 5;
+const TNS_SIDE_MASK: i32 = 0x3;
+const TNS_TTE_SHIFT: u32 = 8;
+const TNS_TTE_MASK: i32 = 0xff;
+
+/// The fields packed into `TimeAndSaleData::raw_flags`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TnsFlags {
+    pub kind: TnsType,
+    pub is_valid_tick: bool,
+    pub is_eth_trade: bool,
+    pub is_spread_leg: bool,
+    pub side: Side,
+    /// The TradeThroughExempt character, or '\0' if none
+    pub trade_through_exempt: char,
+}
+
+impl TnsFlags {
+    pub fn from_raw(raw_flags: i32) -> Self {
+        TnsFlags {
+            kind: TnsType::try_from((raw_flags & TNS_TYPE_MASK) as u32).unwrap_or_default(),
+            is_valid_tick: raw_flags & TNS_VALID_TICK != 0,
+            is_eth_trade: raw_flags & TNS_ETH != 0,
+            is_spread_leg: raw_flags & TNS_SPREAD_LEG != 0,
+            side: Side::try_from(((raw_flags >> TNS_SIDE_SHIFT) & TNS_SIDE_MASK) as u32)
+                .unwrap_or_default(),
+            trade_through_exempt: ((raw_flags >> TNS_TTE_SHIFT) & TNS_TTE_MASK) as u8 as char,
+        }
+    }
+
+    /// The flags packed back into a `raw_flags` value. A TradeThroughExempt character outside
+    /// Latin-1 is dropped.
+    pub fn to_raw(self) -> i32 {
+        let tte = u8::try_from(self.trade_through_exempt).unwrap_or(0) as i32;
+        let mut raw = dxf_tns_type_t::from(self.kind) as i32 & TNS_TYPE_MASK;
+        raw |= (dxf_order_side_t::from(self.side) as i32 & TNS_SIDE_MASK) << TNS_SIDE_SHIFT;
+        raw |= tte << TNS_TTE_SHIFT;
+        for (set, bit) in [
+            (self.is_valid_tick, TNS_VALID_TICK),
+            (self.is_eth_trade, TNS_ETH),
+            (self.is_spread_leg, TNS_SPREAD_LEG),
+        ] {
+            if set {
+                raw |= bit;
+            }
+        }
+        raw
+    }
+}
+
+impl TimeAndSaleData {
+    /// The fields packed into `raw_flags`, which the C API also unpacks into `kind`, `side`,
+    /// `is_valid_tick`, `is_eth_trade`, `is_spread_leg` and `trade_through_exempt`.
+    pub fn flags(&self) -> TnsFlags {
+        TnsFlags::from_raw(self.raw_flags)
+    }
+}
+
+c_enum! {
+    /// The tick direction of a trade: its price against the last different trade price.
+    Direction(dxf_direction_t, "tick direction") {
+        Undefined = dxf_direction_t_dxf_dir_undefined,
+        Down = dxf_direction_t_dxf_dir_down,
+        /// Unchanged, after a down tick
+        ZeroDown = dxf_direction_t_dxf_dir_zero_down,
+        /// Unchanged, with no earlier different price
+        Zero = dxf_direction_t_dxf_dir_zero,
+        /// Unchanged, after an up tick
+        ZeroUp = dxf_direction_t_dxf_dir_zero_up,
+        Up = dxf_direction_t_dxf_dir_up,
+    }
+}
+
+// Trade raw_flags layout: bit 0 extended trading hours, bits 1-3 the tick direction
+const TRADE_ETH: i32 = 0x1;
+const TRADE_DIRECTION_SHIFT: u32 = 1;
+const TRADE_DIRECTION_MASK: i32 = 0x7;
+
+/// The fields packed into a Trade or TradeETH's `raw_flags`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradeFlags {
+    pub direction: Direction,
+    pub is_eth: bool,
+}
+
+impl TradeFlags {
+    pub fn from_raw(raw_flags: i32) -> Self {
+        TradeFlags {
+            direction: Direction::try_from(
+                ((raw_flags >> TRADE_DIRECTION_SHIFT) & TRADE_DIRECTION_MASK) as u32,
+            )
+            .unwrap_or_default(),
+            is_eth: raw_flags & TRADE_ETH != 0,
+        }
+    }
+
+    pub fn to_raw(self) -> i32 {
+        let mut raw = (dxf_direction_t::from(self.direction) as i32 & TRADE_DIRECTION_MASK)
+            << TRADE_DIRECTION_SHIFT;
+        if self.is_eth {
+            raw |= TRADE_ETH;
+        }
+        raw
+    }
+}
+
+impl TradeData {
+    /// The fields packed into `raw_flags`, which the C API also unpacks into `direction` and
+    /// `is_eth`.
+    pub fn flags(&self) -> TradeFlags {
+        TradeFlags::from_raw(self.raw_flags)
+    }
+}
+
+impl TradeEthData {
+    /// As `TradeData::flags`.
+    pub fn flags(&self) -> TradeFlags {
+        TradeFlags::from_raw(self.raw_flags)
+    }
+}
+
+// Order flags layout: bits 0-1 the scope, 2-3 the side, 4-10 the exchange code, 11-14 the
+// action
+const ORDER_SCOPE_MASK: i32 = 0x3;
+const ORDER_SIDE_SHIFT: u32 = 2;
+const ORDER_SIDE_MASK: i32 = 0x3;
+const ORDER_EXCHANGE_SHIFT: u32 = 4;
+const ORDER_EXCHANGE_MASK: i32 = 0x7f;
+const ORDER_ACTION_SHIFT: u32 = 11;
+const ORDER_ACTION_MASK: i32 = 0xf;
+
+/// The fields packed into an Order or SpreadOrder record's `flags`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderFlags {
+    pub scope: Scope,
+    pub side: Side,
+    /// The exchange code, or '\0' if none
+    pub exchange_code: char,
+    pub action: Action,
+}
+
+impl OrderFlags {
+    pub fn from_raw(flags: i32) -> Self {
+        OrderFlags {
+            scope: Scope::try_from((flags & ORDER_SCOPE_MASK) as u32).unwrap_or_default(),
+            side: Side::try_from(((flags >> ORDER_SIDE_SHIFT) & ORDER_SIDE_MASK) as u32)
+                .unwrap_or_default(),
+            exchange_code: ((flags >> ORDER_EXCHANGE_SHIFT) & ORDER_EXCHANGE_MASK) as u8 as char,
+            action: Action::try_from(((flags >> ORDER_ACTION_SHIFT) & ORDER_ACTION_MASK) as u32)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// The flags packed back into a `flags` value. An exchange code outside ASCII is dropped.
+    pub fn to_raw(self) -> i32 {
+        let exchange = if self.exchange_code.is_ascii() {
+            self.exchange_code as i32
+        } else {
+            0
+        };
+        let mut raw = dxf_order_scope_t::from(self.scope) as i32 & ORDER_SCOPE_MASK;
+        raw |= (dxf_order_side_t::from(self.side) as i32 & ORDER_SIDE_MASK) << ORDER_SIDE_SHIFT;
+        raw |= exchange << ORDER_EXCHANGE_SHIFT;
+        raw |= (dxf_order_action_t::from(self.action) as i32 & ORDER_ACTION_MASK)
+            << ORDER_ACTION_SHIFT;
+        raw
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dxf_char_t;
+
+    #[test]
+    fn conversions() {
+        assert_eq!(
+            Side::try_from(dxf_order_side_t_dxf_osd_sell).unwrap(),
+            Side::Sell
+        );
+        assert_eq!(
+            dxf_order_scope_t::from(Scope::Order),
+            dxf_order_scope_t_dxf_osc_order
+        );
+        assert!(matches!(
+            Action::try_from(99),
+            Err(Error::InvalidValue("order action", 99))
+        ));
+        assert_eq!(
+            serde_json::to_string(&Action::Partial).unwrap(),
+            "\"Partial\""
+        );
+        assert_eq!(serde_json::from_str::<Side>("\"Buy\"").unwrap(), Side::Buy);
+    }
+
+    #[test]
+    fn profile_flags() {
+        let profile = ProfileEventData {
+            // Restricted and halted
+            raw_flags: 0b0101,
+            ..Default::default()
+        };
+        assert_eq!(profile.status(), TradingStatus::Halted);
+        assert_eq!(
+            profile.short_sale_restriction(),
+            ShortSaleRestriction::Active
+        );
+        let profile = ProfileEventData {
+            raw_flags: 0b1010,
+            ..Default::default()
+        };
+        assert_eq!(profile.status(), TradingStatus::Active);
+        assert_eq!(
+            profile.short_sale_restriction(),
+            ShortSaleRestriction::Inactive
+        );
+        assert_eq!(
+            ProfileEventData::default().status(),
+            TradingStatus::Undefined
+        );
+    }
+
+    #[test]
+    fn tns_flags_round_trip() {
+        let flags = TnsFlags {
+            kind: TnsType::Correction,
+            is_valid_tick: true,
+            is_eth_trade: false,
+            is_spread_leg: true,
+            side: Side::Sell,
+            trade_through_exempt: 'X',
+        };
+        assert_eq!(TnsFlags::from_raw(flags.to_raw()), flags);
+        for raw in 0..0x80 {
+            // Type and side 3 are out of range, so decode to the defaults
+            let mut expected = raw;
+            if raw & 0x3 == 0x3 {
+                expected &= !0x3;
+            }
+            if raw & 0x60 == 0x60 {
+                expected &= !0x60;
+            }
+            assert_eq!(TnsFlags::from_raw(raw).to_raw(), expected);
+        }
+
+        // As the C API unpacks them into the redundant fields
+        let raw_flags = flags.to_raw();
+        let tns = TimeAndSaleData {
+            raw_flags,
+            kind: (raw_flags & 0x3) as dxf_tns_type_t,
+            side: Side::try_from(((raw_flags >> 5) & 0x3) as u32).unwrap(),
+            is_valid_tick: raw_flags & 0x4 != 0,
+            is_eth_trade: raw_flags & 0x8 != 0,
+            is_spread_leg: raw_flags & 0x10 != 0,
+            trade_through_exempt: ((raw_flags >> 8) & 0xff) as dxf_char_t,
+            ..Default::default()
+        };
+        let decoded = tns.flags();
+        assert_eq!(dxf_tns_type_t::from(decoded.kind), tns.kind);
+        assert_eq!(decoded.side, tns.side);
+        assert_eq!(decoded.is_valid_tick, tns.is_valid_tick);
+        assert_eq!(decoded.is_eth_trade, tns.is_eth_trade);
+        assert_eq!(decoded.is_spread_leg, tns.is_spread_leg);
+        assert_eq!(
+            decoded.trade_through_exempt as dxf_char_t,
+            tns.trade_through_exempt
+        );
+    }
+
+    #[test]
+    fn trade_flags() {
+        let trade = TradeEthData {
+            // Extended hours, zero-up tick
+            raw_flags: 0b1001,
+            ..Default::default()
+        };
+        assert_eq!(
+            trade.flags(),
+            TradeFlags {
+                direction: Direction::ZeroUp,
+                is_eth: true
+            }
+        );
+        for raw in 0..0x10 {
+            // Directions 6 and 7 are out of range
+            let expected = if raw >> 1 > 5 { raw & 0x1 } else { raw };
+            assert_eq!(TradeFlags::from_raw(raw).to_raw(), expected);
+        }
+    }
+}
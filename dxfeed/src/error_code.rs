@@ -0,0 +1,114 @@
+use std::fmt;
+use std::os::raw::c_int;
+
+/// The class of a C API error, following the groups of `dx_error_code_t` in `DXErrorCodes.h`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DxErrorClass {
+    /// Invalid parameters, subsystem failures and internal assertions
+    Common,
+    Memory,
+    Socket,
+    Thread,
+    Network,
+    BufferedIo,
+    Utf,
+    /// Decoding the wire format
+    Codec,
+    Subscription,
+    Logger,
+    /// Parsing and validating protocol messages, including authentication
+    Protocol,
+    Connection,
+    Candle,
+    Snapshot,
+    Configuration,
+    PriceLevelBook,
+    /// A code this version doesn't know
+    Unknown,
+}
+
+impl fmt::Display for DxErrorClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DxErrorClass::Common => "common",
+            DxErrorClass::Memory => "memory",
+            DxErrorClass::Socket => "socket",
+            DxErrorClass::Thread => "thread",
+            DxErrorClass::Network => "network",
+            DxErrorClass::BufferedIo => "buffered I/O",
+            DxErrorClass::Utf => "UTF",
+            DxErrorClass::Codec => "codec",
+            DxErrorClass::Subscription => "subscription",
+            DxErrorClass::Logger => "logger",
+            DxErrorClass::Protocol => "protocol",
+            DxErrorClass::Connection => "connection",
+            DxErrorClass::Candle => "candle",
+            DxErrorClass::Snapshot => "snapshot",
+            DxErrorClass::Configuration => "configuration",
+            DxErrorClass::PriceLevelBook => "price level book",
+            DxErrorClass::Unknown => "unknown",
+        })
+    }
+}
+
+/// A `dx_error_code_t` value, as reported by `dxf_get_last_error`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DxErrorCode(pub c_int);
+
+impl DxErrorCode {
+    pub const CONNECTION_REFUSED: DxErrorCode = DxErrorCode(22);
+    pub const CONNECTION_TIMED_OUT: DxErrorCode = DxErrorCode(28);
+    pub const HOST_NOT_FOUND: DxErrorCode = DxErrorCode(43);
+    pub const CONNECTION_CLOSED: DxErrorCode = DxErrorCode(55);
+    pub const INVALID_SYMBOL_NAME: DxErrorCode = DxErrorCode(69);
+    pub const AUTHENTICATION_ERROR: DxErrorCode = DxErrorCode(89);
+    pub const CREDENTIALS_REQUIRED: DxErrorCode = DxErrorCode(90);
+
+    pub fn class(self) -> DxErrorClass {
+        match self.0 {
+            1..=4 => DxErrorClass::Common,
+            5 => DxErrorClass::Memory,
+            6..=44 => DxErrorClass::Socket,
+            45..=52 => DxErrorClass::Thread,
+            53..=57 => DxErrorClass::Network,
+            58..=61 => DxErrorClass::BufferedIo,
+            62..=63 => DxErrorClass::Utf,
+            64..=66 => DxErrorClass::Codec,
+            67..=70 => DxErrorClass::Subscription,
+            71 => DxErrorClass::Logger,
+            72..=90 => DxErrorClass::Protocol,
+            91..=94 => DxErrorClass::Connection,
+            95 => DxErrorClass::Candle,
+            96..=102 => DxErrorClass::Snapshot,
+            103..=107 => DxErrorClass::Configuration,
+            108..=111 => DxErrorClass::PriceLevelBook,
+            _ => DxErrorClass::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for DxErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} error {}", self.class(), self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classes() {
+        assert_eq!(
+            DxErrorCode::CONNECTION_REFUSED.class(),
+            DxErrorClass::Socket
+        );
+        assert_eq!(
+            DxErrorCode::AUTHENTICATION_ERROR.class(),
+            DxErrorClass::Protocol
+        );
+        assert_eq!(DxErrorCode(111).class(), DxErrorClass::PriceLevelBook);
+        assert_eq!(DxErrorCode(112).class(), DxErrorClass::Unknown);
+        assert_eq!(DxErrorCode(55).to_string(), "network error 55");
+    }
+}
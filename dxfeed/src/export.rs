@@ -0,0 +1,8 @@
+//! Flat export formats for events, each enabled by its own feature: `avro`, `csv` and `parquet`.
+
+#[cfg(feature = "avro")]
+pub mod avro;
+#[cfg(feature = "csv")]
+pub mod csv;
+#[cfg(feature = "parquet")]
+pub mod parquet;
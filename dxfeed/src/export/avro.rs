@@ -0,0 +1,270 @@
+//! Avro records of events, one record schema per event type, for Kafka pipelines built on Avro
+//! and a schema registry.
+//!
+//! ```ignore
+//! // Register `schema(EventType::Quote).canonical_form()` with the registry, then
+//! let mut encoder = AvroEncoder::new().with_schema_id(EventType::Quote, schema_id);
+//! subscription.attach(move |event| {
+//!     if let Ok(event) = event {
+//!         let payload = encoder.encode(&event).unwrap();
+//!         producer.send(&event.sym, &payload);
+//!     }
+//! })?;
+//! ```
+//!
+//! Records are named after their event type in the `dxfeed` namespace, e.g. `dxfeed.Quote`, and
+//! have `export::csv`'s columns as fields. Missing values (`None`, and a '\0' character) are
+//! nulls, enums are strings of their variant names and event flags are their bits.
+
+use crate::{
+    Action, Direction, Error, Event, EventData, EventFlags, EventType, PriceType, Scope, Side,
+};
+use apache_avro::types::Value;
+use apache_avro::Schema;
+use std::collections::HashMap;
+
+/// The namespace of the record schemas.
+pub const NAMESPACE: &str = "dxfeed";
+
+/// A value written as one record field.
+trait Field {
+    /// The field's Avro type, as JSON
+    const SCHEMA: &'static str;
+
+    fn value(&self) -> Value;
+}
+
+macro_rules! fields {
+    ($($ty:ty => $schema:literal($value:expr)),* $(,)?) => {
+        $(
+            impl Field for $ty {
+                const SCHEMA: &'static str = $schema;
+
+                fn value(&self) -> Value {
+                    let convert: fn(&$ty) -> Value = $value;
+                    convert(self)
+                }
+            }
+        )*
+    };
+}
+
+fields! {
+    bool => r#""boolean""#(|value| Value::Boolean(*value)),
+    i32 => r#""int""#(|value| Value::Int(*value)),
+    u16 => r#""int""#(|value| Value::Int(*value as i32)),
+    i64 => r#""long""#(|value| Value::Long(*value)),
+    u32 => r#""long""#(|value| Value::Long(*value as i64)),
+    f64 => r#""double""#(|value| Value::Double(*value)),
+    String => r#""string""#(|value| Value::String(value.clone())),
+    EventFlags => r#""long""#(|flags| Value::Long(flags.bits() as i64)),
+    Action => r#""string""#(|value| Value::String(format!("{:?}", value))),
+    Direction => r#""string""#(|value| Value::String(format!("{:?}", value))),
+    PriceType => r#""string""#(|value| Value::String(format!("{:?}", value))),
+    Scope => r#""string""#(|value| Value::String(format!("{:?}", value))),
+    Side => r#""string""#(|value| Value::String(format!("{:?}", value))),
+}
+
+/// A nullable field's value, as the branch of its `["null", ...]` union.
+fn nullable(value: Option<Value>) -> Value {
+    match value {
+        Some(value) => Value::Union(1, Box::new(value)),
+        None => Value::Union(0, Box::new(Value::Null)),
+    }
+}
+
+impl Field for Option<f64> {
+    const SCHEMA: &'static str = r#"["null", "double"]"#;
+
+    fn value(&self) -> Value {
+        nullable(self.map(Value::Double))
+    }
+}
+
+impl Field for char {
+    const SCHEMA: &'static str = r#"["null", "string"]"#;
+
+    fn value(&self) -> Value {
+        nullable((*self != '\0').then(|| Value::String(self.to_string())))
+    }
+}
+
+/// The schema of a field named `name` of `_value`'s type.
+fn field_schema<T: Field>(name: &str, _value: &T) -> String {
+    format!(r#"{{"name": "{}", "type": {}}}"#, name, T::SCHEMA)
+}
+
+/// The default payload of the variant `wrap` makes, so its type is inferred from the variant.
+fn payload_default<T: Default>(_wrap: fn(T) -> EventData) -> T {
+    T::default()
+}
+
+// Defines `field_schemas` and `record` from `event_columns!`
+macro_rules! records {
+    ($(
+        $variant:ident { $($field:ident $(. $sub:ident)?),* $(,)? }
+    )*) => {
+        /// The schemas of the fields of `event_type`'s records, after the symbol.
+        fn field_schemas(event_type: EventType) -> Vec<String> {
+            match event_type {
+                $(
+                    EventType::$variant => {
+                        let data = payload_default(EventData::$variant);
+                        vec![$(field_schema(
+                            concat!(stringify!($field) $(, "_", stringify!($sub))?),
+                            &data.$field$(.$sub)?,
+                        )),*]
+                    }
+                )*
+            }
+        }
+
+        /// `event` as a record of the schema `schema` gives for its type.
+        pub fn record(event: &Event) -> Value {
+            let fields = match &event.data {
+                $(
+                    EventData::$variant(data) => vec![
+                        ("symbol".to_string(), Value::String(event.sym.clone())),
+                        $((
+                            concat!(stringify!($field) $(, "_", stringify!($sub))?).to_string(),
+                            data.$field$(.$sub)?.value(),
+                        ),)*
+                    ],
+                )*
+            };
+            Value::Record(fields)
+        }
+    };
+}
+
+event_columns!(records);
+
+/// The record schema of `event_type`'s events, as JSON.
+pub fn schema_json(event_type: EventType) -> String {
+    let mut fields = vec![field_schema("symbol", &String::new())];
+    fields.extend(field_schemas(event_type));
+    format!(
+        r#"{{"type": "record", "name": "{}", "namespace": "{}", "fields": [{}]}}"#,
+        event_type,
+        NAMESPACE,
+        fields.join(", ")
+    )
+}
+
+/// The record schema of `event_type`'s events.
+pub fn schema(event_type: EventType) -> Schema {
+    Schema::parse_str(&schema_json(event_type)).expect("Event schemas are valid")
+}
+
+/// Encodes events as Avro datums, framed for a schema registry where a schema id is known.
+#[derive(Debug, Default)]
+pub struct AvroEncoder {
+    schemas: HashMap<EventType, Schema>,
+    schema_ids: HashMap<EventType, u32>,
+}
+
+impl AvroEncoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Frame `event_type`'s datums in the Confluent wire format, a zero byte and `schema_id` as
+    /// a big-endian u32, where `schema_id` is its schema's id in the registry.
+    pub fn with_schema_id(mut self, event_type: EventType, schema_id: u32) -> Self {
+        self.schema_ids.insert(event_type, schema_id);
+        self
+    }
+
+    /// The record schema of `event_type`'s events, parsed once.
+    pub fn schema(&mut self, event_type: EventType) -> &Schema {
+        self.schemas
+            .entry(event_type)
+            .or_insert_with(|| schema(event_type))
+    }
+
+    /// `event` as an Avro binary datum, after the wire format header if its type has a schema
+    /// id.
+    pub fn encode(&mut self, event: &Event) -> Result<Vec<u8>, Error> {
+        let event_type = EventType::from(event);
+        let mut bytes = match self.schema_ids.get(&event_type) {
+            Some(schema_id) => {
+                let mut header = vec![0];
+                header.extend_from_slice(&schema_id.to_be_bytes());
+                header
+            }
+            None => Vec::new(),
+        };
+        let datum = apache_avro::to_avro_datum(self.schema(event_type), record(event))
+            .map_err(|err| Error::Avro(Box::new(err)))?;
+        bytes.extend(datum);
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConfigurationData, QuoteData};
+    use apache_avro::from_avro_datum;
+
+    #[test]
+    fn schemas_match_records() {
+        for &event_type in EventType::all() {
+            let schema = schema(event_type);
+            assert_eq!(
+                schema.name().map(|name| name.fullname(None)),
+                Some(format!("dxfeed.{}", event_type))
+            );
+        }
+        let mut encoder = AvroEncoder::new();
+        let data = [
+            EventData::Trade(Default::default()),
+            EventData::Quote(Default::default()),
+            EventData::Summary(Default::default()),
+            EventData::Profile(Default::default()),
+            EventData::Order(Default::default()),
+            EventData::TimeAndSale(Default::default()),
+            EventData::Candle(Default::default()),
+            EventData::TradeETH(Default::default()),
+            EventData::SpreadOrder(Default::default()),
+            EventData::Greeks(Default::default()),
+            EventData::TheoPrice(Default::default()),
+            EventData::Underlying(Default::default()),
+            EventData::Series(Default::default()),
+            EventData::Configuration(ConfigurationData::default()),
+        ];
+        for data in data {
+            let event = Event::new("AAPL".to_string(), data);
+            assert!(encoder.encode(&event).is_ok(), "{:?}", event);
+        }
+    }
+
+    #[test]
+    fn quote_datum() {
+        let mut quote = QuoteData::default();
+        quote.bid.price = 100.5;
+        quote.bid.exchange = 'Q';
+        let event = Event::new("AAPL".to_string(), EventData::Quote(quote));
+        let mut encoder = AvroEncoder::new().with_schema_id(EventType::Quote, 7);
+        let bytes = encoder.encode(&event).unwrap();
+        assert_eq!(bytes[..5], [0, 0, 0, 0, 7]);
+
+        let schema = schema(EventType::Quote);
+        let value = from_avro_datum(&schema, &mut &bytes[5..], None).unwrap();
+        let Value::Record(fields) = value else {
+            panic!("Unexpected {:?}", value);
+        };
+        let field = |name: &str| fields.iter().find(|(n, _)| n == name).unwrap().1.clone();
+        assert_eq!(field("symbol"), Value::String("AAPL".to_string()));
+        assert_eq!(field("bid_price"), Value::Double(100.5));
+        assert_eq!(
+            field("bid_exchange"),
+            Value::Union(1, Box::new(Value::String("Q".to_string())))
+        );
+        assert_eq!(
+            field("ask_exchange"),
+            Value::Union(0, Box::new(Value::Null))
+        );
+        assert_eq!(field("scope"), Value::String("Composite".to_string()));
+    }
+}
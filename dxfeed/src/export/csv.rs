@@ -0,0 +1,206 @@
+//! CSV rows of events, one fixed set of columns per event type, led by the symbol.
+//!
+//! ```ignore
+//! let mut writer = CsvWriter::new(File::create("quotes.csv")?, EventType::Quote)?;
+//! subscription.attach(move |event| {
+//!     if let Ok(event) = event {
+//!         let _ = writer.write(&event);
+//!     }
+//! })?;
+//! ```
+//!
+//! Missing values (`None`, and a '\0' character) are written as empty fields, enums by variant
+//! name and event flags by their bits.
+
+use crate::{
+    Action, Direction, Error, Event, EventData, EventFlags, EventType, PriceType, Scope, Side,
+};
+use std::io;
+
+/// A value written as one CSV field.
+trait Field {
+    fn field(&self) -> String;
+}
+
+macro_rules! display_fields {
+    ($($ty:ty),*) => {
+        $(
+            impl Field for $ty {
+                fn field(&self) -> String {
+                    self.to_string()
+                }
+            }
+        )*
+    };
+}
+
+display_fields!(i32, i64, u16, u32, f64, bool, String);
+
+macro_rules! enum_fields {
+    ($($ty:ty),*) => {
+        $(
+            impl Field for $ty {
+                fn field(&self) -> String {
+                    format!("{:?}", self)
+                }
+            }
+        )*
+    };
+}
+
+enum_fields!(Action, Direction, PriceType, Scope, Side);
+
+impl Field for Option<f64> {
+    fn field(&self) -> String {
+        self.map_or_else(String::new, |value| value.to_string())
+    }
+}
+
+impl Field for char {
+    fn field(&self) -> String {
+        if *self == '\0' {
+            String::new()
+        } else {
+            self.to_string()
+        }
+    }
+}
+
+impl Field for EventFlags {
+    fn field(&self) -> String {
+        self.bits().to_string()
+    }
+}
+
+// Defines `header` and `row` from `event_columns!`
+macro_rules! flatteners {
+    ($(
+        $variant:ident { $($field:ident $(. $sub:ident)?),* $(,)? }
+    )*) => {
+        /// The column names of `event_type`'s rows.
+        pub fn header(event_type: EventType) -> Vec<&'static str> {
+            match event_type {
+                $(
+                    EventType::$variant => vec![
+                        "symbol",
+                        $(concat!(stringify!($field) $(, "_", stringify!($sub))?),)*
+                    ],
+                )*
+            }
+        }
+
+        /// `event` as a row of the columns `header` gives for its type.
+        pub fn row(event: &Event) -> Vec<String> {
+            match &event.data {
+                $(
+                    EventData::$variant(data) => vec![
+                        event.sym.clone(),
+                        $(data.$field$(.$sub)?.field(),)*
+                    ],
+                )*
+            }
+        }
+    };
+}
+
+event_columns!(flatteners);
+
+/// Writes events of one type as CSV rows, after a header row.
+pub struct CsvWriter<W: io::Write> {
+    writer: ::csv::Writer<W>,
+    event_type: EventType,
+}
+
+impl<W: io::Write> CsvWriter<W> {
+    /// Write the header row for `event_type` to `writer`.
+    pub fn new(writer: W, event_type: EventType) -> Result<Self, Error> {
+        let mut writer = ::csv::Writer::from_writer(writer);
+        writer
+            .write_record(header(event_type))
+            .map_err(io::Error::from)?;
+        Ok(CsvWriter { writer, event_type })
+    }
+
+    /// Write `event` as a row. Returns false, writing nothing, if it is of another type.
+    pub fn write(&mut self, event: &Event) -> Result<bool, Error> {
+        if EventType::from(event) != self.event_type {
+            return Ok(false);
+        }
+        self.writer
+            .write_record(row(event))
+            .map_err(io::Error::from)?;
+        Ok(true)
+    }
+
+    pub fn flush(&mut self) -> Result<(), Error> {
+        Ok(self.writer.flush()?)
+    }
+
+    /// Flush and return the underlying writer.
+    pub fn into_inner(self) -> Result<W, Error> {
+        self.writer
+            .into_inner()
+            .map_err(|err| Error::Io(err.into_error()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConfigurationData, QuoteData, UnderlyingData};
+
+    #[test]
+    fn quote_rows() {
+        let mut writer = CsvWriter::new(Vec::new(), EventType::Quote).unwrap();
+        let mut quote = QuoteData::default();
+        quote.bid.price = 100.5;
+        quote.bid.exchange = 'Q';
+        let event = Event::new("AAPL".to_string(), EventData::Quote(quote));
+        assert!(writer.write(&event).unwrap());
+        let underlying = Event::new(
+            "AAPL".to_string(),
+            EventData::Underlying(UnderlyingData::default()),
+        );
+        assert!(!writer.write(&underlying).unwrap());
+
+        let csv = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "symbol,time,sequence,time_nanos,bid_price,bid_size,bid_exchange,bid_time,\
+                 ask_price,ask_size,ask_exchange,ask_time,scope",
+                "AAPL,0,0,0,100.5,0,Q,0,0,0,,0,Composite",
+            ]
+        );
+    }
+
+    #[test]
+    fn headers_match_rows() {
+        let data = [
+            EventData::Trade(Default::default()),
+            EventData::Quote(Default::default()),
+            EventData::Summary(Default::default()),
+            EventData::Profile(Default::default()),
+            EventData::Order(Default::default()),
+            EventData::TimeAndSale(Default::default()),
+            EventData::Candle(Default::default()),
+            EventData::TradeETH(Default::default()),
+            EventData::SpreadOrder(Default::default()),
+            EventData::Greeks(Default::default()),
+            EventData::TheoPrice(Default::default()),
+            EventData::Underlying(Default::default()),
+            EventData::Series(Default::default()),
+            EventData::Configuration(ConfigurationData {
+                version: 1,
+                object: String::new(),
+            }),
+        ];
+        for data in data {
+            let event = Event::new("AAPL".to_string(), data);
+            let header = header(EventType::from(&event));
+            assert_eq!(header[0], "symbol");
+            assert_eq!(row(&event).len(), header.len());
+        }
+    }
+}
@@ -0,0 +1,534 @@
+//! Recording events into Parquet files, partitioned by event type, date and symbol:
+//! `{directory}/{EventType}/date={YYYY-MM-DD}/symbol={symbol}/part-{millis}.parquet`.
+//!
+//! ```ignore
+//! let mut sink = ParquetSink::new("recordings", ParquetSinkOptions::default());
+//! let (sender, receiver) = std::sync::mpsc::channel();
+//! subscription.attach(move |event| {
+//!     let _ = sender.send(event);
+//! })?;
+//! for event in receiver.iter().flatten() {
+//!     sink.write(&event)?;
+//! }
+//! sink.close()?;
+//! ```
+//!
+//! Each partition keeps one file open, buffering rows until `row_group_size` of them make a row
+//! group. Every `flush_interval`, buffered rows are written out and partitions that received
+//! nothing since the previous interval are closed, so yesterday's files are finalized while
+//! recording continues. A file is only readable once closed. Columns match `export::csv`'s, with
+//! `None` and a '\0' character as nulls, enums as their variant names and event flags as bits.
+
+use crate::{
+    Action, Direction, Error, Event, EventData, EventFlags, EventType, PriceType, Scope, Side,
+};
+use ::parquet::basic::Compression;
+use ::parquet::column::writer::ColumnWriter;
+use ::parquet::data_type::ByteArray;
+use ::parquet::file::properties::WriterProperties;
+use ::parquet::file::writer::SerializedFileWriter;
+use ::parquet::schema::parser::parse_message_type;
+use chrono::NaiveDate;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Options for `ParquetSink`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParquetSinkOptions {
+    /// Rows buffered per partition before they are written as a row group
+    pub row_group_size: usize,
+    /// How often buffered rows are written out, and idle partitions closed
+    pub flush_interval: Duration,
+}
+
+impl Default for ParquetSinkOptions {
+    fn default() -> Self {
+        ParquetSinkOptions {
+            row_group_size: 100_000,
+            flush_interval: Duration::from_secs(60),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    Boolean,
+    Int32,
+    Int64,
+    Double,
+    Text,
+}
+
+impl Kind {
+    /// The optional field of this kind named `name`, in the schema's message syntax.
+    fn schema_field(self, name: &str) -> String {
+        match self {
+            Kind::Boolean => format!("OPTIONAL BOOLEAN {};", name),
+            Kind::Int32 => format!("OPTIONAL INT32 {};", name),
+            Kind::Int64 => format!("OPTIONAL INT64 {};", name),
+            Kind::Double => format!("OPTIONAL DOUBLE {};", name),
+            Kind::Text => format!("OPTIONAL BINARY {} (UTF8);", name),
+        }
+    }
+}
+
+enum Value {
+    Boolean(bool),
+    Int32(i32),
+    Int64(i64),
+    Double(f64),
+    Text(String),
+}
+
+/// A value written to one column, or None for a null.
+trait Field {
+    const KIND: Kind;
+
+    fn value(&self) -> Option<Value>;
+}
+
+macro_rules! fields {
+    ($($ty:ty => $kind:ident($value:expr)),* $(,)?) => {
+        $(
+            impl Field for $ty {
+                const KIND: Kind = Kind::$kind;
+
+                fn value(&self) -> Option<Value> {
+                    let convert: fn(&$ty) -> _ = $value;
+                    Some(Value::$kind(convert(self)))
+                }
+            }
+        )*
+    };
+}
+
+fields! {
+    bool => Boolean(|value| *value),
+    i32 => Int32(|value| *value),
+    u16 => Int32(|value| *value as i32),
+    i64 => Int64(|value| *value),
+    u32 => Int64(|value| *value as i64),
+    f64 => Double(|value| *value),
+    String => Text(|value| value.clone()),
+    EventFlags => Int64(|flags| flags.bits() as i64),
+    Action => Text(|value| format!("{:?}", value)),
+    Direction => Text(|value| format!("{:?}", value)),
+    PriceType => Text(|value| format!("{:?}", value)),
+    Scope => Text(|value| format!("{:?}", value)),
+    Side => Text(|value| format!("{:?}", value)),
+}
+
+impl Field for Option<f64> {
+    const KIND: Kind = Kind::Double;
+
+    fn value(&self) -> Option<Value> {
+        self.map(Value::Double)
+    }
+}
+
+impl Field for char {
+    const KIND: Kind = Kind::Text;
+
+    fn value(&self) -> Option<Value> {
+        (*self != '\0').then(|| Value::Text(self.to_string()))
+    }
+}
+
+/// The buffered values of one optional column.
+struct Column {
+    name: &'static str,
+    kind: Kind,
+    values: Vec<Value>,
+    def_levels: Vec<i16>,
+}
+
+impl Column {
+    fn new<T: Field>(name: &'static str, _: &T) -> Self {
+        Column {
+            name,
+            kind: T::KIND,
+            values: Vec::new(),
+            def_levels: Vec::new(),
+        }
+    }
+
+    fn push<T: Field>(&mut self, field: &T) {
+        match field.value() {
+            Some(value) => {
+                self.values.push(value);
+                self.def_levels.push(1);
+            }
+            None => self.def_levels.push(0),
+        }
+    }
+
+    /// Write the buffered values through `writer`, and clear them.
+    fn write(&mut self, writer: &mut ColumnWriter) -> Result<(), Error> {
+        let values = std::mem::take(&mut self.values);
+        let def_levels = Some(&self.def_levels[..]);
+        match writer {
+            ColumnWriter::BoolColumnWriter(writer) => {
+                let values: Vec<bool> = values
+                    .into_iter()
+                    .filter_map(|value| match value {
+                        Value::Boolean(value) => Some(value),
+                        _ => None,
+                    })
+                    .collect();
+                writer.write_batch(&values, def_levels, None)?;
+            }
+            ColumnWriter::Int32ColumnWriter(writer) => {
+                let values: Vec<i32> = values
+                    .into_iter()
+                    .filter_map(|value| match value {
+                        Value::Int32(value) => Some(value),
+                        _ => None,
+                    })
+                    .collect();
+                writer.write_batch(&values, def_levels, None)?;
+            }
+            ColumnWriter::Int64ColumnWriter(writer) => {
+                let values: Vec<i64> = values
+                    .into_iter()
+                    .filter_map(|value| match value {
+                        Value::Int64(value) => Some(value),
+                        _ => None,
+                    })
+                    .collect();
+                writer.write_batch(&values, def_levels, None)?;
+            }
+            ColumnWriter::DoubleColumnWriter(writer) => {
+                let values: Vec<f64> = values
+                    .into_iter()
+                    .filter_map(|value| match value {
+                        Value::Double(value) => Some(value),
+                        _ => None,
+                    })
+                    .collect();
+                writer.write_batch(&values, def_levels, None)?;
+            }
+            ColumnWriter::ByteArrayColumnWriter(writer) => {
+                let values: Vec<ByteArray> = values
+                    .into_iter()
+                    .filter_map(|value| match value {
+                        Value::Text(value) => Some(ByteArray::from(value.into_bytes())),
+                        _ => None,
+                    })
+                    .collect();
+                writer.write_batch(&values, def_levels, None)?;
+            }
+            _ => unreachable!("no column of another physical type is declared"),
+        }
+        self.def_levels.clear();
+        Ok(())
+    }
+}
+
+// Defines `columns` and `push_row` from `event_columns!`
+macro_rules! recorders {
+    ($(
+        $variant:ident { $($field:ident $(. $sub:ident)?),* $(,)? }
+    )*) => {
+        /// Empty columns for events of `event`'s type.
+        fn columns(event: &Event) -> Vec<Column> {
+            match &event.data {
+                $(
+                    EventData::$variant(data) => vec![
+                        Column::new("symbol", &event.sym),
+                        $(Column::new(
+                            concat!(stringify!($field) $(, "_", stringify!($sub))?),
+                            &data.$field$(.$sub)?,
+                        ),)*
+                    ],
+                )*
+            }
+        }
+
+        /// Append `event` to `columns`, made by `columns` for its type.
+        fn push_row(event: &Event, columns: &mut [Column]) {
+            let mut columns = columns.iter_mut();
+            match &event.data {
+                $(
+                    EventData::$variant(data) => {
+                        if let Some(column) = columns.next() {
+                            column.push(&event.sym);
+                        }
+                        $(
+                            if let Some(column) = columns.next() {
+                                column.push(&data.$field$(.$sub)?);
+                            }
+                        )*
+                    }
+                )*
+            }
+        }
+    };
+}
+
+event_columns!(recorders);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Partition {
+    event_type: EventType,
+    date: NaiveDate,
+    symbol: String,
+}
+
+impl Partition {
+    /// The partition of `event`, dated by its timestamp, or today for types without one.
+    fn of(event: &Event) -> Self {
+        let time = event.time_utc().unwrap_or_else(chrono::Utc::now);
+        Partition {
+            event_type: EventType::from(event),
+            date: time.date_naive(),
+            symbol: event.sym.clone(),
+        }
+    }
+
+    fn directory(&self, root: &Path) -> PathBuf {
+        root.join(self.event_type.to_string())
+            .join(format!("date={}", self.date.format("%Y-%m-%d")))
+            .join(format!("symbol={}", escape(&self.symbol)))
+    }
+}
+
+/// `symbol` as a path component, with bytes other than ASCII alphanumerics, '.', '_' and '-'
+/// percent-encoded, e.g. "/ESZ24:XCME" as "%2FESZ24%3AXCME".
+fn escape(symbol: &str) -> String {
+    symbol
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'.' | b'_' | b'-' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// An open file of one partition, with its buffered rows.
+struct PartitionFile {
+    writer: SerializedFileWriter<File>,
+    columns: Vec<Column>,
+    rows: usize,
+    written_since_flush: bool,
+}
+
+impl PartitionFile {
+    fn create(directory: &Path, event: &Event) -> Result<Self, Error> {
+        let columns = columns(event);
+        let fields: Vec<String> = columns
+            .iter()
+            .map(|column| column.kind.schema_field(column.name))
+            .collect();
+        let schema = parse_message_type(&format!(
+            "message {} {{ {} }}",
+            EventType::from(event),
+            fields.join(" ")
+        ))?;
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+        fs::create_dir_all(directory)?;
+        let millis = chrono::Utc::now().timestamp_millis();
+        let file = File::create(directory.join(format!("part-{}.parquet", millis)))?;
+        let writer = SerializedFileWriter::new(file, Arc::new(schema), Arc::new(properties))?;
+        Ok(PartitionFile {
+            writer,
+            columns,
+            rows: 0,
+            written_since_flush: false,
+        })
+    }
+
+    /// Write the buffered rows, if any, as a row group.
+    fn flush(&mut self) -> Result<(), Error> {
+        if self.rows == 0 {
+            return Ok(());
+        }
+        let mut row_group = self.writer.next_row_group()?;
+        for column in &mut self.columns {
+            let mut writer = match row_group.next_column()? {
+                Some(writer) => writer,
+                None => break,
+            };
+            column.write(writer.untyped())?;
+            writer.close()?;
+        }
+        row_group.close()?;
+        self.rows = 0;
+        Ok(())
+    }
+
+    /// Flush and write the footer.
+    fn close(mut self) -> Result<(), Error> {
+        self.flush()?;
+        self.writer.close()?;
+        Ok(())
+    }
+}
+
+/// Records events into Parquet files partitioned by event type, date and symbol. Dropping the
+/// sink closes its files, ignoring errors; call `close` to see them.
+pub struct ParquetSink {
+    directory: PathBuf,
+    options: ParquetSinkOptions,
+    files: HashMap<Partition, PartitionFile>,
+    last_flush: Instant,
+}
+
+impl ParquetSink {
+    /// A sink writing under `directory`, which is created as needed.
+    pub fn new<P: AsRef<Path>>(directory: P, options: ParquetSinkOptions) -> Self {
+        ParquetSink {
+            directory: directory.as_ref().to_path_buf(),
+            options,
+            files: HashMap::new(),
+            last_flush: Instant::now(),
+        }
+    }
+
+    /// Buffer `event` in its partition, writing a row group once `row_group_size` rows are
+    /// buffered there, and flushing every partition once `flush_interval` has passed.
+    pub fn write(&mut self, event: &Event) -> Result<(), Error> {
+        let partition = Partition::of(event);
+        if !self.files.contains_key(&partition) {
+            let file = PartitionFile::create(&partition.directory(&self.directory), event)?;
+            self.files.insert(partition.clone(), file);
+        }
+        if let Some(file) = self.files.get_mut(&partition) {
+            push_row(event, &mut file.columns);
+            file.rows += 1;
+            file.written_since_flush = true;
+            if file.rows >= self.options.row_group_size {
+                file.flush()?;
+            }
+        }
+        if self.last_flush.elapsed() >= self.options.flush_interval {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Write every partition's buffered rows, and close the partitions that received nothing
+    /// since the previous flush.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.last_flush = Instant::now();
+        let idle: Vec<Partition> = self
+            .files
+            .iter()
+            .filter(|(_, file)| !file.written_since_flush)
+            .map(|(partition, _)| partition.clone())
+            .collect();
+        for partition in idle {
+            if let Some(file) = self.files.remove(&partition) {
+                file.close()?;
+            }
+        }
+        for file in self.files.values_mut() {
+            file.flush()?;
+            file.written_since_flush = false;
+        }
+        Ok(())
+    }
+
+    /// Flush and close every file.
+    pub fn close(mut self) -> Result<(), Error> {
+        self.close_files()
+    }
+
+    fn close_files(&mut self) -> Result<(), Error> {
+        for (_, file) in self.files.drain() {
+            file.close()?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ParquetSink {
+    fn drop(&mut self) {
+        let _ = self.close_files();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{QuoteData, TradeData};
+    use ::parquet::file::reader::{FileReader, SerializedFileReader};
+    use ::parquet::record::RowAccessor;
+
+    fn files_in(directory: &Path) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = fs::read_dir(directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn partitioned_files() {
+        let root = std::env::temp_dir().join(format!("dxfeed-parquet-{}", std::process::id()));
+        let options = ParquetSinkOptions {
+            row_group_size: 2,
+            flush_interval: Duration::from_secs(3600),
+        };
+        let mut sink = ParquetSink::new(&root, options);
+        // 2024-01-19 and 2024-01-20
+        for time in [
+            1_705_700_000_000,
+            1_705_700_000_001,
+            1_705_700_000_002,
+            1_705_790_000_000,
+        ] {
+            let mut quote = QuoteData {
+                time,
+                ..Default::default()
+            };
+            quote.bid.exchange = if time % 2 == 0 { 'Q' } else { '\0' };
+            sink.write(&Event::new("AAPL".to_string(), EventData::Quote(quote)))
+                .unwrap();
+        }
+        let trade = TradeData {
+            time: 1_705_700_000_000,
+            price: 4800.25,
+            ..Default::default()
+        };
+        sink.write(&Event::new(
+            "/ESH24:XCME".to_string(),
+            EventData::Trade(trade),
+        ))
+        .unwrap();
+        sink.close().unwrap();
+
+        let quotes = files_in(&root.join("Quote/date=2024-01-19/symbol=AAPL"));
+        assert_eq!(quotes.len(), 1);
+        let reader = SerializedFileReader::new(File::open(&quotes[0]).unwrap()).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 3);
+        assert_eq!(reader.num_row_groups(), 2);
+        let rows: Vec<_> = reader
+            .get_row_iter(None)
+            .unwrap()
+            .map(|row| row.unwrap())
+            .collect();
+        assert_eq!(rows[0].get_string(0).unwrap(), "AAPL");
+        // symbol, time, sequence, time_nanos, bid_price, bid_size, bid_exchange
+        assert_eq!(rows[0].get_string(6).unwrap(), "Q");
+        assert!(rows[1].get_string(6).is_err());
+        assert_eq!(
+            files_in(&root.join("Quote/date=2024-01-20/symbol=AAPL")).len(),
+            1
+        );
+
+        let trades = files_in(&root.join("Trade/date=2024-01-19/symbol=%2FESH24%3AXCME"));
+        let reader = SerializedFileReader::new(File::open(&trades[0]).unwrap()).unwrap();
+        let row = reader.get_row_iter(None).unwrap().next().unwrap().unwrap();
+        // symbol, time, sequence, time_nanos, exchange_code, price
+        assert_eq!(row.get_double(5).unwrap(), 4800.25);
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
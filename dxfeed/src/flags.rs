@@ -0,0 +1,174 @@
+use crate::{
+    dxf_event_flag_t_dxf_ef_remove_event, dxf_event_flag_t_dxf_ef_remove_symbol,
+    dxf_event_flag_t_dxf_ef_snapshot_begin, dxf_event_flag_t_dxf_ef_snapshot_end,
+    dxf_event_flag_t_dxf_ef_snapshot_snip, dxf_event_flag_t_dxf_ef_tx_pending, dxf_event_flags_t,
+    EventData,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::{BitAnd, BitOr, BitOrAssign};
+
+/// The `event_flags` of indexed events (Order, TimeAndSale, Candle, Greeks, Series), which carry
+/// the snapshot and transaction protocol.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct EventFlags(pub dxf_event_flags_t);
+
+impl EventFlags {
+    /// Part of a transaction still in progress; wait for an event without it before acting
+    pub const TX_PENDING: EventFlags = EventFlags(dxf_event_flag_t_dxf_ef_tx_pending);
+    /// The event at this index was removed
+    pub const REMOVE_EVENT: EventFlags = EventFlags(dxf_event_flag_t_dxf_ef_remove_event);
+    /// The first event of a snapshot; previously received events for the symbol are stale
+    pub const SNAPSHOT_BEGIN: EventFlags = EventFlags(dxf_event_flag_t_dxf_ef_snapshot_begin);
+    /// The last event of a snapshot
+    pub const SNAPSHOT_END: EventFlags = EventFlags(dxf_event_flag_t_dxf_ef_snapshot_end);
+    /// The last event of a snapshot cut short; older events are omitted
+    pub const SNAPSHOT_SNIP: EventFlags = EventFlags(dxf_event_flag_t_dxf_ef_snapshot_snip);
+    /// The symbol was unsubscribed
+    pub const REMOVE_SYMBOL: EventFlags = EventFlags(dxf_event_flag_t_dxf_ef_remove_symbol);
+
+    const NAMES: [(EventFlags, &'static str); 6] = [
+        (EventFlags::TX_PENDING, "TX_PENDING"),
+        (EventFlags::REMOVE_EVENT, "REMOVE_EVENT"),
+        (EventFlags::SNAPSHOT_BEGIN, "SNAPSHOT_BEGIN"),
+        (EventFlags::SNAPSHOT_END, "SNAPSHOT_END"),
+        (EventFlags::SNAPSHOT_SNIP, "SNAPSHOT_SNIP"),
+        (EventFlags::REMOVE_SYMBOL, "REMOVE_SYMBOL"),
+    ];
+
+    pub fn bits(self) -> dxf_event_flags_t {
+        self.0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Whether every flag of `other` is set.
+    pub fn contains(self, other: EventFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn is_tx_pending(self) -> bool {
+        self.contains(EventFlags::TX_PENDING)
+    }
+
+    pub fn is_remove_event(self) -> bool {
+        self.contains(EventFlags::REMOVE_EVENT)
+    }
+
+    pub fn is_snapshot_begin(self) -> bool {
+        self.contains(EventFlags::SNAPSHOT_BEGIN)
+    }
+
+    pub fn is_snapshot_end(self) -> bool {
+        self.contains(EventFlags::SNAPSHOT_END)
+    }
+
+    pub fn is_snapshot_snip(self) -> bool {
+        self.contains(EventFlags::SNAPSHOT_SNIP)
+    }
+
+    /// Whether a snapshot is complete with this event, whether ended or snipped.
+    pub fn is_snapshot_complete(self) -> bool {
+        self.is_snapshot_end() || self.is_snapshot_snip()
+    }
+
+    pub fn is_remove_symbol(self) -> bool {
+        self.contains(EventFlags::REMOVE_SYMBOL)
+    }
+}
+
+impl From<dxf_event_flags_t> for EventFlags {
+    fn from(bits: dxf_event_flags_t) -> Self {
+        EventFlags(bits)
+    }
+}
+
+impl BitOr for EventFlags {
+    type Output = EventFlags;
+
+    fn bitor(self, other: EventFlags) -> EventFlags {
+        EventFlags(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for EventFlags {
+    fn bitor_assign(&mut self, other: EventFlags) {
+        self.0 |= other.0;
+    }
+}
+
+impl BitAnd for EventFlags {
+    type Output = EventFlags;
+
+    fn bitand(self, other: EventFlags) -> EventFlags {
+        EventFlags(self.0 & other.0)
+    }
+}
+
+/// E.g. `TX_PENDING | SNAPSHOT_BEGIN`, with any unknown bits in hex.
+impl fmt::Debug for EventFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names: Vec<String> = EventFlags::NAMES
+            .iter()
+            .filter(|(flag, _)| self.contains(*flag))
+            .map(|(_, name)| name.to_string())
+            .collect();
+        let known = EventFlags::NAMES
+            .iter()
+            .fold(0, |bits, (flag, _)| bits | flag.0);
+        if self.0 & !known != 0 {
+            names.push(format!("{:#x}", self.0 & !known));
+        }
+        if names.is_empty() {
+            return f.write_str("(empty)");
+        }
+        f.write_str(&names.join(" | "))
+    }
+}
+
+impl EventData {
+    /// The event flags of indexed event types, or `None` for types without them.
+    pub fn event_flags(&self) -> Option<EventFlags> {
+        match self {
+            EventData::Order(order) => Some(order.event_flags.into()),
+            EventData::TimeAndSale(time_and_sale) => Some(time_and_sale.event_flags.into()),
+            EventData::Candle(candle) => Some(candle.event_flags),
+            EventData::Greeks(greeks) => Some(greeks.event_flags),
+            EventData::Series(series) => Some(series.event_flags),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CandleData;
+
+    #[test]
+    fn flag_queries() {
+        let flags = EventFlags::SNAPSHOT_BEGIN | EventFlags::TX_PENDING;
+        assert!(flags.is_snapshot_begin() && flags.is_tx_pending());
+        assert!(!flags.is_remove_event() && !flags.is_snapshot_complete());
+        assert!(EventFlags::SNAPSHOT_SNIP.is_snapshot_complete());
+        assert_eq!(format!("{:?}", flags), "TX_PENDING | SNAPSHOT_BEGIN");
+        assert_eq!(format!("{:?}", EventFlags(0x102)), "REMOVE_EVENT | 0x100");
+        assert_eq!(format!("{:?}", EventFlags::default()), "(empty)");
+    }
+
+    #[test]
+    fn event_data_flags() {
+        let candle = CandleData {
+            event_flags: EventFlags::REMOVE_EVENT,
+            ..Default::default()
+        };
+        assert!(EventData::Candle(candle)
+            .event_flags()
+            .unwrap()
+            .is_remove_event());
+        assert_eq!(EventData::Quote(Default::default()).event_flags(), None);
+    }
+}
@@ -0,0 +1,457 @@
+//! A backend over the Graal-native dxFeed SDK (`dxfg_*`), enabled by the `graal` feature.
+//!
+//! Quote, Trade, TradeETH, Summary and Greeks are converted to the same `Event` model as the C
+//! API. OptionSale, which the C API lacks, is available through
+//! `GraalConnection::subscribe_option_sales`.
+
+use crate::{
+    dxf_char_t, dxf_greeks_t, dxf_quote_t, dxf_summary_t, dxf_trade_t, Error, Event, EventData,
+    EventType, FeedBackend, FeedSubscription, GreeksData, QuoteData, SummaryData, TradeData,
+    TradeEthData,
+};
+use crate::{panics, utf};
+use libdxfeed_graal_sys::*;
+use serde::{Deserialize, Serialize};
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_void};
+use std::sync::Arc;
+
+type RawCallback = Box<dyn FnMut(*const dxfg_event_type_t) + Send>;
+
+/// Owns the Graal isolate and endpoint, and closes them when the last reference is dropped.
+struct GraalHandle {
+    isolate: *mut graal_isolate_t,
+    endpoint: *mut dxfg_endpoint_t,
+    /// Whether `endpoint` was connected, and so must be closed
+    connected: bool,
+    feed: *mut dxfg_feed_t,
+}
+
+// The SDK is thread-safe, provided each OS thread attaches to the isolate before calling into it.
+unsafe impl Send for GraalHandle {}
+unsafe impl Sync for GraalHandle {}
+
+impl GraalHandle {
+    /// The isolate thread for the calling OS thread, attaching it if necessary.
+    fn thread(&self) -> Result<*mut graal_isolatethread_t, Error> {
+        let mut thread = std::ptr::null_mut();
+        if unsafe { graal_attach_thread(self.isolate, &mut thread) } != 0 {
+            return Err(Error::CallFailed("graal_attach_thread"));
+        }
+        Ok(thread)
+    }
+}
+
+impl Drop for GraalHandle {
+    fn drop(&mut self) {
+        if let Ok(thread) = self.thread() {
+            unsafe {
+                // Partly built when `GraalConnection::new` fails
+                if self.connected {
+                    dxfg_DXEndpoint_close(thread, self.endpoint);
+                }
+                if !self.feed.is_null() {
+                    dxfg_JavaObjectHandler_release(thread, self.feed as *mut _);
+                }
+                if !self.endpoint.is_null() {
+                    dxfg_JavaObjectHandler_release(thread, self.endpoint as *mut _);
+                }
+                graal_tear_down_isolate(thread);
+            }
+        }
+    }
+}
+
+/// A connection through the Graal-native SDK.
+#[derive(Clone)]
+pub struct GraalConnection {
+    handle: Arc<GraalHandle>,
+}
+
+impl GraalConnection {
+    /// Connect to `address`, e.g. "demo.dxfeed.com:7300".
+    pub fn new(address: &str) -> Result<Self, Error> {
+        let c_address = CString::new(address).map_err(|_| Error::ContainsNul)?;
+        let mut isolate = std::ptr::null_mut();
+        let mut thread = std::ptr::null_mut();
+        if unsafe { graal_create_isolate(std::ptr::null_mut(), &mut isolate, &mut thread) } != 0 {
+            return Err(Error::CallFailed("graal_create_isolate"));
+        }
+        let mut handle = GraalHandle {
+            isolate,
+            endpoint: std::ptr::null_mut(),
+            connected: false,
+            feed: std::ptr::null_mut(),
+        };
+        handle.endpoint = unsafe { dxfg_DXEndpoint_create(thread) };
+        if handle.endpoint.is_null() {
+            return Err(Error::CallFailed("dxfg_DXEndpoint_create"));
+        }
+        if unsafe { dxfg_DXEndpoint_connect(thread, handle.endpoint, c_address.as_ptr()) }
+            != DXFG_EXECUTE_SUCCESSFULLY as i32
+        {
+            return Err(Error::CallFailed("dxfg_DXEndpoint_connect"));
+        }
+        handle.connected = true;
+        handle.feed = unsafe { dxfg_DXEndpoint_getFeed(thread, handle.endpoint) };
+        if handle.feed.is_null() {
+            return Err(Error::CallFailed("dxfg_DXEndpoint_getFeed"));
+        }
+        Ok(GraalConnection {
+            handle: Arc::new(handle),
+        })
+    }
+
+    /// Deliver OptionSale events, which the legacy C API does not support, to `listener`.
+    pub fn subscribe_option_sales<F>(&self, mut listener: F) -> Result<GraalSubscription, Error>
+    where
+        F: FnMut(&str, OptionSaleData) + Send + 'static,
+    {
+        self.subscribe_raw(
+            &[DXFG_EVENT_OPTION_SALE],
+            Box::new(move |event| {
+                let sale = unsafe { &*(event as *const dxfg_option_sale_t) };
+                let sym = unsafe { string_from_c(sale.market_event.event_symbol) };
+                listener(&sym, OptionSaleData::from(sale));
+            }),
+        )
+    }
+
+    fn subscribe_raw(
+        &self,
+        clazzes: &[dxfg_event_clazz_t],
+        callback: RawCallback,
+    ) -> Result<GraalSubscription, Error> {
+        let thread = self.handle.thread()?;
+        let mut clazzes = clazzes.to_vec();
+        let mut elements: Vec<*mut dxfg_event_clazz_t> =
+            clazzes.iter_mut().map(|clazz| clazz as *mut _).collect();
+        let mut clazz_list = dxfg_event_clazz_list_t {
+            size: elements.len() as i32,
+            elements: elements.as_mut_ptr(),
+        };
+        let handle =
+            unsafe { dxfg_DXFeed_createSubscription2(thread, self.handle.feed, &mut clazz_list) };
+        if handle.is_null() {
+            return Err(Error::CallFailed("dxfg_DXFeed_createSubscription2"));
+        }
+        let mut sub = GraalSubscription {
+            handle,
+            listener: std::ptr::null_mut(),
+            callback: Box::new(callback),
+            connection: self.handle.clone(),
+        };
+        let user_data = &mut *sub.callback as *mut RawCallback as *mut c_void;
+        sub.listener =
+            unsafe { dxfg_DXFeedEventListener_new(thread, Some(listener_trampoline), user_data) };
+        if sub.listener.is_null() {
+            return Err(Error::CallFailed("dxfg_DXFeedEventListener_new"));
+        }
+        if unsafe { dxfg_DXFeedSubscription_addEventListener(thread, sub.handle, sub.listener) }
+            != DXFG_EXECUTE_SUCCESSFULLY as i32
+        {
+            return Err(Error::CallFailed(
+                "dxfg_DXFeedSubscription_addEventListener",
+            ));
+        }
+        Ok(sub)
+    }
+}
+
+/// A `dxfg_subscription_t`, closed when dropped.
+pub struct GraalSubscription {
+    handle: *mut dxfg_subscription_t,
+    listener: *mut dxfg_feed_event_listener_t,
+    callback: Box<RawCallback>,
+    connection: Arc<GraalHandle>,
+}
+
+unsafe impl Send for GraalSubscription {}
+
+impl GraalSubscription {
+    fn symbol_call(
+        &self,
+        symbols: &[&str],
+        call: unsafe extern "C" fn(
+            *mut graal_isolatethread_t,
+            *mut dxfg_subscription_t,
+            *mut dxfg_symbol_t,
+        ) -> i32,
+        name: &'static str,
+    ) -> Result<(), Error> {
+        let thread = self.connection.thread()?;
+        for symbol in symbols {
+            let c_symbol = CString::new(*symbol).map_err(|_| Error::ContainsNul)?;
+            let mut string_symbol = dxfg_string_symbol_t {
+                supper: dxfg_symbol_t { type_: STRING },
+                symbol: c_symbol.as_ptr(),
+            };
+            let symbol = &mut string_symbol as *mut dxfg_string_symbol_t as *mut dxfg_symbol_t;
+            if unsafe { call(thread, self.handle, symbol) } != DXFG_EXECUTE_SUCCESSFULLY as i32 {
+                return Err(Error::CallFailed(name));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FeedSubscription for GraalSubscription {
+    fn add_symbols(&self, symbols: &[&str]) -> Result<(), Error> {
+        self.symbol_call(
+            symbols,
+            dxfg_DXFeedSubscription_addSymbol,
+            "dxfg_DXFeedSubscription_addSymbol",
+        )
+    }
+
+    fn remove_symbols(&self, symbols: &[&str]) -> Result<(), Error> {
+        self.symbol_call(
+            symbols,
+            dxfg_DXFeedSubscription_removeSymbol,
+            "dxfg_DXFeedSubscription_removeSymbol",
+        )
+    }
+}
+
+impl Drop for GraalSubscription {
+    fn drop(&mut self) {
+        // Close before `callback` is freed, so the SDK stops calling into it.
+        if let Ok(thread) = self.connection.thread() {
+            unsafe {
+                dxfg_DXFeedSubscription_close(thread, self.handle);
+                if !self.listener.is_null() {
+                    dxfg_JavaObjectHandler_release(thread, self.listener as *mut _);
+                }
+                dxfg_JavaObjectHandler_release(thread, self.handle as *mut _);
+            }
+        }
+    }
+}
+
+impl FeedBackend for GraalConnection {
+    type Subscription = GraalSubscription;
+
+    fn connect(address: &str) -> Result<Self, Error> {
+        GraalConnection::new(address)
+    }
+
+    fn subscribe<F>(
+        &self,
+        event_types: &[EventType],
+        mut listener: F,
+    ) -> Result<GraalSubscription, Error>
+    where
+        F: FnMut(Result<Event, Error>) + Send + 'static,
+    {
+        let clazzes = event_types
+            .iter()
+            .map(|event_type| event_clazz(*event_type).ok_or(Error::Invalid(*event_type as i32)))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.subscribe_raw(
+            &clazzes,
+            Box::new(move |event| listener(unsafe { event_from_graal(event) })),
+        )
+    }
+}
+
+extern "C" fn listener_trampoline(
+    _thread: *mut graal_isolatethread_t,
+    events: *mut dxfg_event_type_list,
+    user_data: *mut c_void,
+) {
+    panics::guard("event listener", (), || {
+        let callback = unsafe { &mut *(user_data as *mut RawCallback) };
+        let events = unsafe { &*events };
+        for i in 0..events.size.max(0) as usize {
+            callback(unsafe { *events.elements.add(i) });
+        }
+    })
+}
+
+fn event_clazz(event_type: EventType) -> Option<dxfg_event_clazz_t> {
+    match event_type {
+        EventType::Quote => Some(DXFG_EVENT_QUOTE),
+        EventType::Trade => Some(DXFG_EVENT_TRADE),
+        EventType::TradeETH => Some(DXFG_EVENT_TRADE_ETH),
+        EventType::Summary => Some(DXFG_EVENT_SUMMARY),
+        EventType::Greeks => Some(DXFG_EVENT_GREEKS),
+        _ => None,
+    }
+}
+
+unsafe fn string_from_c(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        String::new()
+    } else {
+        CStr::from_ptr(ptr).to_string_lossy().into_owned()
+    }
+}
+
+/// Millisecond time from a Java `timeSequence`/`index`: seconds in the high 32 bits, then 10
+/// bits of milliseconds above a 22-bit sequence.
+fn time_of(time_sequence: i64) -> i64 {
+    (time_sequence >> 32) * 1000 + ((time_sequence >> 22) & 0x3ff)
+}
+
+fn sequence_of(time_sequence: i64) -> i32 {
+    (time_sequence & 0x3f_ffff) as i32
+}
+
+unsafe fn trade_from_graal(trade: &dxfg_trade_base_t) -> dxf_trade_t {
+    dxf_trade_t {
+        time: time_of(trade.time_sequence),
+        sequence: sequence_of(trade.time_sequence),
+        time_nanos: trade.time_nano_part,
+        exchange_code: trade.exchange_code as dxf_char_t,
+        price: trade.price,
+        size: trade.size,
+        tick: 0,
+        change: trade.change,
+        day_id: trade.day_id,
+        day_volume: trade.day_volume,
+        day_turnover: trade.day_turnover,
+        raw_flags: trade.flags,
+        direction: ((trade.flags >> 1) & 0x7) as _,
+        is_eth: (trade.flags & 1) as _,
+        scope: 0,
+    }
+}
+
+/// Convert a `dxfg_event_type_t` of a supported class into an `Event`.
+unsafe fn event_from_graal(event: *const dxfg_event_type_t) -> Result<Event, Error> {
+    let clazz = (*event).clazz;
+    let market = &*(event as *const dxfg_market_event_t);
+    let data = match clazz {
+        DXFG_EVENT_QUOTE => {
+            let quote = &*(event as *const dxfg_quote_t);
+            let millis_sequence = quote.time_millis_sequence;
+            EventData::Quote(QuoteData::from(&dxf_quote_t {
+                time: quote.bid_time.max(quote.ask_time),
+                sequence: millis_sequence & 0x3f_ffff,
+                time_nanos: quote.time_nano_part,
+                bid_time: quote.bid_time,
+                bid_exchange_code: quote.bid_exchange_code as dxf_char_t,
+                bid_price: quote.bid_price,
+                bid_size: quote.bid_size,
+                ask_time: quote.ask_time,
+                ask_exchange_code: quote.ask_exchange_code as dxf_char_t,
+                ask_price: quote.ask_price,
+                ask_size: quote.ask_size,
+                scope: 0,
+            }))
+        }
+        DXFG_EVENT_TRADE => {
+            let trade = &*(event as *const dxfg_trade_t);
+            EventData::Trade(TradeData::from(&trade_from_graal(&trade.trade_base)))
+        }
+        DXFG_EVENT_TRADE_ETH => {
+            let trade = &*(event as *const dxfg_trade_eth_t);
+            EventData::TradeETH(TradeEthData::from(&trade_from_graal(&trade.trade_base)))
+        }
+        DXFG_EVENT_SUMMARY => {
+            let summary = &*(event as *const dxfg_summary_t);
+            EventData::Summary(SummaryData::from(&dxf_summary_t {
+                day_id: summary.day_id,
+                day_open_price: summary.day_open_price,
+                day_high_price: summary.day_high_price,
+                day_low_price: summary.day_low_price,
+                day_close_price: summary.day_close_price,
+                prev_day_id: summary.prev_day_id,
+                prev_day_close_price: summary.prev_day_close_price,
+                prev_day_volume: summary.prev_day_volume,
+                open_interest: summary.open_interest as f64,
+                raw_flags: summary.flags,
+                exchange_code: 0,
+                day_close_price_type: ((summary.flags >> 2) & 0x3) as _,
+                prev_day_close_price_type: (summary.flags & 0x3) as _,
+                scope: 0,
+            }))
+        }
+        DXFG_EVENT_GREEKS => {
+            let greeks = &*(event as *const dxfg_greeks_t);
+            EventData::Greeks(GreeksData::from(&dxf_greeks_t {
+                event_flags: greeks.event_flags as _,
+                index: greeks.index,
+                time: time_of(greeks.index),
+                price: greeks.price,
+                volatility: greeks.volatility,
+                delta: greeks.delta,
+                gamma: greeks.gamma,
+                theta: greeks.theta,
+                rho: greeks.rho,
+                vega: greeks.vega,
+            }))
+        }
+        _ => return Err(Error::Invalid(clazz as i32)),
+    };
+    Ok(Event::new(string_from_c(market.event_symbol), data))
+}
+
+// dxfg_option_sale_t: not available through the legacy C API
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct OptionSaleData {
+    /// Transactional event flags
+    pub event_flags: i32,
+    /// Unique per-symbol index of this option sale event
+    pub index: i64,
+    /// Timestamp of the original event, in milliseconds
+    pub time: i64,
+    /// Sequence number of this event, to distinguish events with the same time
+    pub sequence: i32,
+    /// Microseconds and nanoseconds part of time
+    pub time_nanos: i32,
+    /// Exchange code of this option sale event, or '\0' if none
+    pub exchange_code: char,
+    pub price: f64,
+    pub size: f64,
+    /// The current bid price on the market when this option sale event had occurred
+    pub bid_price: f64,
+    /// The current ask price on the market when this option sale event had occurred
+    pub ask_price: f64,
+    /// Sale conditions provided for this event by data feed
+    pub exchange_sale_conditions: String,
+    pub raw_flags: i32,
+    /// Underlying price at the time of this option sale event
+    pub underlying_price: f64,
+    /// Black-Scholes implied volatility of the option at the time of this option sale event
+    pub volatility: f64,
+    /// Option delta at the time of this option sale event
+    pub delta: f64,
+    /// Option symbol, since OptionSale events are subscribed by underlying
+    pub option_symbol: String,
+}
+
+impl From<&dxfg_option_sale_t> for OptionSaleData {
+    fn from(sale: &dxfg_option_sale_t) -> Self {
+        OptionSaleData {
+            event_flags: sale.event_flags,
+            index: sale.index,
+            time: time_of(sale.time_sequence),
+            sequence: sequence_of(sale.time_sequence),
+            time_nanos: sale.time_nano_part,
+            exchange_code: utf::decode_char(sale.exchange_code as dxf_char_t),
+            price: sale.price,
+            size: sale.size,
+            bid_price: sale.bid_price,
+            ask_price: sale.ask_price,
+            exchange_sale_conditions: unsafe { string_from_c(sale.exchange_sale_conditions) },
+            raw_flags: sale.flags,
+            underlying_price: sale.underlying_price,
+            volatility: sale.volatility,
+            delta: sale.delta,
+            option_symbol: unsafe { string_from_c(sale.option_symbol) },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_sequence_split() {
+        let time_sequence = (1_700_000_000i64 << 32) | (250 << 22) | 17;
+        assert_eq!(time_of(time_sequence), 1_700_000_000_250);
+        assert_eq!(sequence_of(time_sequence), 17);
+    }
+}
@@ -0,0 +1,82 @@
+//! Owners of memory the C API allocates and hands back, freeing it when dropped.
+
+use crate::{dxf_free, dxf_free_connection_properties_snapshot, dxf_property_item_t, dxf_string_t};
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int, c_void};
+use widestring::WideCString;
+
+/// A `dxf_get_connection_properties_snapshot` result, freed with
+/// `dxf_free_connection_properties_snapshot`.
+pub(crate) struct PropertiesGuard {
+    pub(crate) items: *mut dxf_property_item_t,
+    pub(crate) count: c_int,
+}
+
+impl Default for PropertiesGuard {
+    fn default() -> Self {
+        PropertiesGuard {
+            items: std::ptr::null_mut(),
+            count: 0,
+        }
+    }
+}
+
+impl PropertiesGuard {
+    pub(crate) fn to_map(&self) -> HashMap<String, String> {
+        if self.items.is_null() {
+            return HashMap::new();
+        }
+        let items = unsafe { std::slice::from_raw_parts(self.items, self.count as usize) };
+        items
+            .iter()
+            .map(|item| unsafe { (wide_to_string(item.key), wide_to_string(item.value)) })
+            .collect()
+    }
+}
+
+impl Drop for PropertiesGuard {
+    fn drop(&mut self) {
+        if !self.items.is_null() {
+            unsafe { dxf_free_connection_properties_snapshot(self.items, self.count) };
+        }
+    }
+}
+
+/// A nul-terminated string allocated by the C API, freed with `dxf_free`.
+pub(crate) struct CStringGuard(pub(crate) *mut c_char);
+
+impl Default for CStringGuard {
+    fn default() -> Self {
+        CStringGuard(std::ptr::null_mut())
+    }
+}
+
+impl CStringGuard {
+    /// The string, or None if the C API returned null.
+    pub(crate) fn to_string_lossy(&self) -> Option<String> {
+        if self.0.is_null() {
+            return None;
+        }
+        Some(
+            unsafe { CStr::from_ptr(self.0) }
+                .to_string_lossy()
+                .into_owned(),
+        )
+    }
+}
+
+impl Drop for CStringGuard {
+    fn drop(&mut self) {
+        if !self.0.is_null() {
+            unsafe { dxf_free(self.0 as *mut c_void) };
+        }
+    }
+}
+
+unsafe fn wide_to_string(s: dxf_string_t) -> String {
+    if s.is_null() {
+        return String::new();
+    }
+    WideCString::from_ptr_str(s as *const _).to_string_lossy()
+}
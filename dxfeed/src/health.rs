@@ -0,0 +1,208 @@
+use crate::last_error::call_failed;
+use crate::panics;
+use crate::{
+    dxf_connection_status_t, dxf_connection_status_t_dxf_cs_authorized,
+    dxf_connection_status_t_dxf_cs_connected, dxf_connection_t, dxf_get_current_connection_status,
+    dxf_int_t, dxf_long_t, dxf_set_on_server_heartbeat_notifier, Connection, Error, DXF_SUCCESS,
+};
+use std::os::raw::{c_int, c_void};
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// A connection whose last server heartbeat is older than this is considered down, whatever its
+/// status.
+pub const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The most recent heartbeat received from the server.
+#[derive(Debug, Clone, Copy)]
+pub struct ServerHeartbeat {
+    /// When it was received, locally
+    pub received: Instant,
+    /// The server's time, in milliseconds since the unix epoch
+    pub server_millis: i64,
+    /// The server's lag composing messages, in microseconds
+    pub server_lag_mark: i32,
+    /// Round-trip time to the server, as measured by the C API
+    pub rtt: Duration,
+}
+
+impl ServerHeartbeat {
+    /// `server_lag_mark` as a `Duration`.
+    pub fn server_lag(&self) -> Duration {
+        Duration::from_micros(self.server_lag_mark.max(0) as u64)
+    }
+}
+
+type HeartbeatCallback = Box<dyn FnMut(&ServerHeartbeat) + Send>;
+
+/// Heartbeats received on a connection, recorded by `heartbeat_notifier`.
+#[derive(Default)]
+pub(crate) struct Heartbeats {
+    last: Mutex<Option<ServerHeartbeat>>,
+    received: Condvar,
+    callback: Mutex<Option<HeartbeatCallback>>,
+}
+
+impl Heartbeats {
+    fn record(&self, heartbeat: ServerHeartbeat) {
+        *self.last.lock().unwrap() = Some(heartbeat);
+        self.received.notify_all();
+        if let Some(callback) = self.callback.lock().unwrap().as_mut() {
+            callback(&heartbeat);
+        }
+    }
+
+    fn last(&self) -> Option<ServerHeartbeat> {
+        *self.last.lock().unwrap()
+    }
+
+    /// Wait up to `timeout` for a heartbeat received after `since`.
+    fn wait_after(&self, since: Instant, timeout: Duration) -> Option<ServerHeartbeat> {
+        let last = self.last.lock().unwrap();
+        let (last, _) = self
+            .received
+            .wait_timeout_while(last, timeout, |last| {
+                last.is_none_or(|heartbeat| heartbeat.received <= since)
+            })
+            .unwrap();
+        last.filter(|heartbeat| heartbeat.received > since)
+    }
+}
+
+/// Start recording heartbeats on `connection` into `heartbeats`.
+///
+/// # Safety
+/// `heartbeats` must outlive `connection`.
+pub(crate) unsafe fn watch_heartbeats(
+    connection: dxf_connection_t,
+    heartbeats: &Heartbeats,
+) -> Result<(), Error> {
+    let user_data = heartbeats as *const Heartbeats as *mut c_void;
+    if dxf_set_on_server_heartbeat_notifier(connection, Some(heartbeat_notifier), user_data)
+        != DXF_SUCCESS as c_int
+    {
+        return Err(call_failed("dxf_set_on_server_heartbeat_notifier"));
+    }
+    Ok(())
+}
+
+unsafe extern "C" fn heartbeat_notifier(
+    _connection: dxf_connection_t,
+    server_millis: dxf_long_t,
+    server_lag_mark: dxf_int_t,
+    connection_rtt: dxf_int_t,
+    user_data: *mut c_void,
+) {
+    panics::guard("heartbeat notifier", (), || {
+        let heartbeats = &*(user_data as *const Heartbeats);
+        heartbeats.record(ServerHeartbeat {
+            received: Instant::now(),
+            server_millis,
+            server_lag_mark,
+            // Reported in microseconds
+            rtt: Duration::from_micros(connection_rtt.max(0) as u64),
+        });
+    })
+}
+
+impl Connection {
+    /// The C API's view of the connection status.
+    pub fn status(&self) -> Result<dxf_connection_status_t, Error> {
+        let mut status: dxf_connection_status_t = 0;
+        let result = unsafe { dxf_get_current_connection_status(self.as_raw(), &mut status) };
+        if result != DXF_SUCCESS as c_int {
+            return Err(call_failed("dxf_get_current_connection_status"));
+        }
+        Ok(status)
+    }
+
+    /// The most recent server heartbeat, if any has arrived.
+    pub fn last_heartbeat(&self) -> Option<ServerHeartbeat> {
+        self.handle.heartbeats.last()
+    }
+
+    /// Call `callback` with each server heartbeat from now on, on the connection's socket thread,
+    /// replacing any previous callback. The C API reports no dropped-event counts with
+    /// heartbeats; see `DispatchStats` and `PriorityStats` for those.
+    pub fn on_heartbeat<F: FnMut(&ServerHeartbeat) + Send + 'static>(&self, callback: F) {
+        *self.handle.heartbeats.callback.lock().unwrap() = Some(Box::new(callback));
+    }
+
+    /// A cheap health check: the connection is connected (or authorized), and has received a
+    /// server heartbeat within `HEARTBEAT_TIMEOUT` if it has received any.
+    pub fn is_connected(&self) -> bool {
+        let connected = self.status().is_ok_and(|status| {
+            status == dxf_connection_status_t_dxf_cs_connected
+                || status == dxf_connection_status_t_dxf_cs_authorized
+        });
+        connected
+            && self
+                .last_heartbeat()
+                .is_none_or(|heartbeat| heartbeat.received.elapsed() < HEARTBEAT_TIMEOUT)
+    }
+
+    /// Wait up to `timeout` for a fresh server heartbeat and return the round-trip time it
+    /// reports. The C API has no explicit ping, so this depends on the server's heartbeat period.
+    pub fn ping(&self, timeout: Duration) -> Result<Duration, Error> {
+        self.handle
+            .heartbeats
+            .wait_after(Instant::now(), timeout)
+            .map(|heartbeat| heartbeat.rtt)
+            .ok_or(Error::Timeout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn wait_for_fresh_heartbeat() {
+        let heartbeats = Arc::new(Heartbeats::default());
+        let since = Instant::now();
+        assert!(heartbeats
+            .wait_after(since, Duration::from_millis(10))
+            .is_none());
+
+        let notifier = heartbeats.clone();
+        let thread = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            unsafe {
+                heartbeat_notifier(
+                    std::ptr::null_mut(),
+                    0,
+                    0,
+                    1500,
+                    &*notifier as *const Heartbeats as *mut c_void,
+                )
+            };
+        });
+        let heartbeat = heartbeats
+            .wait_after(since, Duration::from_secs(5))
+            .unwrap();
+        assert_eq!(heartbeat.rtt, Duration::from_micros(1500));
+        assert_eq!(heartbeats.last().unwrap().rtt, heartbeat.rtt);
+        thread.join().unwrap();
+    }
+
+    #[test]
+    fn callback_receives_heartbeats() {
+        let heartbeats = Heartbeats::default();
+        let lags = Arc::new(Mutex::new(Vec::new()));
+        let seen = lags.clone();
+        *heartbeats.callback.lock().unwrap() = Some(Box::new(move |heartbeat| {
+            seen.lock().unwrap().push(heartbeat.server_lag())
+        }));
+        unsafe {
+            heartbeat_notifier(
+                std::ptr::null_mut(),
+                0,
+                250,
+                0,
+                &heartbeats as *const Heartbeats as *mut c_void,
+            )
+        };
+        assert_eq!(*lags.lock().unwrap(), [Duration::from_micros(250)]);
+    }
+}
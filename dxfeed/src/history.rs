@@ -0,0 +1,120 @@
+use crate::{
+    CandleData, CandleSymbol, Connection, EpochMillis, Error, EventData, EventType, TimeAndSaleData,
+};
+use std::time::{Duration, SystemTime};
+
+/// How many times the lookback of `fetch_last_n_candles` is doubled, from `n` periods, while
+/// fewer than `n` candles arrive, e.g. across weekends and holidays.
+const MAX_LOOKBACK_DOUBLINGS: u32 = 6;
+
+impl Connection {
+    /// Fetch the last `n` candles of `period` for `symbol`, oldest first. The snapshot is requested
+    /// from `n` periods ago, and the lookback doubled while fewer than `n` candles arrive, as
+    /// across non-trading hours, up to 64 times `n` periods; fewer are returned only past that.
+    pub fn fetch_last_n_candles(
+        &self,
+        symbol: &str,
+        period: Duration,
+        n: usize,
+        timeout: Duration,
+    ) -> Result<Vec<CandleData>, Error> {
+        let candle_symbol = CandleSymbol::new(symbol).time_period(period)?.to_string();
+        let mut lookback = u32::try_from(n)
+            .ok()
+            .and_then(|n| period.checked_mul(n))
+            .ok_or(Error::InvalidPeriod(period))?;
+        let mut candles = Vec::new();
+        for doubling in 0..=MAX_LOOKBACK_DOUBLINGS {
+            let from = SystemTime::now()
+                .checked_sub(lookback)
+                .ok_or(Error::InvalidPeriod(period))?;
+            let events = self
+                .snapshot(EventType::Candle, &candle_symbol, None, from.epoch_millis())?
+                .collect(timeout)?;
+            candles = events
+                .into_iter()
+                .filter_map(|event| match event.data {
+                    EventData::Candle(candle) => Some(candle),
+                    _ => None,
+                })
+                .collect();
+            if candles.len() >= n || doubling == MAX_LOOKBACK_DOUBLINGS {
+                break;
+            }
+            match lookback.checked_mul(2) {
+                Some(doubled) => lookback = doubled,
+                None => break,
+            }
+        }
+        candles.sort_by_key(|candle| candle.index);
+        Ok(last_n(candles, n))
+    }
+
+    /// Fetch the last `n` TimeAndSale events for `symbol` within `lookback`, oldest first.
+    pub fn fetch_last_n_time_and_sales(
+        &self,
+        symbol: &str,
+        lookback: Duration,
+        n: usize,
+        timeout: Duration,
+    ) -> Result<Vec<TimeAndSaleData>, Error> {
+        let from = SystemTime::now()
+            .checked_sub(lookback)
+            .ok_or(Error::InvalidPeriod(lookback))?;
+        let time_and_sales = self.time_and_sales_since(symbol, from, timeout)?;
+        Ok(last_n(time_and_sales, n))
+    }
+
+    /// Fetch the TimeAndSale tape for `symbol` between `from` and `to` (inclusive), ordered
+    /// oldest first.
+    pub fn fetch_time_and_sales(
+        &self,
+        symbol: &str,
+        from: SystemTime,
+        to: SystemTime,
+        timeout: Duration,
+    ) -> Result<Vec<TimeAndSaleData>, Error> {
+        let to = to.epoch_millis();
+        let mut time_and_sales = self.time_and_sales_since(symbol, from, timeout)?;
+        time_and_sales.retain(|time_and_sale| time_and_sale.time <= to);
+        Ok(time_and_sales)
+    }
+
+    /// Snapshot of TimeAndSale events for `symbol` since `from`, sorted by index (i.e. time).
+    fn time_and_sales_since(
+        &self,
+        symbol: &str,
+        from: SystemTime,
+        timeout: Duration,
+    ) -> Result<Vec<TimeAndSaleData>, Error> {
+        let events = self
+            .snapshot(EventType::TimeAndSale, symbol, None, from.epoch_millis())?
+            .collect(timeout)?;
+        let mut time_and_sales: Vec<TimeAndSaleData> = events
+            .into_iter()
+            .filter_map(|event| match event.data {
+                EventData::TimeAndSale(time_and_sale) => Some(time_and_sale),
+                _ => None,
+            })
+            .collect();
+        time_and_sales.sort_by_key(|time_and_sale| time_and_sale.index);
+        Ok(time_and_sales)
+    }
+}
+
+fn last_n<T>(mut items: Vec<T>, n: usize) -> Vec<T> {
+    let excess = items.len().saturating_sub(n);
+    items.drain(..excess);
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_n_keeps_newest() {
+        assert_eq!(last_n(vec![1, 2, 3, 4], 2), vec![3, 4]);
+        assert_eq!(last_n(vec![1, 2], 5), vec![1, 2]);
+    }
+}
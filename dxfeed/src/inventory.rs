@@ -0,0 +1,223 @@
+use crate::last_error::call_failed;
+use crate::{
+    dxf_const_string_t, dxf_get_symbols, dxf_subscription_t, utf, Connection, Error, EventType,
+    EventTypeMask, Subscription, DXF_SUCCESS,
+};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::os::raw::c_int;
+use std::sync::PoisonError;
+
+/// Counts of subscribed symbols, as the C API currently holds them, e.g. to alert when the
+/// universe shrinks after a reconnect.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SubscriptionInventory {
+    /// Subscriptions counted
+    pub subscriptions: usize,
+    /// Distinct symbols across those subscriptions
+    pub symbols: usize,
+    /// Distinct symbols subscribed for each event type
+    pub by_event_type: BTreeMap<EventType, usize>,
+}
+
+impl SubscriptionInventory {
+    /// The inventory of subscriptions given as (event type mask, symbols) pairs.
+    fn count<I: IntoIterator<Item = (c_int, Vec<String>)>>(subscriptions: I) -> Self {
+        let mut inventory = Self::default();
+        let mut symbols: HashSet<String> = HashSet::new();
+        let mut by_event_type: BTreeMap<EventType, HashSet<String>> = BTreeMap::new();
+        for (event_types, sub_symbols) in subscriptions {
+            inventory.subscriptions += 1;
+            for event_type in EventTypeMask(event_types).event_types() {
+                by_event_type
+                    .entry(event_type)
+                    .or_default()
+                    .extend(sub_symbols.iter().cloned());
+            }
+            symbols.extend(sub_symbols);
+        }
+        inventory.symbols = symbols.len();
+        inventory.by_event_type = by_event_type
+            .into_iter()
+            .map(|(event_type, symbols)| (event_type, symbols.len()))
+            .collect();
+        inventory
+    }
+}
+
+/// A live subscription on a connection, as listed by `Connection::subscriptions`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriptionInfo {
+    /// The raw `dxf_subscription_t` handle as an address, to tell subscriptions apart
+    pub id: usize,
+    pub event_types: EventTypeMask,
+    /// The symbols the C API holds for it
+    pub symbols: Vec<String>,
+}
+
+impl SubscriptionInfo {
+    pub fn symbol_count(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// Whether it would deliver `event_type` events for `symbol`.
+    pub fn covers(&self, symbol: &str, event_type: EventType) -> bool {
+        self.event_types.contains(event_type.into()) && self.symbols.iter().any(|s| s == symbol)
+    }
+}
+
+/// E.g. `0x7f3a2c001230: Trade | Quote, 2 symbols: AAPL, MSFT`, listing at most 10 symbols.
+impl fmt::Display for SubscriptionInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:#x}: {:?}, {} symbols",
+            self.id,
+            self.event_types,
+            self.symbols.len()
+        )?;
+        if !self.symbols.is_empty() {
+            let shown = &self.symbols[..self.symbols.len().min(10)];
+            write!(f, ": {}", shown.join(", "))?;
+            if self.symbols.len() > 10 {
+                f.write_str(", ...")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The symbols the C API holds for `subscription`.
+///
+/// # Safety
+/// `subscription` must be a valid handle.
+pub(crate) unsafe fn subscribed_symbols(
+    subscription: dxf_subscription_t,
+) -> Result<Vec<String>, Error> {
+    let mut symbols: *mut dxf_const_string_t = std::ptr::null_mut();
+    let mut count: c_int = 0;
+    if dxf_get_symbols(subscription, &mut symbols, &mut count) != DXF_SUCCESS as c_int {
+        return Err(call_failed("dxf_get_symbols"));
+    }
+    if symbols.is_null() {
+        return Ok(Vec::new());
+    }
+    // Owned by the subscription, so copied rather than freed
+    std::slice::from_raw_parts(symbols, count as usize)
+        .iter()
+        .map(|symbol| utf::decode(*symbol))
+        .collect()
+}
+
+impl Subscription {
+    /// The symbols this subscription currently holds, counted per event type.
+    pub fn inventory(&self) -> Result<SubscriptionInventory, Error> {
+        let event_types = self
+            .connection
+            .subscriptions
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(handle, _)| *handle == self.as_raw())
+            .map_or(0, |(_, event_types)| *event_types);
+        let symbols = unsafe { subscribed_symbols(self.as_raw()) }?;
+        Ok(SubscriptionInventory::count([(event_types, symbols)]))
+    }
+}
+
+impl Connection {
+    /// The symbols held across all of this connection's open subscriptions.
+    pub fn inventory(&self) -> Result<SubscriptionInventory, Error> {
+        let counted = self
+            .open_subscriptions()
+            .into_iter()
+            .filter_map(|(handle, event_types)| {
+                self.symbols_of(handle)
+                    .map(|symbols| symbols.map(|symbols| (event_types, symbols)))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(SubscriptionInventory::count(counted))
+    }
+
+    /// Every open subscription on this connection, with its event types and the symbols the C
+    /// API holds for it, e.g. to find out why an expected event isn't arriving.
+    pub fn subscriptions(&self) -> Result<Vec<SubscriptionInfo>, Error> {
+        self.open_subscriptions()
+            .into_iter()
+            .filter_map(|(handle, event_types)| {
+                self.symbols_of(handle).map(|symbols| {
+                    Ok(SubscriptionInfo {
+                        id: handle as usize,
+                        event_types: EventTypeMask(event_types),
+                        symbols: symbols?,
+                    })
+                })
+            })
+            .collect()
+    }
+
+    /// The open subscriptions, copied out so the C API is called without the lock held: a
+    /// listener running inside the C API may itself create or close a subscription.
+    fn open_subscriptions(&self) -> Vec<(dxf_subscription_t, c_int)> {
+        self.handle
+            .subscriptions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// The symbols of `handle`, or None if it was closed since `open_subscriptions`.
+    fn symbols_of(&self, handle: dxf_subscription_t) -> Option<Result<Vec<String>, Error>> {
+        match unsafe { subscribed_symbols(handle) } {
+            Ok(symbols) => Some(Ok(symbols)),
+            Err(_) if !self.is_open(handle) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+
+    fn is_open(&self, handle: dxf_subscription_t) -> bool {
+        self.handle
+            .subscriptions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .any(|(open, _)| *open == handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DXF_ET_QUOTE, DXF_ET_TRADE};
+
+    #[test]
+    fn count_distinct_symbols() {
+        let symbols = |names: &[&str]| names.iter().map(|s| s.to_string()).collect();
+        let inventory = SubscriptionInventory::count([
+            (DXF_ET_QUOTE | DXF_ET_TRADE, symbols(&["AAPL", "MSFT"])),
+            (DXF_ET_QUOTE, symbols(&["AAPL", "SPY"])),
+        ]);
+        assert_eq!(inventory.subscriptions, 2);
+        assert_eq!(inventory.symbols, 3);
+        assert_eq!(inventory.by_event_type[&EventType::Quote], 3);
+        assert_eq!(inventory.by_event_type[&EventType::Trade], 2);
+        assert_eq!(inventory.by_event_type.len(), 2);
+    }
+
+    #[test]
+    fn subscription_info() {
+        let info = SubscriptionInfo {
+            id: 0x1230,
+            event_types: EventTypeMask(DXF_ET_QUOTE | DXF_ET_TRADE),
+            symbols: vec!["AAPL".to_string(), "MSFT".to_string()],
+        };
+        assert_eq!(info.symbol_count(), 2);
+        assert!(info.covers("MSFT", EventType::Quote));
+        assert!(!info.covers("MSFT", EventType::Candle));
+        assert!(!info.covers("SPY", EventType::Quote));
+        assert_eq!(
+            info.to_string(),
+            "0x1230: Trade | Quote, 2 symbols: AAPL, MSFT"
+        );
+    }
+}
@@ -0,0 +1,206 @@
+//! Publishes events to local consumers over a Unix domain socket, enabled by the `ipc` feature.
+//!
+//! Each event is framed as a little-endian `u32` byte length followed by that many bytes of
+//! `Event`'s JSON serialization. `UnixSocketReader` decodes the stream on the consumer side.
+
+use crate::{Error, Event};
+use std::io::{BufReader, ErrorKind, Read, Write};
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+
+/// Frames buffered per consumer. A consumer that falls further behind is disconnected.
+const CONSUMER_BUFFER: usize = 4096;
+
+type Consumers = Arc<Mutex<Vec<SyncSender<Arc<[u8]>>>>>;
+
+/// Writes every published event to each connected consumer. Stops accepting connections,
+/// disconnects every consumer and removes the socket file when dropped.
+pub struct UnixSocketPublisher {
+    consumers: Consumers,
+    path: PathBuf,
+    closed: Arc<AtomicBool>,
+}
+
+impl UnixSocketPublisher {
+    /// Listen on `path`, replacing any stale socket file there. Fails if something other than a
+    /// socket is at `path`.
+    pub fn bind<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        match std::fs::symlink_metadata(&path) {
+            Ok(metadata) if metadata.file_type().is_socket() => std::fs::remove_file(&path)?,
+            Ok(_) => {
+                return Err(Error::Io(std::io::Error::new(
+                    ErrorKind::AlreadyExists,
+                    format!("{} exists and is not a socket", path.display()),
+                )))
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        let listener = UnixListener::bind(&path)?;
+        let consumers = Consumers::default();
+        let closed = Arc::new(AtomicBool::new(false));
+        let accept_consumers = consumers.clone();
+        let accept_closed = closed.clone();
+        std::thread::Builder::new()
+            .name("ipc-accept".to_string())
+            .spawn(move || {
+                for stream in listener.incoming() {
+                    let Ok(stream) = stream else { continue };
+                    let (frames, receiver) = sync_channel(CONSUMER_BUFFER);
+                    {
+                        // Checked under the lock so no consumer is added after `drop` clears them
+                        let mut consumers = accept_consumers.lock().unwrap();
+                        if accept_closed.load(Ordering::SeqCst) {
+                            break;
+                        }
+                        consumers.push(frames);
+                    }
+                    let _ = std::thread::Builder::new()
+                        .name("ipc-consumer".to_string())
+                        .spawn(move || write_frames(stream, receiver));
+                }
+            })?;
+        Ok(UnixSocketPublisher {
+            consumers,
+            path,
+            closed,
+        })
+    }
+
+    /// Number of consumers currently receiving events.
+    pub fn consumer_count(&self) -> usize {
+        self.consumers.lock().unwrap().len()
+    }
+
+    pub fn publish(&self, event: &Event) {
+        publish(&self.consumers, event);
+    }
+
+    /// A listener for `Subscription::attach` that publishes each event.
+    pub fn listener(&self) -> impl FnMut(Result<Event, Error>) + Send + 'static {
+        let consumers = self.consumers.clone();
+        move |event| {
+            if let Ok(event) = event {
+                publish(&consumers, &event);
+            }
+        }
+    }
+}
+
+impl Drop for UnixSocketPublisher {
+    fn drop(&mut self) {
+        self.closed.store(true, Ordering::SeqCst);
+        // Wake the accept thread so it sees `closed`
+        let _ = UnixStream::connect(&self.path);
+        // Ends each consumer's frames, so its thread exits even while `listener()`s live on
+        self.consumers.lock().unwrap().clear();
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+fn publish(consumers: &Consumers, event: &Event) {
+    let mut consumers = consumers.lock().unwrap();
+    if consumers.is_empty() {
+        return;
+    }
+    let json = match serde_json::to_vec(event) {
+        Ok(json) => json,
+        Err(_) => return,
+    };
+    let mut frame = Vec::with_capacity(4 + json.len());
+    frame.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    frame.extend_from_slice(&json);
+    let frame: Arc<[u8]> = frame.into();
+    consumers.retain(|consumer| match consumer.try_send(frame.clone()) {
+        Ok(()) => true,
+        Err(TrySendError::Full(_)) | Err(TrySendError::Disconnected(_)) => false,
+    });
+}
+
+fn write_frames(mut stream: UnixStream, frames: Receiver<Arc<[u8]>>) -> std::io::Result<()> {
+    for frame in frames {
+        stream.write_all(&frame)?;
+    }
+    Ok(())
+}
+
+/// Reads events from a `UnixSocketPublisher`.
+pub struct UnixSocketReader {
+    stream: BufReader<UnixStream>,
+    buf: Vec<u8>,
+}
+
+impl UnixSocketReader {
+    pub fn connect<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Ok(UnixSocketReader {
+            stream: BufReader::new(UnixStream::connect(path)?),
+            buf: Vec::new(),
+        })
+    }
+
+    /// Block until the next event arrives.
+    pub fn read_event(&mut self) -> Result<Event, Error> {
+        let mut len = [0u8; 4];
+        self.stream.read_exact(&mut len)?;
+        self.buf.resize(u32::from_le_bytes(len) as usize, 0);
+        self.stream.read_exact(&mut self.buf)?;
+        serde_json::from_slice(&self.buf)
+            .map_err(|e| Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))
+    }
+}
+
+impl Iterator for UnixSocketReader {
+    type Item = Event;
+
+    /// The next event, or None once the publisher closes the connection.
+    fn next(&mut self) -> Option<Event> {
+        self.read_event().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EventData, ProfileEventData};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn publish_to_reader() {
+        let path = std::env::temp_dir().join(format!("dxfeed-ipc-{}.sock", std::process::id()));
+        let publisher = UnixSocketPublisher::bind(&path).unwrap();
+        let mut reader = UnixSocketReader::connect(&path).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while publisher.consumer_count() == 0 {
+            assert!(Instant::now() < deadline, "consumer never registered");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        for sym in ["AAPL", "MSFT"] {
+            let data = EventData::Profile(ProfileEventData::default());
+            publisher.publish(&Event::new(sym.to_string(), data));
+        }
+        assert_eq!(reader.read_event().unwrap().sym, "AAPL");
+        assert_eq!(reader.next().unwrap().sym, "MSFT");
+
+        let listener = publisher.listener();
+        drop(publisher);
+        assert!(!path.exists());
+        // Disconnected, though the listener still holds the consumers
+        assert!(reader.next().is_none());
+        drop(listener);
+    }
+
+    #[test]
+    fn bind_keeps_other_files() {
+        let path = std::env::temp_dir().join(format!("dxfeed-ipc-{}.txt", std::process::id()));
+        std::fs::write(&path, "keep").unwrap();
+        assert!(UnixSocketPublisher::bind(&path).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "keep");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
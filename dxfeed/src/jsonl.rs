@@ -0,0 +1,128 @@
+//! Serves events as newline-delimited JSON over TCP, enabled by the `jsonl` feature.
+//!
+//! On connecting, a client may send a single JSON line to filter what it receives, e.g.
+//! `{"symbols": ["AAPL"], "event_types": ["Quote", "Trade"]}`. Omitted or empty fields match
+//! everything, as does a client that sends nothing within `FILTER_TIMEOUT`. Each event is then
+//! written as one line of `Event`'s serde serialization.
+
+use crate::server::clients::{Acceptor, ClientFilter, Clients, SharedFilter};
+use crate::{Error, Event};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How long a new client has to send its filter line.
+const FILTER_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// A TCP server writing published events to each connected client that wants them. Stops
+/// accepting connections, and disconnects its clients, when dropped.
+pub struct JsonLinesServer {
+    clients: Clients,
+    acceptor: Acceptor,
+}
+
+impl JsonLinesServer {
+    /// Listen on `addr`, e.g. "127.0.0.1:7700".
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> Result<Self, Error> {
+        let clients = Clients::new(|event| {
+            let mut json = serde_json::to_string(event).unwrap_or_default();
+            json.push('\n');
+            json
+        });
+        let acceptor = Acceptor::bind(addr, "jsonl", clients.clone(), serve_client)?;
+        Ok(JsonLinesServer { clients, acceptor })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.acceptor.local_addr()
+    }
+
+    /// Number of clients currently receiving events.
+    pub fn client_count(&self) -> usize {
+        self.clients.len()
+    }
+
+    /// Write `event` to every client whose filter matches it.
+    pub fn publish(&self, event: &Event) {
+        self.clients.publish(event);
+    }
+
+    /// A listener for `Subscription::attach` that publishes each event.
+    pub fn listener(&self) -> impl FnMut(Result<Event, Error>) + Send + 'static {
+        self.clients.listener()
+    }
+}
+
+fn serve_client(stream: TcpStream, clients: Clients) {
+    let filter: SharedFilter = Arc::new(Mutex::new(Some(read_filter(&stream).unwrap_or_default())));
+    let Some(lines) = clients.add(filter.clone()) else {
+        return;
+    };
+    let _ = write_lines(stream, lines);
+    clients.remove(&filter);
+}
+
+fn read_filter(stream: &TcpStream) -> Option<ClientFilter> {
+    stream.set_read_timeout(Some(FILTER_TIMEOUT)).ok()?;
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line).ok()?;
+    serde_json::from_str(&line).ok()
+}
+
+fn write_lines(mut stream: TcpStream, lines: Receiver<Arc<str>>) -> std::io::Result<()> {
+    for line in lines {
+        stream.write_all(line.as_bytes())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConfigurationData, EventData, ProfileEventData};
+    use std::time::Instant;
+
+    fn wait_for_clients(server: &JsonLinesServer, n: usize) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while server.client_count() < n {
+            assert!(Instant::now() < deadline, "client never registered");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn filtered_json_lines() {
+        let server = JsonLinesServer::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(server.local_addr()).unwrap();
+        client
+            .write_all(b"{\"symbols\": [\"AAPL\"], \"event_types\": [\"Profile\"]}\n")
+            .unwrap();
+        wait_for_clients(&server, 1);
+
+        let config = EventData::Configuration(ConfigurationData {
+            version: 0,
+            object: String::new(),
+        });
+        let profile = EventData::Profile(ProfileEventData::default());
+        server.publish(&Event::new("AAPL".to_string(), config));
+        server.publish(&Event::new("MSFT".to_string(), profile.clone()));
+        server.publish(&Event::new("AAPL".to_string(), profile));
+
+        client
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let mut reader = BufReader::new(client);
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let event: Event = serde_json::from_str(&line).unwrap();
+        assert_eq!(event.sym, "AAPL");
+        assert!(matches!(event.data, EventData::Profile(_)));
+
+        // Dropping the server disconnects its clients
+        drop(server);
+        line.clear();
+        assert_eq!(reader.read_line(&mut line).unwrap(), 0);
+    }
+}
@@ -0,0 +1,325 @@
+//! Publishing events to Kafka, enabled by the `kafka` feature.
+//!
+//! ```ignore
+//! let options = KafkaSinkOptions::new(vec!["kafka-1:9092".to_string()], "dxfeed.events")
+//!     .topic_for(EventType::Quote, "dxfeed.quotes")
+//!     .format(KafkaFormat::Json);
+//! let sink = KafkaSink::connect(options, |failure, events| {
+//!     log::error!("{} events lost on {}: {}", events.len(), failure.topic, failure.error);
+//! })?;
+//! let types = [EventType::Quote, EventType::Trade];
+//! let subscription = connection.subscribe(&types, sink.listener())?;
+//! ```
+//!
+//! Each event is a record keyed by its symbol, so a symbol's events stay in order on one
+//! partition. Events are sent in batches from a worker thread; events published while its
+//! queue is full are dropped and counted.
+
+use crate::{Error, Event, EventType};
+use kafka::producer::{Producer, Record, RequiredAcks};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver, RecvTimeoutError, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// How events are serialized into record values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KafkaFormat {
+    /// `Event`'s serde JSON serialization
+    #[default]
+    Json,
+    /// The `proto::Event` message of proto/events.proto
+    #[cfg(feature = "proto")]
+    Proto,
+}
+
+impl KafkaFormat {
+    fn encode(self, event: &Event) -> Result<Vec<u8>, Error> {
+        match self {
+            KafkaFormat::Json => serde_json::to_vec(event)
+                .map_err(|e| Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e))),
+            #[cfg(feature = "proto")]
+            KafkaFormat::Proto => Ok(prost::Message::encode_to_vec(&crate::proto::Event::from(
+                event,
+            ))),
+        }
+    }
+}
+
+/// Where and how a `KafkaSink` publishes.
+#[derive(Debug, Clone)]
+pub struct KafkaSinkOptions {
+    /// Bootstrap brokers, e.g. "kafka-1:9092"
+    pub brokers: Vec<String>,
+    /// The topic of event types without one in `topics`
+    pub topic: String,
+    pub topics: HashMap<EventType, String>,
+    pub format: KafkaFormat,
+    /// The most events sent in one request
+    pub batch_size: usize,
+    /// How long a batch waits to fill before it is sent
+    pub linger: Duration,
+    /// How long the brokers have to acknowledge a batch
+    pub ack_timeout: Duration,
+    /// Events queued for the worker, beyond which they are dropped
+    pub queue_capacity: usize,
+}
+
+impl KafkaSinkOptions {
+    pub fn new(brokers: Vec<String>, topic: &str) -> Self {
+        KafkaSinkOptions {
+            brokers,
+            topic: topic.to_string(),
+            topics: HashMap::new(),
+            format: KafkaFormat::default(),
+            batch_size: 500,
+            linger: Duration::from_millis(50),
+            ack_timeout: Duration::from_secs(5),
+            queue_capacity: 100_000,
+        }
+    }
+
+    /// Publish `event_type` events to `topic` instead of the default.
+    pub fn topic_for(mut self, event_type: EventType, topic: &str) -> Self {
+        self.topics.insert(event_type, topic.to_string());
+        self
+    }
+
+    pub fn format(mut self, format: KafkaFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    pub fn linger(mut self, linger: Duration) -> Self {
+        self.linger = linger;
+        self
+    }
+
+    fn topic(&self, event_type: EventType) -> &str {
+        self.topics.get(&event_type).unwrap_or(&self.topic)
+    }
+}
+
+/// A batch, or part of one, the brokers did not accept.
+#[derive(Debug, Clone)]
+pub struct KafkaDeliveryFailure {
+    pub topic: String,
+    /// The partition that failed, if the request as a whole succeeded
+    pub partition: Option<i32>,
+    pub error: String,
+}
+
+/// Called with a failure and the batch's events for its topic. When a single partition
+/// fails, only some of those events are lost, but which is not known.
+type FailureCallback = Box<dyn FnMut(&KafkaDeliveryFailure, &[Event]) + Send>;
+
+/// Publishes events to Kafka from a worker thread, which sends what is queued and exits once
+/// the sink and its listeners are dropped.
+pub struct KafkaSink {
+    sender: Option<SyncSender<Event>>,
+    worker: Option<JoinHandle<()>>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl KafkaSink {
+    /// Connect to `options.brokers`, calling `on_failure` for events that aren't delivered.
+    pub fn connect<F>(options: KafkaSinkOptions, on_failure: F) -> Result<Self, Error>
+    where
+        F: FnMut(&KafkaDeliveryFailure, &[Event]) + Send + 'static,
+    {
+        let producer = Producer::from_hosts(options.brokers.clone())
+            .with_ack_timeout(options.ack_timeout)
+            .with_required_acks(RequiredAcks::One)
+            .create()
+            .map_err(|e| Error::Kafka(e.to_string()))?;
+        let (sender, events) = sync_channel(options.queue_capacity);
+        let worker = std::thread::Builder::new()
+            .name("dxfeed-kafka".to_string())
+            .spawn(move || publish(producer, &options, &events, Box::new(on_failure)))?;
+        Ok(KafkaSink {
+            sender: Some(sender),
+            worker: Some(worker),
+            dropped: Arc::default(),
+        })
+    }
+
+    /// Queue `event` for publishing, or drop it if the queue is full.
+    pub fn publish(&self, event: Event) {
+        if let Some(sender) = &self.sender {
+            queue(sender, &self.dropped, event);
+        }
+    }
+
+    /// A listener publishing each event, dropping those that failed conversion.
+    pub fn listener(&self) -> impl FnMut(Result<Event, Error>) + Send + 'static {
+        let sender = self.sender.clone();
+        let dropped = self.dropped.clone();
+        move |event| {
+            if let (Some(sender), Ok(event)) = (&sender, event) {
+                queue(sender, &dropped, event);
+            }
+        }
+    }
+
+    /// Total events dropped because the queue was full.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Wait for the worker to send what is queued. Its listeners must have been dropped, e.g.
+    /// with their subscriptions, or this waits for them.
+    pub fn close(mut self) {
+        self.sender = None;
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn queue(sender: &SyncSender<Event>, dropped: &AtomicU64, event: Event) {
+    if let Err(TrySendError::Full(_)) = sender.try_send(event) {
+        dropped.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn publish(
+    mut producer: Producer,
+    options: &KafkaSinkOptions,
+    events: &Receiver<Event>,
+    mut on_failure: FailureCallback,
+) {
+    let mut batch = Vec::with_capacity(options.batch_size);
+    loop {
+        let disconnected = fill(&mut batch, events, options);
+        if !batch.is_empty() {
+            send(&mut producer, options, &batch, &mut on_failure);
+            batch.clear();
+        }
+        if disconnected {
+            return;
+        }
+    }
+}
+
+/// Receive into `batch` until it is full or has lingered. Returns whether the queue is
+/// disconnected.
+fn fill(batch: &mut Vec<Event>, events: &Receiver<Event>, options: &KafkaSinkOptions) -> bool {
+    match events.recv() {
+        Ok(event) => batch.push(event),
+        Err(_) => return true,
+    }
+    let deadline = Instant::now() + options.linger;
+    while batch.len() < options.batch_size {
+        let left = deadline.saturating_duration_since(Instant::now());
+        match events.recv_timeout(left) {
+            Ok(event) => batch.push(event),
+            Err(RecvTimeoutError::Timeout) => break,
+            Err(RecvTimeoutError::Disconnected) => return true,
+        }
+    }
+    false
+}
+
+fn send(
+    producer: &mut Producer,
+    options: &KafkaSinkOptions,
+    batch: &[Event],
+    on_failure: &mut FailureCallback,
+) {
+    let mut by_topic: HashMap<&str, Vec<&Event>> = HashMap::new();
+    let mut records = Vec::with_capacity(batch.len());
+    for event in batch {
+        let topic = options.topic(EventType::from(event));
+        // Events that can't be encoded, which serde_json and prost don't produce, are skipped
+        let Ok(value) = options.format.encode(event) else {
+            continue;
+        };
+        by_topic.entry(topic).or_default().push(event);
+        records.push(Record::from_key_value(topic, event.sym.as_str(), value));
+    }
+    let events_of = |topic: &str| -> Vec<Event> {
+        by_topic.get(topic).map_or_else(Vec::new, |events| {
+            events.iter().map(|&e| e.clone()).collect()
+        })
+    };
+    match producer.send_all(&records) {
+        Ok(confirms) => {
+            for confirm in confirms {
+                for partition in confirm.partition_confirms {
+                    if let Err(code) = partition.offset {
+                        let failure = KafkaDeliveryFailure {
+                            topic: confirm.topic.clone(),
+                            partition: Some(partition.partition),
+                            error: format!("{:?}", code),
+                        };
+                        on_failure(&failure, &events_of(&confirm.topic));
+                    }
+                }
+            }
+        }
+        Err(e) => {
+            for topic in by_topic.keys() {
+                let failure = KafkaDeliveryFailure {
+                    topic: topic.to_string(),
+                    partition: None,
+                    error: e.to_string(),
+                };
+                on_failure(&failure, &events_of(topic));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EventData, QuoteData};
+
+    #[test]
+    fn routes_and_encodes() {
+        let options = KafkaSinkOptions::new(vec!["localhost:9092".to_string()], "events")
+            .topic_for(EventType::Quote, "quotes");
+        assert_eq!(options.topic(EventType::Quote), "quotes");
+        assert_eq!(options.topic(EventType::Trade), "events");
+
+        let event = Event::new("AAPL".to_string(), EventData::Quote(QuoteData::default()));
+        let json = KafkaFormat::Json.encode(&event).unwrap();
+        let decoded: Event = serde_json::from_slice(&json).unwrap();
+        assert_eq!(decoded.sym, "AAPL");
+        #[cfg(feature = "proto")]
+        {
+            let bytes = KafkaFormat::Proto.encode(&event).unwrap();
+            let message: crate::proto::Event = prost::Message::decode(bytes.as_slice()).unwrap();
+            assert_eq!(message.symbol, "AAPL");
+        }
+    }
+
+    #[test]
+    fn batches_fill_to_size_or_linger() {
+        let options = KafkaSinkOptions::new(Vec::new(), "events")
+            .batch_size(2)
+            .linger(Duration::from_millis(10));
+        let (sender, events) = sync_channel(10);
+        let quote = Event::new("AAPL".to_string(), EventData::Quote(QuoteData::default()));
+        for _ in 0..3 {
+            sender.send(quote.clone()).unwrap();
+        }
+        let mut batch = Vec::new();
+        assert!(!fill(&mut batch, &events, &options));
+        assert_eq!(batch.len(), 2);
+        batch.clear();
+        assert!(!fill(&mut batch, &events, &options));
+        assert_eq!(batch.len(), 1);
+        drop(sender);
+        batch.clear();
+        assert!(fill(&mut batch, &events, &options));
+        assert!(batch.is_empty());
+    }
+}
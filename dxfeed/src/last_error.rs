@@ -0,0 +1,33 @@
+use crate::{dxf_const_string_t, dxf_get_last_error, DxErrorCode, Error, DXF_SUCCESS};
+use std::os::raw::c_int;
+use widestring::WideCStr;
+
+/// The code and description of the last C API error on this thread, if any.
+pub fn last_error() -> Option<(DxErrorCode, String)> {
+    let mut code: c_int = 0;
+    let mut description: dxf_const_string_t = std::ptr::null();
+    if unsafe { dxf_get_last_error(&mut code, &mut description) } != DXF_SUCCESS as c_int
+        || code == 0
+    {
+        return None;
+    }
+    // Owned by the C API, so copied rather than freed
+    let message = if description.is_null() {
+        String::new()
+    } else {
+        unsafe { WideCStr::from_ptr_str(description as *const _) }.to_string_lossy()
+    };
+    Some((DxErrorCode(code), message))
+}
+
+/// The error for a failed C API `call`, with the C API's own error when it recorded one.
+pub(crate) fn call_failed(call: &'static str) -> Error {
+    match last_error() {
+        Some((code, message)) => Error::DxFeed {
+            call,
+            code,
+            message,
+        },
+        None => Error::CallFailed(call),
+    }
+}
@@ -2,12 +2,193 @@ use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
 use std::fmt;
 use std::os::raw::{c_int, c_uint};
+use std::time::Duration;
 use strum_macros::EnumString;
 use thiserror::Error;
-use widestring::WideCString;
 
 pub use libdxfeed_sys::*;
 
+#[cfg(feature = "macros")]
+pub use dxfeed_macros::listener;
+
+/// Items used by code `#[dxfeed::listener]` generates. Not part of the API.
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::panics::guard;
+}
+
+// Lets `#[dxfeed::listener]`'s `::dxfeed::` paths resolve in this crate's own tests.
+#[cfg(all(test, feature = "macros"))]
+extern crate self as dxfeed;
+
+#[macro_use]
+mod columns;
+mod average;
+mod backend;
+mod backpressure;
+mod batch;
+mod book;
+#[cfg(feature = "tokio")]
+mod broadcast;
+mod builder;
+mod candle;
+#[cfg(feature = "canonical")]
+mod canonical;
+#[cfg(feature = "capture")]
+mod capture;
+#[cfg(feature = "crossbeam")]
+mod channel;
+mod chunk;
+#[cfg(feature = "codec")]
+mod codec;
+mod compact;
+mod conflate;
+mod connection;
+mod dispatch;
+#[cfg(feature = "dxlink")]
+mod dxlink;
+mod enums;
+mod error_code;
+#[cfg(any(feature = "avro", feature = "csv", feature = "parquet"))]
+pub mod export;
+mod flags;
+#[cfg(feature = "graal")]
+mod graal;
+mod guard;
+mod health;
+mod history;
+mod inventory;
+#[cfg(all(unix, feature = "ipc"))]
+mod ipc;
+#[cfg(feature = "jsonl")]
+mod jsonl;
+#[cfg(feature = "kafka")]
+mod kafka_sink;
+mod last_error;
+mod listener;
+mod logger;
+mod mask;
+mod mock;
+#[cfg(feature = "nats")]
+mod nats;
+mod nbbo;
+mod ohlc;
+mod option_chain;
+mod panics;
+mod pricing;
+mod priority;
+#[cfg(feature = "proto")]
+pub mod proto;
+mod raw;
+mod reconnect;
+#[cfg(feature = "recorder")]
+mod recorder;
+#[cfg(feature = "redis")]
+mod redis_bridge;
+#[cfg(feature = "capture")]
+mod replay;
+mod ring;
+#[cfg(any(feature = "grpc", feature = "jsonl", feature = "ws"))]
+pub mod server;
+#[cfg(feature = "shm")]
+mod shm;
+mod snapshot;
+#[cfg(feature = "futures")]
+mod stream;
+mod subscription;
+pub mod tagged;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+mod throttle;
+mod time;
+#[cfg(feature = "tls")]
+mod tls;
+mod utf;
+mod view;
+#[cfg(feature = "tokio")]
+mod watch;
+
+pub use average::{AverageWindow, TwapCalculator, VwapCalculator};
+pub use backend::{EventStream, FeedBackend, FeedSubscription};
+pub use backpressure::{Backpressure, BackpressureStats, EventQueue};
+pub use batch::{BatchOptions, EventBatches};
+pub use book::{BookDiff, BookLevel, LevelMismatch, OrderBook, OrderMismatch};
+#[cfg(feature = "tokio")]
+pub use broadcast::{EventBroadcast, EventReceiver};
+pub use builder::ConnectionBuilder;
+pub use candle::{CandleAlignment, CandlePeriodType, CandlePrice, CandleSession, CandleSymbol};
+#[cfg(feature = "canonical")]
+pub use canonical::to_canonical_json;
+#[cfg(feature = "capture")]
+pub use capture::{CaptureReader, CaptureRecord, CaptureWriter};
+pub use chunk::{ChunkOptions, ChunkProgress};
+#[cfg(feature = "codec")]
+pub use codec::EventCodec;
+pub use compact::Compact;
+pub use conflate::Conflator;
+pub use connection::{Connection, SummaryProfile};
+pub use dispatch::{DispatchMap, DispatchStats};
+#[cfg(feature = "dxlink")]
+pub use dxlink::{DxLinkConnection, DxLinkFeed};
+pub use enums::{
+    Action, Direction, OrderFlags, PriceType, Scope, ShortSaleRestriction, Side, TnsFlags, TnsType,
+    TradeFlags, TradingStatus,
+};
+pub use error_code::{DxErrorClass, DxErrorCode};
+pub use flags::EventFlags;
+#[cfg(feature = "graal")]
+pub use graal::{GraalConnection, GraalSubscription, OptionSaleData};
+pub use health::{ServerHeartbeat, HEARTBEAT_TIMEOUT};
+pub use inventory::{SubscriptionInfo, SubscriptionInventory};
+#[cfg(all(unix, feature = "ipc"))]
+pub use ipc::{UnixSocketPublisher, UnixSocketReader};
+#[cfg(feature = "jsonl")]
+pub use jsonl::JsonLinesServer;
+#[cfg(feature = "kafka")]
+pub use kafka_sink::{KafkaDeliveryFailure, KafkaFormat, KafkaSink, KafkaSinkOptions};
+pub use last_error::last_error;
+pub use listener::*;
+#[cfg(feature = "log")]
+pub use logger::{forward_log, LogForwarder, LOG_TARGET};
+pub use logger::{initialize_logger, LoggerOptions};
+pub use mask::EventTypeMask;
+pub use mock::{MockFeed, MockSubscription};
+#[cfg(feature = "nats")]
+pub use nats::{NatsOptions, NatsPublisher, NatsStats};
+pub use nbbo::{BestQuote, Nbbo, NbboTracker};
+pub use ohlc::{Bar, BarInterval, OhlcAggregator};
+pub use option_chain::{
+    Expiration, OptionChain, OptionContract, OptionKind, OptionSymbol, StrikeRow,
+};
+pub use panics::{panic_policy, set_panic_policy, CallbackPanic, PanicPolicy};
+pub use pricing::{black_scholes, years_to_expiration, PricingInputs, TheoGreeks};
+pub use priority::{PriorityDispatcher, PriorityStats};
+pub use raw::{RawEvent, RawEventData};
+pub use reconnect::{Backoff, ReconnectingConnection, ReconnectingSubscription};
+#[cfg(feature = "recorder")]
+pub use recorder::{NdjsonRecorder, RecorderOptions};
+#[cfg(feature = "redis")]
+pub use redis_bridge::{RedisBridge, RedisOptions, RedisStats};
+#[cfg(feature = "capture")]
+pub use replay::{ReplayConnection, ReplaySpeed, ReplaySubscription};
+pub use ring::EventRing;
+// The gateway's original path, from before `server` held it
+#[cfg(feature = "grpc")]
+pub use server::grpc;
+#[cfg(feature = "shm")]
+pub use shm::{ShmRingReader, ShmRingWriter};
+pub use snapshot::Snapshot;
+#[cfg(feature = "futures")]
+pub use stream::SubscriptionStream;
+pub use subscription::Subscription;
+pub use tagged::{AdjacentlyTagged, InternallyTagged};
+pub use throttle::{ThrottleStats, ThrottledSubscription};
+pub use time::{EpochMillis, EventTime};
+#[cfg(feature = "tls")]
+pub use tls::TlsConfig;
+pub use utf::{set_utf_strategy, utf_error_count, utf_strategy, UtfStrategy};
+pub use view::EventRef;
+
 ////////////////////////////////////////////////////////////////////////////////
 // Trade event macros from EventData.h
 ////////////////////////////////////////////////////////////////////////////////
@@ -43,6 +224,8 @@ pub const DXF_ET_UNUSED: c_uint = !((1 << dx_event_id_dx_eid_count) - 1);
 #[derive(
     Serialize, Deserialize, PartialEq, Eq, Ord, PartialOrd, Copy, Clone, Debug, Hash, EnumString,
 )]
+// Parsed from the names `Display` writes, ignoring ASCII case, e.g. "timeandsale"
+#[strum(ascii_case_insensitive)]
 pub enum EventType {
     Trade = DXF_ET_TRADE as isize,
     Quote = DXF_ET_QUOTE as isize,
@@ -112,17 +295,70 @@ impl fmt::Display for EventType {
 }
 
 impl EventType {
+    const ALL: [EventType; 14] = [
+        EventType::Trade,
+        EventType::Quote,
+        EventType::Summary,
+        EventType::Profile,
+        EventType::Order,
+        EventType::TimeAndSale,
+        EventType::Candle,
+        EventType::TradeETH,
+        EventType::SpreadOrder,
+        EventType::Greeks,
+        EventType::TheoPrice,
+        EventType::Underlying,
+        EventType::Series,
+        EventType::Configuration,
+    ];
+
+    /// Every event type, in mask bit order.
+    pub fn all() -> &'static [EventType] {
+        &Self::ALL
+    }
+
+    /// As `all`, by value.
+    pub fn iter() -> impl Iterator<Item = EventType> {
+        Self::ALL.into_iter()
+    }
+
     pub fn to_string(value: c_int) -> String {
         Self::try_from(value).map_or_else(
             |_err| format!("<Unknown>({})", value),
             |event_type| format!("{}", event_type),
         )
     }
+
+    /// The `dx_event_id_t` (bit index) for APIs that take a single event id rather than a mask.
+    pub fn event_id(&self) -> dx_event_id_t {
+        (*self as c_int).trailing_zeros()
+    }
+
+    /// Size of the C struct delivered for this event type, i.e. the stride of event arrays.
+    pub(crate) fn record_size(&self) -> usize {
+        match self {
+            EventType::Trade => std::mem::size_of::<dxf_trade_t>(),
+            EventType::Quote => std::mem::size_of::<dxf_quote_t>(),
+            EventType::Summary => std::mem::size_of::<dxf_summary_t>(),
+            EventType::Profile => std::mem::size_of::<dxf_profile_t>(),
+            EventType::Order => std::mem::size_of::<dxf_order_t>(),
+            EventType::TimeAndSale => std::mem::size_of::<dxf_time_and_sale_t>(),
+            EventType::Candle => std::mem::size_of::<dxf_candle_t>(),
+            EventType::TradeETH => std::mem::size_of::<dxf_trade_eth_t>(),
+            EventType::SpreadOrder => std::mem::size_of::<dx_spread_order>(),
+            EventType::Greeks => std::mem::size_of::<dxf_greeks_t>(),
+            EventType::TheoPrice => std::mem::size_of::<dxf_theo_price_t>(),
+            EventType::Underlying => std::mem::size_of::<dxf_underlying_t>(),
+            EventType::Series => std::mem::size_of::<dxf_series_t>(),
+            EventType::Configuration => std::mem::size_of::<dxf_configuration_t>(),
+        }
+    }
 }
 
 // A Rustified dxf_profile_t. namely for converting non-serializable raw C strings (pointers) to
 // Strings.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ProfileEventData {
     ///  The correlation coefficient of the instrument to the S&P500 index (calculated, or received from other data providers)
     pub beta: f64,
@@ -179,50 +415,48 @@ pub struct ProfileEventData {
     /// Description of the reason that trading was halted
     pub status_reason: String,
 
-    /// Trading status of the security instrument
+    /// Trading status of the security instrument; see `status` for it as a `TradingStatus`
     pub trading_status: u32,
 
-    /// Short sale restriction of the security instrument
+    /// Short sale restriction of the security instrument; see `short_sale_restriction` for it as
+    /// a `ShortSaleRestriction`
     pub ssr: u32,
 }
 
 // impl <T: AsRef<dxf_profile_t>> From<T> for ProfileEventData {
 impl From<&dxf_profile_t> for ProfileEventData {
     fn from(c_profile: &dxf_profile_t) -> Self {
-        let description = unsafe {
-            WideCString::from_ptr_str(c_profile.description as *const _).to_string_lossy()
-        };
-        let status_reason = unsafe {
-            WideCString::from_ptr_str(c_profile.status_reason as *const _).to_string_lossy()
-        };
+        let description = unsafe { utf::decode_field(c_profile.description) };
+        let status_reason = unsafe { utf::decode_field(c_profile.status_reason) };
         Self {
-            beta: c_profile.beta as f64,
-            eps: c_profile.eps as f64,
-            div_freq: c_profile.div_freq as f64,
-            exd_div_amount: c_profile.exd_div_amount as f64,
-            exd_div_date: c_profile.exd_div_date as i32,
-            high_52_week_price: c_profile.high_52_week_price as f64,
-            low_52_week_price: c_profile.low_52_week_price as f64,
-            shares: c_profile.shares as f64,
-            free_float: c_profile.free_float as f64,
-            high_limit_price: c_profile.high_limit_price as f64,
-            low_limit_price: c_profile.low_limit_price as f64,
-            halt_start_time: c_profile.halt_start_time as i64,
-            halt_end_time: c_profile.halt_end_time as i64,
-            raw_flags: c_profile.raw_flags as i32,
+            beta: c_profile.beta,
+            eps: c_profile.eps,
+            div_freq: c_profile.div_freq,
+            exd_div_amount: c_profile.exd_div_amount,
+            exd_div_date: c_profile.exd_div_date,
+            high_52_week_price: c_profile.high_52_week_price,
+            low_52_week_price: c_profile.low_52_week_price,
+            shares: c_profile.shares,
+            free_float: c_profile.free_float,
+            high_limit_price: c_profile.high_limit_price,
+            low_limit_price: c_profile.low_limit_price,
+            halt_start_time: c_profile.halt_start_time,
+            halt_end_time: c_profile.halt_end_time,
+            raw_flags: c_profile.raw_flags,
             description,
             status_reason,
-            trading_status: c_profile.trading_status as u32,
-            ssr: c_profile.ssr as u32,
+            trading_status: c_profile.trading_status,
+            ssr: c_profile.ssr,
         }
     }
 }
 
 //  dxf_order_t, but dealing with the string-containingan
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct OrderEventData {
-    /// Source of this order
-    pub source: [dxf_char_t; 17usize],
+    /// Source of this order, e.g. "NTV"
+    pub source: String,
     /// Transactional event flags.
     pub event_flags: dxf_event_flags_t,
     /// Unique per-symbol index of this order.
@@ -234,7 +468,7 @@ pub struct OrderEventData {
     /// Microseconds and nanoseconds part of time of this order.
     pub time_nanos: dxf_int_t,
     /// Order action if available, otherwise - dxf_oa_undefined. This field is a part of the FOB (\
-    pub action: dxf_order_action_t,
+    pub action: Action,
     /// Time of the last \\ref dxf_order.action if available, otherwise - 0. This field is a part of the FOB (\
     pub action_time: dxf_long_t,
     /// Contains order ID if available, otherwise - 0. Some actions dxf_oa_trade, dxf_oa_bust have no order since they are not related\n to any order in Order book.\n\n This field is a part of the FOB (\
@@ -256,29 +490,26 @@ pub struct OrderEventData {
     /// Contains trade size for events containing trade-related action.\n\n This field is a part of the FOB (\
     pub trade_size: dxf_double_t,
     /// Exchange code of this order
-    pub exchange_code: dxf_char_t,
+    pub exchange_code: char,
     /// Side of this order
-    pub side: dxf_order_side_t,
+    pub side: Side,
     /// Scope of this order
-    pub scope: dxf_order_scope_t,
+    pub scope: Scope,
     /// Market maker or spread order
     pub mm_or_spread: String,
 }
 
 impl From<&dxf_order_t> for OrderEventData {
     fn from(c_order: &dxf_order_t) -> Self {
-        let mm_or_spread = unsafe {
-            WideCString::from_ptr_str(c_order.__bindgen_anon_1.market_maker as *const _)
-                .to_string_lossy()
-        };
+        let mm_or_spread = unsafe { utf::decode_field(c_order.__bindgen_anon_1.market_maker) };
         Self {
-            source: c_order.source,
+            source: utf::decode_chars(&c_order.source),
             event_flags: c_order.event_flags,
             index: c_order.index,
             time: c_order.time,
             sequence: c_order.sequence,
             time_nanos: c_order.time_nanos,
-            action: c_order.action,
+            action: c_order.action.try_into().unwrap_or_default(),
             action_time: c_order.action_time,
             order_id: c_order.order_id,
             aux_order_id: c_order.aux_order_id,
@@ -289,9 +520,9 @@ impl From<&dxf_order_t> for OrderEventData {
             trade_id: c_order.trade_id,
             trade_price: c_order.trade_price,
             trade_size: c_order.trade_size,
-            exchange_code: c_order.exchange_code,
-            side: c_order.side,
-            scope: c_order.scope,
+            exchange_code: utf::decode_char(c_order.exchange_code),
+            side: c_order.side.try_into().unwrap_or_default(),
+            scope: c_order.scope.try_into().unwrap_or_default(),
             mm_or_spread,
         }
     }
@@ -299,6 +530,7 @@ impl From<&dxf_order_t> for OrderEventData {
 
 // dxf_time_and_sale / dxf_time_and_sale_t
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct TimeAndSaleData {
     /// Transactional event flags. See: #dxf_event_flag
     pub event_flags: dxf_event_flags_t,
@@ -307,7 +539,7 @@ pub struct TimeAndSaleData {
     /// Timestamp of the original event
     pub time: dxf_long_t,
     /// Exchange code of this time and sale event
-    pub exchange_code: dxf_char_t,
+    pub exchange_code: char,
     /// Price of this time and sale event
     pub price: dxf_double_t,
     /// Size of this time and sale event
@@ -328,7 +560,7 @@ pub struct TimeAndSaleData {
     /// Seller of this time and sale event
     pub seller: String,
     /// Aggressor side of this time and sale event
-    pub side: dxf_order_side_t,
+    pub side: Side,
     /// Type of this time and sale event
     pub kind: dxf_tns_type_t,
     /// Whether this event represents a valid intraday tick
@@ -339,27 +571,21 @@ pub struct TimeAndSaleData {
     pub trade_through_exempt: dxf_char_t,
     /// Whether this event represents a spread leg
     pub is_spread_leg: bool,
-    /// Scope of this TimeAndSale.\n\n Possible values: #dxf_osc_composite (TimeAndSale events) , #dxf_osc_regional (TimeAndSale& events)
-    pub scope: dxf_order_scope_t,
+    /// Scope of this TimeAndSale, `Composite` or, for an exchange's own tape, `Regional`
+    pub scope: Scope,
 }
 
 impl From<&dxf_time_and_sale_t> for TimeAndSaleData {
     fn from(c_time_and_sale: &dxf_time_and_sale_t) -> Self {
-        let exchange_sale_conditions = unsafe {
-            WideCString::from_ptr_str(c_time_and_sale.exchange_sale_conditions as *const _)
-                .to_string_lossy()
-        };
-        let buyer = unsafe {
-            WideCString::from_ptr_str(c_time_and_sale.buyer as *const _).to_string_lossy()
-        };
-        let seller = unsafe {
-            WideCString::from_ptr_str(c_time_and_sale.seller as *const _).to_string_lossy()
-        };
+        let exchange_sale_conditions =
+            unsafe { utf::decode_field(c_time_and_sale.exchange_sale_conditions) };
+        let buyer = unsafe { utf::decode_field(c_time_and_sale.buyer) };
+        let seller = unsafe { utf::decode_field(c_time_and_sale.seller) };
         Self {
             event_flags: c_time_and_sale.event_flags,
             index: c_time_and_sale.index,
             time: c_time_and_sale.time,
-            exchange_code: c_time_and_sale.exchange_code,
+            exchange_code: utf::decode_char(c_time_and_sale.exchange_code),
             price: c_time_and_sale.price,
             size: c_time_and_sale.size,
             bid_price: c_time_and_sale.bid_price,
@@ -368,19 +594,689 @@ impl From<&dxf_time_and_sale_t> for TimeAndSaleData {
             raw_flags: c_time_and_sale.raw_flags,
             buyer,
             seller,
-            side: c_time_and_sale.side,
+            side: c_time_and_sale.side.try_into().unwrap_or_default(),
             kind: c_time_and_sale.type_,
             is_valid_tick: c_time_and_sale.is_valid_tick > 0,
             is_eth_trade: c_time_and_sale.is_eth_trade > 0,
             trade_through_exempt: c_time_and_sale.trade_through_exempt,
             is_spread_leg: c_time_and_sale.is_spread_leg > 0,
-            scope: c_time_and_sale.scope,
+            scope: c_time_and_sale.scope.try_into().unwrap_or_default(),
+        }
+    }
+}
+
+// A Rustified dxf_trade_t, with its tick direction and exchange code converted. Code written
+// against the C struct can convert back with `to_raw`; `EventTime::time_utc` gives the time as
+// a chrono `DateTime`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct TradeData {
+    /// Time of the last trade
+    pub time: dxf_long_t,
+    /// Sequence number of the last trade, to distinguish trades within the same `time`
+    pub sequence: dxf_int_t,
+    /// Microseconds and nanoseconds part of the time of the last trade
+    pub time_nanos: dxf_int_t,
+    /// Exchange code of the last trade
+    pub exchange_code: char,
+    /// Price of the last trade
+    pub price: dxf_double_t,
+    /// Size of the last trade
+    pub size: dxf_double_t,
+    /// Trend indicator of the last trade: 1 for up, 2 for down, else undefined
+    pub tick: dxf_int_t,
+    /// Change of the last trade
+    pub change: dxf_double_t,
+    /// Identifier of the current trading day
+    pub day_id: dxf_dayid_t,
+    /// Total volume traded for a day
+    pub day_volume: dxf_double_t,
+    /// Total turnover traded for a day
+    pub day_turnover: dxf_double_t,
+    /// This field contains several individual flags encoded as an integer number (i.e. it's
+    /// redundant with `direction` and `is_eth`). See `TradeFlags`.
+    pub raw_flags: dxf_int_t,
+    /// Tick direction of the last trade
+    pub direction: Direction,
+    /// Whether the last trade was in extended trading hours
+    pub is_eth: bool,
+    /// Scope of this trade, `Composite` or `Regional`
+    pub scope: Scope,
+}
+
+impl From<&dxf_trade_t> for TradeData {
+    fn from(c_trade: &dxf_trade_t) -> Self {
+        Self {
+            time: c_trade.time,
+            sequence: c_trade.sequence,
+            time_nanos: c_trade.time_nanos,
+            exchange_code: utf::decode_char(c_trade.exchange_code),
+            price: c_trade.price,
+            size: c_trade.size,
+            tick: c_trade.tick,
+            change: c_trade.change,
+            day_id: c_trade.day_id,
+            day_volume: c_trade.day_volume,
+            day_turnover: c_trade.day_turnover,
+            raw_flags: c_trade.raw_flags,
+            direction: c_trade.direction.try_into().unwrap_or_default(),
+            is_eth: c_trade.is_eth != 0,
+            scope: c_trade.scope.try_into().unwrap_or_default(),
+        }
+    }
+}
+
+impl TradeData {
+    /// The trade as the C API's struct.
+    pub fn to_raw(&self) -> dxf_trade_t {
+        dxf_trade_t {
+            time: self.time,
+            sequence: self.sequence,
+            time_nanos: self.time_nanos,
+            exchange_code: self.exchange_code as dxf_char_t,
+            price: self.price,
+            size: self.size,
+            tick: self.tick,
+            change: self.change,
+            day_id: self.day_id,
+            day_volume: self.day_volume,
+            day_turnover: self.day_turnover,
+            raw_flags: self.raw_flags,
+            direction: self.direction.into(),
+            is_eth: self.is_eth as _,
+            scope: self.scope.into(),
+        }
+    }
+}
+
+// A Rustified dxf_trade_eth_t, with its tick direction and exchange code converted. TradeETH
+// events leave `tick` unset, so it's omitted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct TradeEthData {
+    /// Time of the last trade
+    pub time: dxf_long_t,
+    /// Sequence number of the last trade, to distinguish trades within the same `time`
+    pub sequence: dxf_int_t,
+    /// Microseconds and nanoseconds part of the time of the last trade
+    pub time_nanos: dxf_int_t,
+    /// Exchange code of the last trade
+    pub exchange_code: char,
+    /// Price of the last trade
+    pub price: dxf_double_t,
+    /// Size of the last trade
+    pub size: dxf_double_t,
+    /// Change of the last trade
+    pub change: dxf_double_t,
+    /// Identifier of the current trading day
+    pub day_id: dxf_dayid_t,
+    /// Total volume traded for a day
+    pub day_volume: dxf_double_t,
+    /// Total turnover traded for a day
+    pub day_turnover: dxf_double_t,
+    /// This field contains several individual flags encoded as an integer number (i.e. it's
+    /// redundant with `direction` and `is_eth`). See `TradeFlags`.
+    pub raw_flags: dxf_int_t,
+    /// Tick direction of the last trade
+    pub direction: Direction,
+    /// Whether the last trade was in extended trading hours
+    pub is_eth: bool,
+    /// Scope of this trade, `Composite` or `Regional`
+    pub scope: Scope,
+}
+
+impl From<&dxf_trade_eth_t> for TradeEthData {
+    fn from(c_trade: &dxf_trade_eth_t) -> Self {
+        Self {
+            time: c_trade.time,
+            sequence: c_trade.sequence,
+            time_nanos: c_trade.time_nanos,
+            exchange_code: utf::decode_char(c_trade.exchange_code),
+            price: c_trade.price,
+            size: c_trade.size,
+            change: c_trade.change,
+            day_id: c_trade.day_id,
+            day_volume: c_trade.day_volume,
+            day_turnover: c_trade.day_turnover,
+            raw_flags: c_trade.raw_flags,
+            direction: c_trade.direction.try_into().unwrap_or_default(),
+            is_eth: c_trade.is_eth != 0,
+            scope: c_trade.scope.try_into().unwrap_or_default(),
+        }
+    }
+}
+
+// A Rustified dxf_summary_t, with its exchange code and price types converted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct SummaryData {
+    /// Identifier of the day this summary is for, in days since the unix epoch
+    pub day_id: dxf_dayid_t,
+    /// The first (open) price for the day
+    pub day_open_price: dxf_double_t,
+    /// The maximal (high) price for the day
+    pub day_high_price: dxf_double_t,
+    /// The minimal (low) price for the day
+    pub day_low_price: dxf_double_t,
+    /// The last (close) price for the day
+    pub day_close_price: dxf_double_t,
+    /// Identifier of the previous day, in days since the unix epoch
+    pub prev_day_id: dxf_dayid_t,
+    /// The last (close) price for the previous day
+    pub prev_day_close_price: dxf_double_t,
+    /// Total volume traded for the previous day
+    pub prev_day_volume: dxf_double_t,
+    /// Open interest of the symbol as the number of open contracts
+    pub open_interest: dxf_double_t,
+    /// This field contains several individual flags encoded as an integer number (i.e. it's
+    /// redundant with the price types)
+    pub raw_flags: dxf_int_t,
+    /// Exchange code of this summary, or '\0' for a composite one
+    pub exchange_code: char,
+    /// How final the day's close price is
+    pub day_close_price_type: PriceType,
+    /// How final the previous day's close price is
+    pub prev_day_close_price_type: PriceType,
+    /// Scope of this summary, `Composite` or `Regional`
+    pub scope: Scope,
+}
+
+impl From<&dxf_summary_t> for SummaryData {
+    fn from(c_summary: &dxf_summary_t) -> Self {
+        Self {
+            day_id: c_summary.day_id,
+            day_open_price: c_summary.day_open_price,
+            day_high_price: c_summary.day_high_price,
+            day_low_price: c_summary.day_low_price,
+            day_close_price: c_summary.day_close_price,
+            prev_day_id: c_summary.prev_day_id,
+            prev_day_close_price: c_summary.prev_day_close_price,
+            prev_day_volume: c_summary.prev_day_volume,
+            open_interest: c_summary.open_interest,
+            raw_flags: c_summary.raw_flags,
+            exchange_code: utf::decode_char(c_summary.exchange_code),
+            day_close_price_type: c_summary
+                .day_close_price_type
+                .try_into()
+                .unwrap_or_default(),
+            prev_day_close_price_type: c_summary
+                .prev_day_close_price_type
+                .try_into()
+                .unwrap_or_default(),
+            scope: c_summary.scope.try_into().unwrap_or_default(),
+        }
+    }
+}
+
+impl SummaryData {
+    /// The date of `day_id`, if set.
+    #[cfg(feature = "chrono")]
+    pub fn day(&self) -> Option<chrono::NaiveDate> {
+        time::date_of_day_id(self.day_id)
+    }
+
+    /// The date of `prev_day_id`, if set.
+    #[cfg(feature = "chrono")]
+    pub fn prev_day(&self) -> Option<chrono::NaiveDate> {
+        time::date_of_day_id(self.prev_day_id)
+    }
+}
+
+/// `value`, or `None` if NaN, as the C API fills in values that aren't available.
+fn non_nan(value: f64) -> Option<f64> {
+    (!value.is_nan()).then_some(value)
+}
+
+// A Rustified dxf_candle_t, with typed event flags and `None` for the fields the C API leaves NaN
+// when unavailable.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct CandleData {
+    /// Transactional event flags
+    pub event_flags: EventFlags,
+    /// Unique per-symbol index of this candle
+    pub index: dxf_long_t,
+    /// Timestamp of this candle in milliseconds
+    pub time: dxf_long_t,
+    /// Sequence number of this candle, to distinguish candles within the same `time`
+    pub sequence: dxf_int_t,
+    /// Total number of original trade (or quote) events in this candle
+    pub count: dxf_double_t,
+    /// The first (open) price of this candle
+    pub open: dxf_double_t,
+    /// The maximal (high) price of this candle
+    pub high: dxf_double_t,
+    /// The minimal (low) price of this candle
+    pub low: dxf_double_t,
+    /// The last (close) price of this candle
+    pub close: dxf_double_t,
+    /// Total volume in this candle
+    pub volume: dxf_double_t,
+    /// Volume-weighted average price (VWAP) in this candle
+    pub vwap: Option<dxf_double_t>,
+    /// Bid volume in this candle
+    pub bid_volume: Option<dxf_double_t>,
+    /// Ask volume in this candle
+    pub ask_volume: Option<dxf_double_t>,
+    /// Open interest
+    pub open_interest: Option<dxf_double_t>,
+    /// Implied volatility
+    pub imp_volatility: Option<dxf_double_t>,
+}
+
+impl From<&dxf_candle_t> for CandleData {
+    fn from(c_candle: &dxf_candle_t) -> Self {
+        Self {
+            event_flags: c_candle.event_flags.into(),
+            index: c_candle.index,
+            time: c_candle.time,
+            sequence: c_candle.sequence,
+            count: c_candle.count,
+            open: c_candle.open,
+            high: c_candle.high,
+            low: c_candle.low,
+            close: c_candle.close,
+            volume: c_candle.volume,
+            vwap: non_nan(c_candle.vwap),
+            bid_volume: non_nan(c_candle.bid_volume),
+            ask_volume: non_nan(c_candle.ask_volume),
+            open_interest: non_nan(c_candle.open_interest),
+            imp_volatility: non_nan(c_candle.imp_volatility),
+        }
+    }
+}
+
+impl CandleData {
+    /// The candle as the C API's struct, with NaN for `None`.
+    pub fn to_raw(&self) -> dxf_candle_t {
+        dxf_candle_t {
+            event_flags: self.event_flags.bits(),
+            index: self.index,
+            time: self.time,
+            sequence: self.sequence,
+            count: self.count,
+            open: self.open,
+            high: self.high,
+            low: self.low,
+            close: self.close,
+            volume: self.volume,
+            vwap: self.vwap.unwrap_or(f64::NAN),
+            bid_volume: self.bid_volume.unwrap_or(f64::NAN),
+            ask_volume: self.ask_volume.unwrap_or(f64::NAN),
+            open_interest: self.open_interest.unwrap_or(f64::NAN),
+            imp_volatility: self.imp_volatility.unwrap_or(f64::NAN),
+        }
+    }
+}
+
+// A Rustified dxf_greeks_t, with typed event flags. Greeks is an indexed event, delivered as
+// snapshots and transactions; see `EventFlags` for assembling them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct GreeksData {
+    /// Transactional event flags
+    pub event_flags: EventFlags,
+    /// Unique per-symbol index of this event
+    pub index: dxf_long_t,
+    /// Timestamp of this event in milliseconds
+    pub time: dxf_long_t,
+    /// Option market price
+    pub price: dxf_double_t,
+    /// Black-Scholes implied volatility of the option
+    pub volatility: dxf_double_t,
+    /// Option delta
+    pub delta: dxf_double_t,
+    /// Option gamma
+    pub gamma: dxf_double_t,
+    /// Option theta
+    pub theta: dxf_double_t,
+    /// Option rho
+    pub rho: dxf_double_t,
+    /// Option vega
+    pub vega: dxf_double_t,
+}
+
+impl From<&dxf_greeks_t> for GreeksData {
+    fn from(c_greeks: &dxf_greeks_t) -> Self {
+        Self {
+            event_flags: c_greeks.event_flags.into(),
+            index: c_greeks.index,
+            time: c_greeks.time,
+            price: c_greeks.price,
+            volatility: c_greeks.volatility,
+            delta: c_greeks.delta,
+            gamma: c_greeks.gamma,
+            theta: c_greeks.theta,
+            rho: c_greeks.rho,
+            vega: c_greeks.vega,
+        }
+    }
+}
+
+impl GreeksData {
+    /// The first event of a snapshot; earlier events for the symbol are stale.
+    pub fn is_snapshot_begin(&self) -> bool {
+        self.event_flags.is_snapshot_begin()
+    }
+
+    /// The last event of a snapshot.
+    pub fn is_snapshot_end(&self) -> bool {
+        self.event_flags.is_snapshot_end()
+    }
+
+    /// The last event of a snapshot cut short.
+    pub fn is_snapshot_snip(&self) -> bool {
+        self.event_flags.is_snapshot_snip()
+    }
+
+    /// The last event of a snapshot, whole or cut short.
+    pub fn is_snapshot_complete(&self) -> bool {
+        self.event_flags.is_snapshot_complete()
+    }
+
+    /// Part of a transaction still in progress.
+    pub fn is_tx_pending(&self) -> bool {
+        self.event_flags.is_tx_pending()
+    }
+
+    /// The event at `index` was removed; its other fields are meaningless.
+    pub fn is_remove_event(&self) -> bool {
+        self.event_flags.is_remove_event()
+    }
+
+    /// The greeks as the C API's struct.
+    pub fn to_raw(&self) -> dxf_greeks_t {
+        dxf_greeks_t {
+            event_flags: self.event_flags.bits(),
+            index: self.index,
+            time: self.time,
+            price: self.price,
+            volatility: self.volatility,
+            delta: self.delta,
+            gamma: self.gamma,
+            theta: self.theta,
+            rho: self.rho,
+            vega: self.vega,
+        }
+    }
+}
+
+// A Rustified dxf_series_t, with typed event flags. Series is an indexed event, keyed by
+// expiration.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct SeriesData {
+    /// Transactional event flags
+    pub event_flags: EventFlags,
+    /// Unique per-symbol index of this series
+    pub index: dxf_long_t,
+    /// Timestamp of this event in milliseconds
+    pub time: dxf_long_t,
+    /// Sequence number of this event, to distinguish events within the same `time`
+    pub sequence: dxf_int_t,
+    /// Day id of expiration, in days since the unix epoch; see `expiration_date`
+    pub expiration: dxf_dayid_t,
+    /// Implied volatility index for this series, based on VIX methodology
+    pub volatility: dxf_double_t,
+    /// Call options traded volume for a day
+    pub call_volume: dxf_double_t,
+    /// Put options traded volume for a day
+    pub put_volume: dxf_double_t,
+    /// Options traded volume for a day
+    pub option_volume: dxf_double_t,
+    /// Ratio of put options traded volume to call options traded volume for a day
+    pub put_call_ratio: dxf_double_t,
+    /// Implied forward price for this option series
+    pub forward_price: dxf_double_t,
+    /// Implied simple dividend return of the corresponding option series
+    pub dividend: dxf_double_t,
+    /// Implied simple interest return of the corresponding option series
+    pub interest: dxf_double_t,
+}
+
+impl From<&dxf_series_t> for SeriesData {
+    fn from(c_series: &dxf_series_t) -> Self {
+        Self {
+            event_flags: c_series.event_flags.into(),
+            index: c_series.index,
+            time: c_series.time,
+            sequence: c_series.sequence,
+            expiration: c_series.expiration,
+            volatility: c_series.volatility,
+            call_volume: c_series.call_volume,
+            put_volume: c_series.put_volume,
+            option_volume: c_series.option_volume,
+            put_call_ratio: c_series.put_call_ratio,
+            forward_price: c_series.forward_price,
+            dividend: c_series.dividend,
+            interest: c_series.interest,
+        }
+    }
+}
+
+impl SeriesData {
+    /// The date of `expiration`, if set.
+    #[cfg(feature = "chrono")]
+    pub fn expiration_date(&self) -> Option<chrono::NaiveDate> {
+        time::date_of_day_id(self.expiration)
+    }
+
+    /// The series as the C API's struct.
+    pub fn to_raw(&self) -> dxf_series_t {
+        dxf_series_t {
+            event_flags: self.event_flags.bits(),
+            index: self.index,
+            time: self.time,
+            sequence: self.sequence,
+            expiration: self.expiration,
+            volatility: self.volatility,
+            call_volume: self.call_volume,
+            put_volume: self.put_volume,
+            option_volume: self.option_volume,
+            put_call_ratio: self.put_call_ratio,
+            forward_price: self.forward_price,
+            dividend: self.dividend,
+            interest: self.interest,
+        }
+    }
+}
+
+// A Rustified dxf_theo_price_t, with `None` for the dividend and interest when unavailable.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct TheoPriceData {
+    /// Timestamp of this event in milliseconds
+    pub time: dxf_long_t,
+    /// Theoretical option price
+    pub price: dxf_double_t,
+    /// Underlying price at the time of theo price computation
+    pub underlying_price: dxf_double_t,
+    /// Delta of the theoretical price
+    pub delta: dxf_double_t,
+    /// Gamma of the theoretical price
+    pub gamma: dxf_double_t,
+    /// Implied simple dividend return of the corresponding option series
+    pub dividend: Option<dxf_double_t>,
+    /// Implied simple interest return of the corresponding option series
+    pub interest: Option<dxf_double_t>,
+}
+
+impl From<&dxf_theo_price_t> for TheoPriceData {
+    fn from(c_theo: &dxf_theo_price_t) -> Self {
+        Self {
+            time: c_theo.time,
+            price: c_theo.price,
+            underlying_price: c_theo.underlying_price,
+            delta: c_theo.delta,
+            gamma: c_theo.gamma,
+            dividend: non_nan(c_theo.dividend),
+            interest: non_nan(c_theo.interest),
+        }
+    }
+}
+
+impl TheoPriceData {
+    /// The theo price as the C API's struct, with NaN for `None`.
+    pub fn to_raw(&self) -> dxf_theo_price_t {
+        dxf_theo_price_t {
+            time: self.time,
+            price: self.price,
+            underlying_price: self.underlying_price,
+            delta: self.delta,
+            gamma: self.gamma,
+            dividend: self.dividend.unwrap_or(f64::NAN),
+            interest: self.interest.unwrap_or(f64::NAN),
+        }
+    }
+}
+
+// A Rustified dxf_underlying_t, with `None` for the front and back month volatilities when
+// unavailable.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct UnderlyingData {
+    /// 30-day implied volatility for this underlying based on VIX methodology
+    pub volatility: dxf_double_t,
+    /// Front month implied volatility for this underlying based on VIX methodology
+    pub front_volatility: Option<dxf_double_t>,
+    /// Back month implied volatility for this underlying based on VIX methodology
+    pub back_volatility: Option<dxf_double_t>,
+    /// Call options traded volume for a day
+    pub call_volume: dxf_double_t,
+    /// Put options traded volume for a day
+    pub put_volume: dxf_double_t,
+    /// Options traded volume for a day
+    pub option_volume: dxf_double_t,
+    /// Ratio of put options traded volume to call options traded volume for a day
+    pub put_call_ratio: dxf_double_t,
+}
+
+impl From<&dxf_underlying_t> for UnderlyingData {
+    fn from(c_underlying: &dxf_underlying_t) -> Self {
+        Self {
+            volatility: c_underlying.volatility,
+            front_volatility: non_nan(c_underlying.front_volatility),
+            back_volatility: non_nan(c_underlying.back_volatility),
+            call_volume: c_underlying.call_volume,
+            put_volume: c_underlying.put_volume,
+            option_volume: c_underlying.option_volume,
+            put_call_ratio: c_underlying.put_call_ratio,
+        }
+    }
+}
+
+impl UnderlyingData {
+    /// The underlying as the C API's struct, with NaN for `None`.
+    pub fn to_raw(&self) -> dxf_underlying_t {
+        dxf_underlying_t {
+            volatility: self.volatility,
+            front_volatility: self.front_volatility.unwrap_or(f64::NAN),
+            back_volatility: self.back_volatility.unwrap_or(f64::NAN),
+            call_volume: self.call_volume,
+            put_volume: self.put_volume,
+            option_volume: self.option_volume,
+            put_call_ratio: self.put_call_ratio,
+        }
+    }
+}
+
+/// The bid or ask of a quote.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct QuoteSide {
+    /// NaN if not quoted
+    pub price: dxf_double_t,
+    pub size: dxf_double_t,
+    /// Exchange code of the side, or '\0' if none
+    pub exchange: char,
+    /// Time of the last change to the side
+    pub time: dxf_long_t,
+}
+
+impl QuoteSide {
+    /// Whether the side has a price.
+    pub fn is_quoted(&self) -> bool {
+        self.price.is_finite() && self.price > 0.0
+    }
+}
+
+// A Rustified dxf_quote_t, with its bid and ask as `QuoteSide`s.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct QuoteData {
+    /// Time of the last bid or ask change
+    pub time: dxf_long_t,
+    /// Sequence number of this quote, to distinguish quotes within the same `time`
+    pub sequence: dxf_int_t,
+    /// Microseconds and nanoseconds part of the time of the last bid or ask change
+    pub time_nanos: dxf_int_t,
+    pub bid: QuoteSide,
+    pub ask: QuoteSide,
+    /// Scope of this quote, `Composite` or `Regional`
+    pub scope: Scope,
+}
+
+impl From<&dxf_quote_t> for QuoteData {
+    fn from(c_quote: &dxf_quote_t) -> Self {
+        Self {
+            time: c_quote.time,
+            sequence: c_quote.sequence,
+            time_nanos: c_quote.time_nanos,
+            bid: QuoteSide {
+                price: c_quote.bid_price,
+                size: c_quote.bid_size,
+                exchange: utf::decode_char(c_quote.bid_exchange_code),
+                time: c_quote.bid_time,
+            },
+            ask: QuoteSide {
+                price: c_quote.ask_price,
+                size: c_quote.ask_size,
+                exchange: utf::decode_char(c_quote.ask_exchange_code),
+                time: c_quote.ask_time,
+            },
+            scope: c_quote.scope.try_into().unwrap_or_default(),
+        }
+    }
+}
+
+impl QuoteData {
+    /// The midpoint of the bid and ask, if both are quoted.
+    pub fn mid(&self) -> Option<f64> {
+        self.two_sided()
+            .then_some((self.bid.price + self.ask.price) / 2.0)
+    }
+
+    /// The ask less the bid, if both are quoted.
+    pub fn spread(&self) -> Option<f64> {
+        self.two_sided().then_some(self.ask.price - self.bid.price)
+    }
+
+    fn two_sided(&self) -> bool {
+        self.bid.is_quoted() && self.ask.is_quoted()
+    }
+
+    /// The quote as the C API's struct.
+    pub fn to_raw(&self) -> dxf_quote_t {
+        dxf_quote_t {
+            time: self.time,
+            sequence: self.sequence,
+            time_nanos: self.time_nanos,
+            bid_time: self.bid.time,
+            bid_exchange_code: self.bid.exchange as dxf_char_t,
+            bid_price: self.bid.price,
+            bid_size: self.bid.size,
+            ask_time: self.ask.time,
+            ask_exchange_code: self.ask.exchange as dxf_char_t,
+            ask_price: self.ask.price,
+            ask_size: self.ask.size,
+            scope: self.scope.into(),
         }
     }
 }
 
 // dx_spread_order_t
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SpreadOrderData {
     pub index: dxf_int_t,
     pub time: dxf_int_t,
@@ -393,18 +1289,27 @@ pub struct SpreadOrderData {
     pub size: dxf_double_t,
     pub executed_size: dxf_double_t,
     pub count: dxf_double_t,
+    /// The record's raw flags, which `action`, `exchange_code`, `side` and `scope` are decoded
+    /// from.
     pub flags: dxf_int_t,
     pub trade_id: dxf_long_t,
     pub trade_price: dxf_double_t,
     pub trade_size: dxf_double_t,
     pub spread_symbol: String,
+    /// Order action, decoded from the record's flags.
+    pub action: Action,
+    /// Exchange code, decoded from the record's flags.
+    pub exchange_code: char,
+    /// Side, decoded from the record's flags.
+    pub side: Side,
+    /// Scope, decoded from the record's flags.
+    pub scope: Scope,
 }
 
 impl From<&dx_spread_order_t> for SpreadOrderData {
     fn from(c_spread_order: &dx_spread_order_t) -> Self {
-        let spread_symbol = unsafe {
-            WideCString::from_ptr_str(c_spread_order.spread_symbol as *const _).to_string_lossy()
-        };
+        let spread_symbol = unsafe { utf::decode_field(c_spread_order.spread_symbol) };
+        let flags = OrderFlags::from_raw(c_spread_order.flags);
         Self {
             index: c_spread_order.index,
             time: c_spread_order.time,
@@ -422,11 +1327,16 @@ impl From<&dx_spread_order_t> for SpreadOrderData {
             trade_price: c_spread_order.trade_price,
             trade_size: c_spread_order.trade_size,
             spread_symbol,
+            action: flags.action,
+            exchange_code: flags.exchange_code,
+            side: flags.side,
+            scope: flags.scope,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ConfigurationData {
     pub version: dxf_int_t,
     pub object: String,
@@ -434,8 +1344,7 @@ pub struct ConfigurationData {
 
 impl From<&dxf_configuration_t> for ConfigurationData {
     fn from(c_config: &dxf_configuration_t) -> Self {
-        let object =
-            unsafe { WideCString::from_ptr_str(c_config.object as *const _).to_string_lossy() };
+        let object = unsafe { utf::decode_field(c_config.object) };
         Self {
             version: c_config.version,
             object,
@@ -456,25 +1365,97 @@ pub enum Error {
     #[error("Converting from WideCString")]
     UtfError(#[from] widestring::error::Utf16Error),
 
+    #[error("String contains an interior nul")]
+    ContainsNul,
+
+    #[error("Unexpected null pointer from the C API")]
+    NullPointer,
+
+    #[error("`{0}` failed")]
+    CallFailed(&'static str),
+
+    #[error("`{call}` failed: {message} ({code})")]
+    DxFeed {
+        call: &'static str,
+        code: DxErrorCode,
+        message: String,
+    },
+
+    #[error("Timed out")]
+    Timeout,
+
+    #[error("Invalid candle period: {0:?}")]
+    InvalidPeriod(Duration),
+
+    #[error("Invalid {0}: `{1}`")]
+    InvalidValue(&'static str, c_uint),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[cfg(feature = "dxlink")]
+    #[error("dxLink: {0}")]
+    DxLink(String),
+
+    #[cfg(feature = "avro")]
+    #[error("Avro: {0}")]
+    Avro(Box<apache_avro::Error>),
+
+    #[cfg(feature = "grpc")]
+    #[error("gRPC: {0}")]
+    Grpc(String),
+
+    #[cfg(feature = "kafka")]
+    #[error("Kafka: {0}")]
+    Kafka(String),
+
+    #[cfg(feature = "nats")]
+    #[error("NATS: {0}")]
+    Nats(String),
+
+    #[cfg(feature = "redis")]
+    #[error("Redis: {0}")]
+    Redis(String),
+
+    #[cfg(feature = "parquet")]
+    #[error("Parquet: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
+
+    #[cfg(feature = "proto")]
+    #[error("Protobuf message is missing `{0}`")]
+    MissingProtoField(&'static str),
+
     #[error("Unknown error")]
     Unknown,
 }
 
+impl Error {
+    /// The class of the C API error behind this one, if any, e.g. to retry only on
+    /// `DxErrorClass::Network`.
+    pub fn dx_error_class(&self) -> Option<DxErrorClass> {
+        match self {
+            Error::DxFeed { code, .. } => Some(code.class()),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum EventData {
-    Trade(dxf_trade_t),
-    Quote(dxf_quote_t),
-    Summary(dxf_summary_t),
+    Trade(TradeData),
+    Quote(QuoteData),
+    Summary(SummaryData),
     Profile(ProfileEventData),
     Order(OrderEventData),
     TimeAndSale(TimeAndSaleData),
-    Candle(dxf_candle_t),
-    TradeETH(dxf_trade_eth_t),
+    Candle(CandleData),
+    TradeETH(TradeEthData),
     SpreadOrder(SpreadOrderData),
-    Greeks(dxf_greeks_t),
-    TheoPrice(dxf_theo_price_t),
-    Underlying(dxf_underlying_t),
-    Series(dxf_series_t),
+    Greeks(GreeksData),
+    TheoPrice(TheoPriceData),
+    Underlying(UnderlyingData),
+    Series(SeriesData),
     Configuration(ConfigurationData),
 }
 
@@ -500,22 +1481,37 @@ impl EventData {
 }
 
 impl EventData {
+    /// Convert the C struct of `event_type` at `data`. Invalid strings in it are handled
+    /// according to `utf_strategy`.
     pub fn try_get_event_data(
         event_type: c_int,
         data: *const dxf_event_data_t,
     ) -> Result<EventData, Error> {
+        if data.is_null() {
+            return Err(Error::NullPointer);
+        }
+        // Discard any error left by a direct `From` conversion
+        utf::take_strict_error();
+        let event_data = Self::convert(event_type, data)?;
+        match utf::take_strict_error() {
+            Some(e) => Err(e),
+            None => Ok(event_data),
+        }
+    }
+
+    fn convert(event_type: c_int, data: *const dxf_event_data_t) -> Result<EventData, Error> {
         match event_type {
             DXF_ET_TRADE => {
                 let c_trade: &dxf_trade_t = unsafe { &*(data as *mut dxf_trade_t) };
-                Ok(EventData::Trade(c_trade.clone()))
+                Ok(EventData::Trade(TradeData::from(c_trade)))
             }
             DXF_ET_QUOTE => {
                 let c_quote: &dxf_quote_t = unsafe { &*(data as *mut dxf_quote_t) };
-                Ok(EventData::Quote(c_quote.clone()))
+                Ok(EventData::Quote(QuoteData::from(c_quote)))
             }
             DXF_ET_SUMMARY => {
                 let c_summary: &dxf_summary_t = unsafe { &*(data as *mut dxf_summary_t) };
-                Ok(EventData::Summary(c_summary.clone()))
+                Ok(EventData::Summary(SummaryData::from(c_summary)))
             }
             DXF_ET_PROFILE => {
                 let c_profile: &dxf_profile_t = unsafe { &*(data as *mut dxf_profile_t) };
@@ -534,11 +1530,11 @@ impl EventData {
             }
             DXF_ET_CANDLE => {
                 let c_candle: &dxf_candle_t = unsafe { &*(data as *mut dxf_candle_t) };
-                Ok(EventData::Candle(c_candle.clone()))
+                Ok(EventData::Candle(CandleData::from(c_candle)))
             }
             DXF_ET_TRADE_ETH => {
                 let c_trade_eth: &dxf_trade_eth_t = unsafe { &*(data as *mut dxf_trade_eth_t) };
-                Ok(EventData::TradeETH(c_trade_eth.clone()))
+                Ok(EventData::TradeETH(TradeEthData::from(c_trade_eth)))
             }
             DXF_ET_SPREAD_ORDER => {
                 let c_spread_order: &dx_spread_order = unsafe { &*(data as *mut dx_spread_order) };
@@ -548,19 +1544,19 @@ impl EventData {
             }
             DXF_ET_GREEKS => {
                 let c_greeks: &dxf_greeks_t = unsafe { &*(data as *mut dxf_greeks_t) };
-                Ok(EventData::Greeks(c_greeks.clone()))
+                Ok(EventData::Greeks(GreeksData::from(c_greeks)))
             }
             DXF_ET_THEO_PRICE => {
                 let c_theo: &dxf_theo_price_t = unsafe { &*(data as *mut dxf_theo_price_t) };
-                Ok(EventData::TheoPrice(c_theo.clone()))
+                Ok(EventData::TheoPrice(TheoPriceData::from(c_theo)))
             }
             DXF_ET_UNDERLYING => {
                 let c_underlying: &dxf_underlying_t = unsafe { &*(data as *mut dxf_underlying_t) };
-                Ok(EventData::Underlying(c_underlying.clone()))
+                Ok(EventData::Underlying(UnderlyingData::from(c_underlying)))
             }
             DXF_ET_SERIES => {
                 let c_series: &dxf_series_t = unsafe { &*(data as *mut dxf_series_t) };
-                Ok(EventData::Series(c_series.clone()))
+                Ok(EventData::Series(SeriesData::from(c_series)))
             }
             DXF_ET_CONFIGURATION => {
                 let c_configuration: &dxf_configuration_t =
@@ -574,17 +1570,79 @@ impl EventData {
     }
 }
 
-unsafe impl Send for EventData {}
-unsafe impl Sync for EventData {}
+impl AsRef<EventData> for EventData {
+    fn as_ref(&self) -> &EventData {
+        self
+    }
+}
+
+// Defines an `as_*` accessor per `EventData` variant, and `TryFrom<EventData>` for its payload
+// type, failing with `Error::Invalid` of the event's actual type.
+macro_rules! event_data_accessors {
+    ($($variant:ident($payload:ty) => $method:ident;)*) => {
+        impl EventData {
+            $(
+                #[doc = concat!("The payload if this is `EventData::", stringify!($variant), "`.")]
+                pub fn $method(&self) -> Option<&$payload> {
+                    match self {
+                        Self::$variant(data) => Some(data),
+                        _ => None,
+                    }
+                }
+            )*
+        }
+
+        $(
+            impl TryFrom<EventData> for $payload {
+                type Error = Error;
+
+                fn try_from(data: EventData) -> Result<Self, Error> {
+                    match data {
+                        EventData::$variant(data) => Ok(data),
+                        other => Err(Error::Invalid(other.get_event_type())),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+event_data_accessors! {
+    Trade(TradeData) => as_trade;
+    Quote(QuoteData) => as_quote;
+    Summary(SummaryData) => as_summary;
+    Profile(ProfileEventData) => as_profile;
+    Order(OrderEventData) => as_order;
+    TimeAndSale(TimeAndSaleData) => as_time_and_sale;
+    Candle(CandleData) => as_candle;
+    TradeETH(TradeEthData) => as_trade_eth;
+    SpreadOrder(SpreadOrderData) => as_spread_order;
+    Greeks(GreeksData) => as_greeks;
+    TheoPrice(TheoPriceData) => as_theo_price;
+    Underlying(UnderlyingData) => as_underlying;
+    Series(SeriesData) => as_series;
+    Configuration(ConfigurationData) => as_configuration;
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Event {
     pub sym: String,
     pub data: EventData,
 }
 
-unsafe impl Send for Event {}
-unsafe impl Sync for Event {}
+// Every `EventData` variant is a plain-data C struct or a fully-owned Rust copy, so `Event` is
+// `Send` and `Sync` without asserting it; this stops compiling if a variant ever holds a pointer.
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Event>();
+};
+
+impl AsRef<EventData> for Event {
+    fn as_ref(&self) -> &EventData {
+        &self.data
+    }
+}
 
 impl Event {
     pub fn new(sym: String, data: EventData) -> Self {
@@ -596,8 +1654,7 @@ impl Event {
         raw_sym: dxf_const_string_t,
         data: *const dxf_event_data_t,
     ) -> Result<Self, Error> {
-        let c_sym = unsafe { WideCString::from_ptr_str(raw_sym as *const _) };
-        let sym = c_sym.to_string()?;
+        let sym = unsafe { utf::decode(raw_sym as *const _) }?;
         let event_data = EventData::try_get_event_data(event_type, data)?;
         Ok(Event::new(sym, event_data))
     }
@@ -632,6 +1689,170 @@ mod tests {
         for (name, expected) in name2expected {
             let result = EventType::from_str(name);
             assert_eq!(result, Ok(expected));
+            assert_eq!(expected.to_string(), name);
+            assert_eq!(EventType::from_str(&name.to_lowercase()), Ok(expected));
+            assert_eq!(EventType::from_str(&name.to_uppercase()), Ok(expected));
+        }
+        assert!(EventType::from_str("Quotes").is_err());
+        assert!(EventType::iter().eq(name2expected.map(|(_, expected)| expected)));
+    }
+
+    #[test]
+    fn order_chars_as_strings() {
+        let empty: [dxf_char_t; 1] = [0];
+        let mut c_order = dxf_order_t {
+            exchange_code: 'Q' as dxf_char_t,
+            ..unsafe { std::mem::zeroed() }
+        };
+        for (dst, c) in c_order.source.iter_mut().zip("NTV".chars()) {
+            *dst = c as dxf_char_t;
         }
+        c_order.__bindgen_anon_1.market_maker = empty.as_ptr() as _;
+        let order = OrderEventData::from(&c_order);
+        assert_eq!(order.exchange_code, 'Q');
+        let json = serde_json::to_value(&order).unwrap();
+        assert_eq!(json["exchange_code"], "Q");
+        assert_eq!(json["source"], "NTV");
+    }
+
+    #[test]
+    fn trade_round_trip() {
+        let c_trade = dxf_trade_t {
+            exchange_code: 'D' as dxf_char_t,
+            price: 101.25,
+            raw_flags: 0b1011,
+            direction: dxf_direction_t_dxf_dir_up,
+            is_eth: 1,
+            scope: dxf_order_scope_t_dxf_osc_regional,
+            ..unsafe { std::mem::zeroed() }
+        };
+        let trade = TradeData::from(&c_trade);
+        assert_eq!(trade.exchange_code, 'D');
+        assert_eq!(trade.direction, Direction::Up);
+        assert!(trade.is_eth);
+        assert_eq!(trade.scope, Scope::Regional);
+        assert_eq!(trade.flags().direction, Direction::Up);
+        assert_eq!(trade.to_raw(), c_trade);
+    }
+
+    #[test]
+    fn spread_order_flags() {
+        let flags = OrderFlags {
+            scope: Scope::Order,
+            side: Side::Sell,
+            exchange_code: 'X',
+            action: Action::Modify,
+        };
+        let c_spread_order = dx_spread_order_t {
+            flags: flags.to_raw(),
+            spread_symbol: std::ptr::null(),
+            ..unsafe { std::mem::zeroed() }
+        };
+        let spread_order = SpreadOrderData::from(&c_spread_order);
+        assert_eq!(
+            (spread_order.side, spread_order.scope, spread_order.action),
+            (Side::Sell, Scope::Order, Action::Modify)
+        );
+        assert_eq!(spread_order.exchange_code, 'X');
+        assert_eq!(spread_order.flags, c_spread_order.flags);
+    }
+
+    #[test]
+    fn event_data_downcasts() {
+        let quote = QuoteData {
+            sequence: 3,
+            ..Default::default()
+        };
+        let data = EventData::Quote(quote);
+        assert_eq!(data.as_quote(), Some(&quote));
+        assert!(data.as_trade().is_none());
+        assert_eq!(QuoteData::try_from(data.clone()).unwrap(), quote);
+        assert!(matches!(
+            TradeData::try_from(data),
+            Err(Error::Invalid(DXF_ET_QUOTE))
+        ));
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn event_json_schema() {
+        let schema = serde_json::to_value(schemars::schema_for!(Event)).unwrap();
+        let definitions = &schema["definitions"];
+        assert!(definitions["EventData"]["oneOf"].is_array());
+        assert_eq!(
+            definitions["QuoteData"]["properties"]["bid"]["$ref"],
+            "#/definitions/QuoteSide"
+        );
+        assert!(definitions["Side"]["enum"].is_array());
+    }
+
+    #[test]
+    fn quote_sides() {
+        let c_quote = dxf_quote_t {
+            bid_price: 100.0,
+            bid_exchange_code: 'Q' as dxf_char_t,
+            ask_price: 100.5,
+            ask_time: 7,
+            ..unsafe { std::mem::zeroed() }
+        };
+        let quote = QuoteData::from(&c_quote);
+        assert_eq!((quote.bid.exchange, quote.ask.time), ('Q', 7));
+        assert_eq!(quote.mid(), Some(100.25));
+        assert_eq!(quote.spread(), Some(0.5));
+        assert_eq!(quote.to_raw(), c_quote);
+        let one_sided = QuoteData {
+            ask: QuoteSide::default(),
+            ..quote
+        };
+        assert_eq!(one_sided.mid(), None);
+    }
+
+    #[test]
+    fn candle_nan_fields() {
+        let c_candle = dxf_candle_t {
+            close: 10.0,
+            vwap: 9.5,
+            open_interest: f64::NAN,
+            imp_volatility: f64::NAN,
+            ..unsafe { std::mem::zeroed() }
+        };
+        let candle = CandleData::from(&c_candle);
+        assert_eq!(candle.vwap, Some(9.5));
+        assert_eq!(candle.open_interest, None);
+        let json = serde_json::to_value(candle).unwrap();
+        assert!(json["imp_volatility"].is_null());
+        assert!(candle.to_raw().open_interest.is_nan());
+    }
+
+    #[test]
+    fn greeks_snapshot_flags() {
+        let c_greeks = dxf_greeks_t {
+            event_flags: (EventFlags::SNAPSHOT_BEGIN | EventFlags::SNAPSHOT_SNIP).bits(),
+            delta: 0.5,
+            ..unsafe { std::mem::zeroed() }
+        };
+        let greeks = GreeksData::from(&c_greeks);
+        assert!(greeks.is_snapshot_begin() && greeks.is_snapshot_complete());
+        assert!(!greeks.is_snapshot_end() && !greeks.is_remove_event());
+        assert_eq!(greeks.to_raw(), c_greeks);
+    }
+
+    #[test]
+    fn underlying_and_theo_nan_fields() {
+        let underlying = UnderlyingData::from(&dxf_underlying_t {
+            volatility: 0.2,
+            front_volatility: 0.25,
+            back_volatility: f64::NAN,
+            ..unsafe { std::mem::zeroed() }
+        });
+        assert_eq!(underlying.front_volatility, Some(0.25));
+        assert_eq!(underlying.back_volatility, None);
+        let theo = TheoPriceData::from(&dxf_theo_price_t {
+            dividend: f64::NAN,
+            interest: 0.05,
+            ..unsafe { std::mem::zeroed() }
+        });
+        assert_eq!((theo.dividend, theo.interest), (None, Some(0.05)));
+        assert!(theo.to_raw().dividend.is_nan());
     }
 }
@@ -0,0 +1,240 @@
+use crate::last_error::call_failed;
+use crate::{
+    dxf_attach_event_listener, dxf_detach_event_listener, dxf_event_listener_t, dxf_subscription_t,
+    CandleData, ConfigurationData, Error, Event, EventData, GreeksData, OrderEventData,
+    ProfileEventData, QuoteData, SeriesData, SpreadOrderData, Subscription, SummaryData,
+    TheoPriceData, TimeAndSaleData, TradeData, TradeEthData, UnderlyingData, DXF_SUCCESS,
+};
+use std::os::raw::{c_int, c_void};
+
+// Defines a listener trait per `EventData` variant (implemented for matching closures too) and
+// a `TypedDispatcher` with an optional slot for each.
+macro_rules! typed_listeners {
+    ($(
+        $variant:ident => $trait:ident::$method:ident($payload:ty), $field:ident, $setter:ident;
+    )*) => {
+        $(
+            #[doc = concat!("Receives `EventData::", stringify!($variant), "` payloads.")]
+            pub trait $trait {
+                fn $method(&mut self, sym: &str, data: &$payload);
+            }
+
+            impl<F: FnMut(&str, &$payload)> $trait for F {
+                fn $method(&mut self, sym: &str, data: &$payload) {
+                    self(sym, data)
+                }
+            }
+        )*
+
+        /// Routes each event's payload to the typed listener registered for its type.
+        #[derive(Default)]
+        pub struct TypedDispatcher {
+            $($field: Option<Box<dyn $trait + Send>>,)*
+        }
+
+        impl TypedDispatcher {
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            $(
+                pub fn $setter<L: $trait + Send + 'static>(mut self, listener: L) -> Self {
+                    self.$field = Some(Box::new(listener));
+                    self
+                }
+            )*
+
+            /// Deliver `event` to its listener. Returns false if none is registered for its type.
+            pub fn dispatch(&mut self, event: &Event) -> bool {
+                match &event.data {
+                    $(
+                        EventData::$variant(data) => match self.$field.as_mut() {
+                            Some(listener) => {
+                                listener.$method(&event.sym, data);
+                                true
+                            }
+                            None => false,
+                        },
+                    )*
+                }
+            }
+        }
+    };
+}
+
+typed_listeners! {
+    Trade => TradeListener::on_trade(TradeData), trade, with_trade;
+    Quote => QuoteListener::on_quote(QuoteData), quote, with_quote;
+    Summary => SummaryListener::on_summary(SummaryData), summary, with_summary;
+    Profile => ProfileListener::on_profile(ProfileEventData), profile, with_profile;
+    Order => OrderListener::on_order(OrderEventData), order, with_order;
+    TimeAndSale => TimeAndSaleListener::on_time_and_sale(TimeAndSaleData), time_and_sale, with_time_and_sale;
+    Candle => CandleListener::on_candle(CandleData), candle, with_candle;
+    TradeETH => TradeEthListener::on_trade_eth(TradeEthData), trade_eth, with_trade_eth;
+    SpreadOrder => SpreadOrderListener::on_spread_order(SpreadOrderData), spread_order, with_spread_order;
+    Greeks => GreeksListener::on_greeks(GreeksData), greeks, with_greeks;
+    TheoPrice => TheoPriceListener::on_theo_price(TheoPriceData), theo_price, with_theo_price;
+    Underlying => UnderlyingListener::on_underlying(UnderlyingData), underlying, with_underlying;
+    Series => SeriesListener::on_series(SeriesData), series, with_series;
+    Configuration => ConfigurationListener::on_configuration(ConfigurationData), configuration, with_configuration;
+}
+
+impl Subscription {
+    /// Attach `dispatcher` as this subscription's listener. Events that fail conversion, or have
+    /// no registered listener, are dropped.
+    pub fn attach_typed(&mut self, mut dispatcher: TypedDispatcher) -> Result<(), Error> {
+        self.attach(move |event| {
+            if let Ok(event) = event {
+                dispatcher.dispatch(&event);
+            }
+        })
+    }
+}
+
+/// A listener usable with the raw `dxf_attach_event_listener` callback model, where `self` is
+/// passed as `user_data`. Implemented by `#[dxfeed::listener]` (the `macros` feature).
+///
+/// # Safety
+/// `trampoline` must treat `user_data` as a `*mut Self`.
+pub unsafe trait RawListener: Sized {
+    /// The `extern "C"` callback to register for this type.
+    fn trampoline() -> dxf_event_listener_t;
+
+    /// Attach `self` to `subscription`.
+    ///
+    /// # Safety
+    /// `subscription` must be a valid handle, and `self` must neither move nor be dropped until
+    /// it is detached or the subscription is closed.
+    unsafe fn attach_raw(&mut self, subscription: dxf_subscription_t) -> Result<(), Error> {
+        let user_data = self as *mut Self as *mut c_void;
+        if dxf_attach_event_listener(subscription, Self::trampoline(), user_data)
+            != DXF_SUCCESS as c_int
+        {
+            return Err(call_failed("dxf_attach_event_listener"));
+        }
+        Ok(())
+    }
+
+    /// Detach this type's trampoline from `subscription`.
+    ///
+    /// # Safety
+    /// `subscription` must be a valid handle.
+    unsafe fn detach_raw(subscription: dxf_subscription_t) -> Result<(), Error> {
+        if dxf_detach_event_listener(subscription, Self::trampoline()) != DXF_SUCCESS as c_int {
+            return Err(call_failed("dxf_detach_event_listener"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct ProfileCounter(Arc<AtomicUsize>);
+
+    impl ProfileListener for ProfileCounter {
+        fn on_profile(&mut self, _sym: &str, _data: &ProfileEventData) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn dispatch_to_typed_listener() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let mut dispatcher = TypedDispatcher::new()
+            .with_profile(ProfileCounter(count.clone()))
+            .with_configuration(|sym: &str, data: &ConfigurationData| {
+                assert_eq!(sym, "AAPL");
+                assert_eq!(data.version, 7);
+            });
+
+        let profile = Event::new(
+            "AAPL".to_string(),
+            EventData::Profile(ProfileEventData::default()),
+        );
+        let config = Event::new(
+            "AAPL".to_string(),
+            EventData::Configuration(ConfigurationData {
+                version: 7,
+                object: String::new(),
+            }),
+        );
+        assert!(dispatcher.dispatch(&profile));
+        assert!(dispatcher.dispatch(&config));
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+}
+
+#[cfg(all(test, feature = "macros"))]
+mod macro_tests {
+    use crate::{
+        dxf_configuration_t, dxf_event_data_t, dxf_underlying_t, ConfigurationData, Error, Event,
+        RawListener, DXF_ET_CONFIGURATION, DXF_ET_UNDERLYING,
+    };
+    use std::os::raw::{c_int, c_void};
+    use widestring::WideCString;
+
+    #[derive(Default)]
+    struct Recorder {
+        configurations: Vec<i32>,
+        others: usize,
+        errors: usize,
+    }
+
+    #[crate::listener]
+    impl Recorder {
+        fn on_configuration(&mut self, _sym: &str, data: &ConfigurationData) {
+            self.configurations.push(data.version);
+        }
+
+        fn on_event(&mut self, _event: &Event) {
+            self.others += 1;
+        }
+
+        fn on_error(&mut self, _error: Error) {
+            self.errors += 1;
+        }
+    }
+
+    fn call<T>(recorder: &mut Recorder, event_type: c_int, sym: &WideCString, data: &T) {
+        let trampoline = Recorder::trampoline().unwrap();
+        unsafe {
+            trampoline(
+                event_type,
+                sym.as_ptr() as *const _,
+                data as *const T as *const dxf_event_data_t,
+                1,
+                recorder as *mut Recorder as *mut c_void,
+            )
+        };
+    }
+
+    #[test]
+    fn listener_macro_routes_events() {
+        let sym = WideCString::from_str("AAPL").unwrap();
+        let object = WideCString::from_str("{}").unwrap();
+        let config = dxf_configuration_t {
+            version: 7,
+            object: object.as_ptr() as *mut _,
+        };
+        let underlying = dxf_underlying_t {
+            volatility: 0.2,
+            front_volatility: 0.2,
+            back_volatility: 0.2,
+            call_volume: 1.0,
+            put_volume: 1.0,
+            option_volume: 2.0,
+            put_call_ratio: 1.0,
+        };
+        let mut recorder = Recorder::default();
+        call(&mut recorder, DXF_ET_CONFIGURATION, &sym, &config);
+        call(&mut recorder, DXF_ET_UNDERLYING, &sym, &underlying);
+        call(&mut recorder, 0, &sym, &underlying);
+        assert_eq!(recorder.configurations, vec![7]);
+        assert_eq!(recorder.others, 1);
+        assert_eq!(recorder.errors, 1);
+    }
+}
@@ -0,0 +1,183 @@
+//! The C API's file logger, and with the `log` feature, a bridge forwarding its entries into the
+//! `log` crate.
+
+use crate::last_error::call_failed;
+use crate::{dxf_initialize_logger_v2, Error, DXF_SUCCESS};
+use std::ffi::CString;
+use std::os::raw::c_int;
+use std::path::Path;
+
+/// Options for `initialize_logger`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoggerOptions {
+    /// Truncate the file rather than append to it
+    pub rewrite_file: bool,
+    /// Suffix times with their GMT offset
+    pub show_timezone: bool,
+    /// Also log debug and trace entries
+    pub verbose: bool,
+    /// Log portions of the data sent and received
+    pub log_data_transfer: bool,
+}
+
+/// Start the C API's logger, writing to the file at `path`. Applies process-wide.
+pub fn initialize_logger<P: AsRef<Path>>(path: P, options: LoggerOptions) -> Result<(), Error> {
+    let path = CString::new(path.as_ref().to_string_lossy().into_owned())
+        .map_err(|_| Error::ContainsNul)?;
+    let result = unsafe {
+        dxf_initialize_logger_v2(
+            path.as_ptr(),
+            options.rewrite_file as c_int,
+            options.show_timezone as c_int,
+            options.verbose as c_int,
+            options.log_data_transfer as c_int,
+        )
+    };
+    if result != DXF_SUCCESS as c_int {
+        return Err(call_failed("dxf_initialize_logger_v2"));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "log")]
+pub use bridge::{forward_log, LogForwarder, LOG_TARGET};
+
+#[cfg(feature = "log")]
+mod bridge {
+    use super::{initialize_logger, LoggerOptions};
+    use crate::Error;
+    use log::Level;
+    use std::fs::File;
+    use std::io::{Read, Seek, SeekFrom};
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread::JoinHandle;
+    use std::time::Duration;
+
+    const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// Target of the forwarded records, for filtering.
+    pub const LOG_TARGET: &str = "dxfeed_c_api";
+
+    /// Tails the C API's log file on a thread, forwarding each entry as a `log` record. Stops
+    /// (after forwarding what's been written) when dropped.
+    pub struct LogForwarder {
+        stop: Arc<AtomicBool>,
+        thread: Option<JoinHandle<()>>,
+    }
+
+    impl Drop for LogForwarder {
+        fn drop(&mut self) {
+            self.stop.store(true, Ordering::Relaxed);
+            if let Some(thread) = self.thread.take() {
+                let _ = thread.join();
+            }
+        }
+    }
+
+    /// Start the C API's logger writing to `path`, rewritten, and forward its entries to `log`
+    /// under the target "dxfeed_c_api", at the level each entry was written with.
+    pub fn forward_log<P: AsRef<Path>>(
+        path: P,
+        options: LoggerOptions,
+    ) -> Result<LogForwarder, Error> {
+        let path: PathBuf = path.as_ref().into();
+        let options = LoggerOptions {
+            rewrite_file: true,
+            ..options
+        };
+        initialize_logger(&path, options)?;
+        let file = File::open(&path)?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopping = stop.clone();
+        let thread = std::thread::Builder::new()
+            .name("dxfeed-log".to_string())
+            .spawn(move || tail(file, &stopping))?;
+        Ok(LogForwarder {
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    fn tail(mut file: File, stop: &AtomicBool) {
+        let mut pending = String::new();
+        let mut buf = Vec::new();
+        loop {
+            let stopping = stop.load(Ordering::Relaxed);
+            buf.clear();
+            if file.read_to_end(&mut buf).is_err() {
+                // E.g. truncated underneath us; start over from the end
+                let _ = file.seek(SeekFrom::End(0));
+            }
+            if buf.is_empty() {
+                // Entries are written and flushed whole, each starting with a newline, so the
+                // text after the last one is complete once nothing more arrives
+                if !pending.is_empty() {
+                    emit(&pending);
+                    pending.clear();
+                }
+                if stopping {
+                    return;
+                }
+                std::thread::sleep(POLL_INTERVAL);
+                continue;
+            }
+            pending.push_str(&String::from_utf8_lossy(&buf));
+            if let Some(end) = pending.rfind('\n') {
+                pending[..end].lines().for_each(emit);
+                pending.drain(..=end);
+            }
+        }
+    }
+
+    fn emit(line: &str) {
+        if let Some((level, message)) = parse_line(line) {
+            log::log!(target: LOG_TARGET, level, "{}", message);
+        }
+    }
+
+    /// The level and message of an entry like
+    /// "16.10.2026 12:00:00.123 [00001a2b] [W] message".
+    pub(super) fn parse_line(line: &str) -> Option<(Level, &str)> {
+        let line = line.trim_end();
+        if line.is_empty() {
+            return None;
+        }
+        let Some(rest) = line.find("] ").map(|end| &line[end + 2..]) else {
+            return Some((Level::Info, line));
+        };
+        let level = match rest.get(..4) {
+            Some("[E] ") => Level::Error,
+            Some("[W] ") => Level::Warn,
+            Some("[I] ") => Level::Info,
+            Some("[D] ") => Level::Debug,
+            Some("[T] ") => Level::Trace,
+            _ => return Some((Level::Info, rest)),
+        };
+        Some((level, &rest[4..]))
+    }
+}
+
+#[cfg(all(test, feature = "log"))]
+mod tests {
+    use super::bridge::parse_line;
+    use log::Level;
+
+    #[test]
+    fn parse_entries() {
+        assert_eq!(
+            parse_line("16.10.2026 12:00:00.123 [00001a2b] [W] Reconnecting"),
+            Some((Level::Warn, "Reconnecting"))
+        );
+        assert_eq!(
+            parse_line("16.10.2026 12:00:00.123 GMT+02 [00001a2b] [E] Host not found (43)"),
+            Some((Level::Error, "Host not found (43)"))
+        );
+        assert_eq!(
+            parse_line("16.10.2026 12:00:00.123 [00001a2b] Logging started"),
+            Some((Level::Info, "Logging started"))
+        );
+        assert_eq!(parse_line(""), None);
+    }
+}
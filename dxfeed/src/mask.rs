@@ -0,0 +1,167 @@
+use crate::{
+    EventType, DXF_ET_CANDLE, DXF_ET_CONFIGURATION, DXF_ET_GREEKS, DXF_ET_ORDER, DXF_ET_PROFILE,
+    DXF_ET_QUOTE, DXF_ET_SERIES, DXF_ET_SPREAD_ORDER, DXF_ET_SUMMARY, DXF_ET_THEO_PRICE,
+    DXF_ET_TIME_AND_SALE, DXF_ET_TRADE, DXF_ET_TRADE_ETH, DXF_ET_UNDERLYING,
+};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt;
+use std::iter::FromIterator;
+use std::ops::{BitAnd, BitOr, BitOrAssign};
+use std::os::raw::c_int;
+
+/// A set of event types, as the C API's `DXF_ET_*` mask that subscriptions are created with,
+/// e.g. `EventTypeMask::QUOTE | EventTypeMask::TRADE`.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EventTypeMask(pub c_int);
+
+impl EventTypeMask {
+    pub const TRADE: EventTypeMask = EventTypeMask(DXF_ET_TRADE);
+    pub const QUOTE: EventTypeMask = EventTypeMask(DXF_ET_QUOTE);
+    pub const SUMMARY: EventTypeMask = EventTypeMask(DXF_ET_SUMMARY);
+    pub const PROFILE: EventTypeMask = EventTypeMask(DXF_ET_PROFILE);
+    pub const ORDER: EventTypeMask = EventTypeMask(DXF_ET_ORDER);
+    pub const TIME_AND_SALE: EventTypeMask = EventTypeMask(DXF_ET_TIME_AND_SALE);
+    pub const CANDLE: EventTypeMask = EventTypeMask(DXF_ET_CANDLE);
+    pub const TRADE_ETH: EventTypeMask = EventTypeMask(DXF_ET_TRADE_ETH);
+    pub const SPREAD_ORDER: EventTypeMask = EventTypeMask(DXF_ET_SPREAD_ORDER);
+    pub const GREEKS: EventTypeMask = EventTypeMask(DXF_ET_GREEKS);
+    pub const THEO_PRICE: EventTypeMask = EventTypeMask(DXF_ET_THEO_PRICE);
+    pub const UNDERLYING: EventTypeMask = EventTypeMask(DXF_ET_UNDERLYING);
+    pub const SERIES: EventTypeMask = EventTypeMask(DXF_ET_SERIES);
+    pub const CONFIGURATION: EventTypeMask = EventTypeMask(DXF_ET_CONFIGURATION);
+
+    /// Every event type.
+    pub fn all() -> EventTypeMask {
+        EventType::iter().collect()
+    }
+
+    /// Every event type but Configuration, which carries feed metadata rather than market data.
+    pub fn all_market_events() -> EventTypeMask {
+        EventType::iter()
+            .filter(|event_type| *event_type != EventType::Configuration)
+            .collect()
+    }
+
+    pub fn bits(self) -> c_int {
+        self.0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Whether every type of `other` is set.
+    pub fn contains(self, other: EventTypeMask) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// The event types set, in bit order. Bits that are not an event type are skipped.
+    pub fn event_types(self) -> impl Iterator<Item = EventType> {
+        (0..c_int::BITS).filter_map(move |bit| EventType::try_from(self.0 & (1 << bit)).ok())
+    }
+}
+
+impl From<c_int> for EventTypeMask {
+    fn from(bits: c_int) -> Self {
+        EventTypeMask(bits)
+    }
+}
+
+impl From<EventTypeMask> for c_int {
+    fn from(mask: EventTypeMask) -> Self {
+        mask.0
+    }
+}
+
+impl From<EventType> for EventTypeMask {
+    fn from(event_type: EventType) -> Self {
+        EventTypeMask(event_type as c_int)
+    }
+}
+
+impl From<&[EventType]> for EventTypeMask {
+    fn from(event_types: &[EventType]) -> Self {
+        event_types.iter().copied().collect()
+    }
+}
+
+impl FromIterator<EventType> for EventTypeMask {
+    fn from_iter<I: IntoIterator<Item = EventType>>(event_types: I) -> Self {
+        event_types
+            .into_iter()
+            .fold(EventTypeMask::default(), |mask, event_type| {
+                mask | event_type.into()
+            })
+    }
+}
+
+impl BitOr for EventTypeMask {
+    type Output = EventTypeMask;
+
+    fn bitor(self, other: EventTypeMask) -> EventTypeMask {
+        EventTypeMask(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for EventTypeMask {
+    fn bitor_assign(&mut self, other: EventTypeMask) {
+        self.0 |= other.0;
+    }
+}
+
+impl BitAnd for EventTypeMask {
+    type Output = EventTypeMask;
+
+    fn bitand(self, other: EventTypeMask) -> EventTypeMask {
+        EventTypeMask(self.0 & other.0)
+    }
+}
+
+/// E.g. `Trade | Quote`, with any bits that are not an event type in hex.
+impl fmt::Debug for EventTypeMask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names: Vec<String> = self.event_types().map(|t| t.to_string()).collect();
+        let known = self
+            .event_types()
+            .fold(0, |bits, event_type| bits | event_type as c_int);
+        if self.0 & !known != 0 {
+            names.push(format!("{:#x}", self.0 & !known));
+        }
+        if names.is_empty() {
+            return f.write_str("(empty)");
+        }
+        f.write_str(&names.join(" | "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn mask_conversions() {
+        let mask = EventTypeMask::QUOTE | EventTypeMask::TRADE;
+        assert_eq!(mask.bits(), DXF_ET_QUOTE | DXF_ET_TRADE);
+        assert!(mask.contains(EventTypeMask::QUOTE) && !mask.contains(EventTypeMask::ORDER));
+        let types: BTreeSet<EventType> = mask.event_types().collect();
+        assert_eq!(types, [EventType::Trade, EventType::Quote].into());
+        assert_eq!(types.into_iter().collect::<EventTypeMask>(), mask);
+        assert_eq!(
+            EventTypeMask::from(EventType::Candle),
+            EventTypeMask::CANDLE
+        );
+        assert_eq!(format!("{:?}", mask), "Trade | Quote");
+        assert_eq!(
+            format!("{:?}", EventTypeMask(DXF_ET_SERIES | 1 << 30)),
+            "Series | 0x40000000"
+        );
+        assert_eq!(format!("{:?}", EventTypeMask::default()), "(empty)");
+        assert_eq!(EventTypeMask::all().event_types().count(), 14);
+        assert_eq!(
+            EventTypeMask::all_market_events() | EventTypeMask::CONFIGURATION,
+            EventTypeMask::all()
+        );
+    }
+}
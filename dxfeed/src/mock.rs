@@ -0,0 +1,178 @@
+//! An in-memory `FeedBackend` for tests, which push synthetic events to whatever code under test
+//! has subscribed.
+//!
+//! ```ignore
+//! let feed = MockFeed::new();
+//! let consumer = QuoteConsumer::start(&feed)?; // subscribes through `FeedBackend`
+//! assert!(feed.is_subscribed("AAPL", EventType::Quote));
+//! feed.push(Event::new("AAPL".to_string(), EventData::Quote(quote)));
+//! assert_eq!(consumer.last_bid("AAPL"), Some(100.5));
+//! ```
+
+use crate::{Error, Event, EventType, FeedBackend, FeedSubscription};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, Weak};
+
+type Listener = Box<dyn FnMut(Result<Event, Error>) + Send>;
+
+struct MockListener {
+    event_types: HashSet<EventType>,
+    symbols: Mutex<HashSet<String>>,
+    listener: Mutex<Listener>,
+}
+
+impl MockListener {
+    fn matches(&self, event: &Event) -> bool {
+        self.event_types.contains(&EventType::from(event))
+            && self.symbols.lock().unwrap().contains(&event.sym)
+    }
+}
+
+/// A feed whose events are pushed by the test. Clones share subscriptions, so a test can keep
+/// one while the code under test owns another.
+#[derive(Clone, Default)]
+pub struct MockFeed {
+    listeners: Arc<Mutex<Vec<Weak<MockListener>>>>,
+}
+
+impl MockFeed {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Deliver `event` to each live subscription to its type and symbol, on this thread.
+    /// Returns the number of subscriptions it was delivered to.
+    pub fn push(&self, event: Event) -> usize {
+        let listeners: Vec<_> = self
+            .listeners()
+            .into_iter()
+            .filter(|listener| listener.matches(&event))
+            .collect();
+        for listener in &listeners {
+            (listener.listener.lock().unwrap())(Ok(event.clone()));
+        }
+        listeners.len()
+    }
+
+    /// Deliver the error `make_error` makes to each live subscription.
+    pub fn push_error<F: Fn() -> Error>(&self, make_error: F) {
+        for listener in self.listeners() {
+            (listener.listener.lock().unwrap())(Err(make_error()));
+        }
+    }
+
+    /// Number of live subscriptions.
+    pub fn subscription_count(&self) -> usize {
+        self.listeners().len()
+    }
+
+    /// Whether a live subscription is to `event_type` for `symbol`.
+    pub fn is_subscribed(&self, symbol: &str, event_type: EventType) -> bool {
+        self.listeners().iter().any(|listener| {
+            listener.event_types.contains(&event_type)
+                && listener.symbols.lock().unwrap().contains(symbol)
+        })
+    }
+
+    /// The symbols of every live subscription.
+    pub fn subscribed_symbols(&self) -> HashSet<String> {
+        self.listeners()
+            .iter()
+            .flat_map(|listener| listener.symbols.lock().unwrap().clone())
+            .collect()
+    }
+
+    /// The listeners of live subscriptions, forgetting dropped ones.
+    fn listeners(&self) -> Vec<Arc<MockListener>> {
+        let mut listeners = self.listeners.lock().unwrap();
+        listeners.retain(|listener| listener.strong_count() > 0);
+        listeners.iter().filter_map(Weak::upgrade).collect()
+    }
+}
+
+/// A subscription to a `MockFeed`, receiving pushed events while it is alive.
+pub struct MockSubscription {
+    listener: Arc<MockListener>,
+}
+
+impl FeedBackend for MockFeed {
+    type Subscription = MockSubscription;
+
+    /// A new feed; `address` is ignored.
+    fn connect(_address: &str) -> Result<Self, Error> {
+        Ok(MockFeed::new())
+    }
+
+    fn subscribe<F>(
+        &self,
+        event_types: &[EventType],
+        listener: F,
+    ) -> Result<MockSubscription, Error>
+    where
+        F: FnMut(Result<Event, Error>) + Send + 'static,
+    {
+        let listener = Arc::new(MockListener {
+            event_types: event_types.iter().copied().collect(),
+            symbols: Mutex::default(),
+            listener: Mutex::new(Box::new(listener)),
+        });
+        self.listeners
+            .lock()
+            .unwrap()
+            .push(Arc::downgrade(&listener));
+        Ok(MockSubscription { listener })
+    }
+}
+
+impl FeedSubscription for MockSubscription {
+    fn add_symbols(&self, symbols: &[&str]) -> Result<(), Error> {
+        self.listener
+            .symbols
+            .lock()
+            .unwrap()
+            .extend(symbols.iter().map(|symbol| symbol.to_string()));
+        Ok(())
+    }
+
+    fn remove_symbols(&self, symbols: &[&str]) -> Result<(), Error> {
+        let mut subscribed = self.listener.symbols.lock().unwrap();
+        for symbol in symbols {
+            subscribed.remove(*symbol);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EventData, QuoteData, TradeData};
+
+    #[test]
+    fn pushes_to_subscribers() {
+        let feed = MockFeed::new();
+        let (subscription, events) = feed.subscribe_stream(&[EventType::Quote]).unwrap();
+        subscription.add_symbols(&["AAPL", "MSFT"]).unwrap();
+        subscription.remove_symbols(&["MSFT"]).unwrap();
+        assert!(feed.is_subscribed("AAPL", EventType::Quote));
+        assert!(!feed.is_subscribed("AAPL", EventType::Trade));
+        assert_eq!(
+            feed.subscribed_symbols(),
+            HashSet::from(["AAPL".to_string()])
+        );
+
+        let quote = Event::new("AAPL".to_string(), EventData::Quote(QuoteData::default()));
+        let trade = Event::new("AAPL".to_string(), EventData::Trade(TradeData::default()));
+        let other = Event::new("MSFT".to_string(), EventData::Quote(QuoteData::default()));
+        assert_eq!(feed.push(quote), 1);
+        assert_eq!(feed.push(trade), 0);
+        assert_eq!(feed.push(other), 0);
+        feed.push_error(|| Error::Timeout);
+        assert_eq!(events.try_recv().unwrap().unwrap().sym, "AAPL");
+        assert!(matches!(events.try_recv(), Ok(Err(Error::Timeout))));
+
+        drop(subscription);
+        assert_eq!(feed.subscription_count(), 0);
+        assert!(events.try_recv().is_err());
+    }
+}
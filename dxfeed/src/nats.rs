@@ -0,0 +1,317 @@
+//! Publishing events to NATS, optionally through JetStream, enabled by the `nats` feature.
+//!
+//! ```ignore
+//! let options = NatsOptions::new("nats://nats-1:4222").subject_prefix("dx").jetstream(true);
+//! let publisher = NatsPublisher::connect(options).await?;
+//! let types = [EventType::Quote, EventType::Trade];
+//! let subscription = connection.subscribe(&types, publisher.listener())?;
+//! // ... later, after dropping the subscription
+//! publisher.close().await;
+//! ```
+//!
+//! Each event is published as its serde JSON serialization on `<prefix>.<type>.<symbol>`, e.g.
+//! `dx.quote.AAPL` or `dx.timeandsale.MSFT`, so consumers can subscribe to `dx.quote.>` or
+//! `dx.*.AAPL`. Characters that subjects reserve (`.`, `*`, `>` and whitespace) are replaced
+//! by `_` in symbols, e.g. `.AAPL240119C150` becomes `_AAPL240119C150`.
+//!
+//! The client connects, and reconnects after losing its server, in the background, trying
+//! every server it knows of. Events published while disconnected are buffered by the client,
+//! then by the publisher's queue; events published while that is full are dropped and counted.
+
+use crate::{Error, Event, EventType};
+use async_nats::jetstream::context::PublishAckFuture;
+use async_nats::{Client, ConnectOptions};
+use bytes::Bytes;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+use tokio::task::JoinHandle;
+
+/// JetStream publishes awaiting their acknowledgement, beyond which the worker waits for the
+/// oldest before publishing more.
+const PENDING_ACKS: usize = 256;
+
+/// Where and how a `NatsPublisher` publishes.
+#[derive(Debug, Clone)]
+pub struct NatsOptions {
+    /// Server URLs, e.g. "nats://nats-1:4222". Servers the cluster advertises are tried too.
+    pub servers: Vec<String>,
+    /// The first token of every subject
+    pub subject_prefix: String,
+    /// Publish through JetStream, waiting for the stream's acknowledgement of each event, rather
+    /// than core NATS. A stream must already capture the subjects.
+    pub jetstream: bool,
+    /// Consecutive reconnect attempts before the client gives up, or None to retry forever
+    pub max_reconnects: Option<usize>,
+    /// Events queued for the worker, beyond which they are dropped
+    pub queue_capacity: usize,
+}
+
+impl NatsOptions {
+    pub fn new(server: &str) -> Self {
+        NatsOptions {
+            servers: vec![server.to_string()],
+            subject_prefix: "dx".to_string(),
+            jetstream: false,
+            max_reconnects: None,
+            queue_capacity: 100_000,
+        }
+    }
+
+    /// Also try `server`.
+    pub fn server(mut self, server: &str) -> Self {
+        self.servers.push(server.to_string());
+        self
+    }
+
+    pub fn subject_prefix(mut self, prefix: &str) -> Self {
+        self.subject_prefix = prefix.to_string();
+        self
+    }
+
+    pub fn jetstream(mut self, jetstream: bool) -> Self {
+        self.jetstream = jetstream;
+        self
+    }
+
+    pub fn max_reconnects(mut self, max_reconnects: Option<usize>) -> Self {
+        self.max_reconnects = max_reconnects;
+        self
+    }
+
+    /// The subject `event` is published on.
+    pub fn subject(&self, event: &Event) -> String {
+        let event_type = EventType::from(event).to_string().to_ascii_lowercase();
+        let symbol: String = event
+            .sym
+            .chars()
+            .map(|c| match c {
+                '.' | '*' | '>' => '_',
+                c if c.is_whitespace() => '_',
+                c => c,
+            })
+            .collect();
+        format!("{}.{}.{}", self.subject_prefix, event_type, symbol)
+    }
+}
+
+/// Counts describing a `NatsPublisher`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NatsStats {
+    /// Events handed to the client, or with JetStream, acknowledged by the stream
+    pub published: u64,
+    /// Events dropped because the queue was full
+    pub dropped: u64,
+    /// Events the client or stream rejected
+    pub failed: u64,
+    /// Times the client reconnected after losing its server
+    pub reconnects: u64,
+}
+
+#[derive(Default)]
+struct Shared {
+    published: AtomicU64,
+    dropped: AtomicU64,
+    failed: AtomicU64,
+    reconnects: AtomicU64,
+    connected: AtomicBool,
+    /// Whether the client has connected before, so the next connection is a reconnect
+    was_connected: AtomicBool,
+}
+
+/// Publishes events to NATS from a tokio task, which sends what is queued and exits once the
+/// publisher and its listeners are dropped.
+pub struct NatsPublisher {
+    sender: Option<Sender<Event>>,
+    worker: Option<JoinHandle<()>>,
+    shared: Arc<Shared>,
+}
+
+impl NatsPublisher {
+    /// Start connecting to `options.servers` and spawn the publishing task on the current tokio
+    /// runtime. Returns without waiting for a connection; fails only for invalid options.
+    pub async fn connect(options: NatsOptions) -> Result<Self, Error> {
+        let shared = Arc::new(Shared::default());
+        let callback_shared = shared.clone();
+        let client = ConnectOptions::new()
+            .name("dxfeed")
+            .retry_on_initial_connect()
+            .max_reconnects(options.max_reconnects)
+            .event_callback(move |event| {
+                on_event(&callback_shared, &event);
+                async {}
+            })
+            .connect(options.servers.as_slice())
+            .await
+            .map_err(|e| Error::Nats(e.to_string()))?;
+        let (sender, events) = channel(options.queue_capacity.max(1));
+        let worker = tokio::spawn(publish(client, options, events, shared.clone()));
+        Ok(NatsPublisher {
+            sender: Some(sender),
+            worker: Some(worker),
+            shared,
+        })
+    }
+
+    /// Queue `event` for publishing, or drop it if the queue is full.
+    pub fn publish(&self, event: Event) {
+        if let Some(sender) = &self.sender {
+            queue(sender, &self.shared, event);
+        }
+    }
+
+    /// A listener publishing each event, dropping those that failed conversion.
+    pub fn listener(&self) -> impl FnMut(Result<Event, Error>) + Send + 'static {
+        let sender = self.sender.clone();
+        let shared = self.shared.clone();
+        move |event| {
+            if let (Some(sender), Ok(event)) = (&sender, event) {
+                queue(sender, &shared, event);
+            }
+        }
+    }
+
+    /// Whether the client is connected to a server.
+    pub fn is_connected(&self) -> bool {
+        self.shared.connected.load(Ordering::Relaxed)
+    }
+
+    pub fn stats(&self) -> NatsStats {
+        NatsStats {
+            published: self.shared.published.load(Ordering::Relaxed),
+            dropped: self.shared.dropped.load(Ordering::Relaxed),
+            failed: self.shared.failed.load(Ordering::Relaxed),
+            reconnects: self.shared.reconnects.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Wait for the task to publish what is queued and flush the client. Its listeners must
+    /// have been dropped, e.g. with their subscriptions, or this waits for them.
+    pub async fn close(mut self) {
+        self.sender = None;
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.await;
+        }
+    }
+}
+
+fn on_event(shared: &Shared, event: &async_nats::Event) {
+    match event {
+        async_nats::Event::Connected => {
+            shared.connected.store(true, Ordering::Relaxed);
+            if shared.was_connected.swap(true, Ordering::Relaxed) {
+                shared.reconnects.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        async_nats::Event::Disconnected | async_nats::Event::Closed => {
+            shared.connected.store(false, Ordering::Relaxed);
+        }
+        _ => {}
+    }
+}
+
+fn queue(sender: &Sender<Event>, shared: &Shared, event: Event) {
+    if let Err(TrySendError::Full(_)) = sender.try_send(event) {
+        shared.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+async fn publish(
+    client: Client,
+    options: NatsOptions,
+    mut events: Receiver<Event>,
+    shared: Arc<Shared>,
+) {
+    let jetstream = options
+        .jetstream
+        .then(|| async_nats::jetstream::new(client.clone()));
+    let mut pending: VecDeque<PublishAckFuture> = VecDeque::new();
+    while let Some(event) = events.recv().await {
+        // Events that can't be serialized, which serde_json doesn't produce, are skipped
+        let Ok(payload) = serde_json::to_vec(&event) else {
+            continue;
+        };
+        let subject = options.subject(&event);
+        let payload = Bytes::from(payload);
+        match &jetstream {
+            Some(jetstream) => {
+                if pending.len() >= PENDING_ACKS {
+                    let ack = pending.pop_front().expect("pending is non-empty");
+                    count(&shared, ack.await.is_ok());
+                }
+                match jetstream.publish(subject, payload).await {
+                    Ok(ack) => pending.push_back(ack),
+                    Err(_) => count(&shared, false),
+                }
+            }
+            None => count(&shared, client.publish(subject, payload).await.is_ok()),
+        }
+    }
+    for ack in pending {
+        count(&shared, ack.await.is_ok());
+    }
+    let _ = client.flush().await;
+}
+
+fn count(shared: &Shared, published: bool) {
+    let counter = if published {
+        &shared.published
+    } else {
+        &shared.failed
+    };
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EventData, QuoteData, TimeAndSaleData};
+
+    #[test]
+    fn maps_events_to_subjects() {
+        let options = NatsOptions::new("nats://localhost:4222");
+        let quote = Event::new("AAPL".to_string(), EventData::Quote(QuoteData::default()));
+        assert_eq!(options.subject(&quote), "dx.quote.AAPL");
+
+        let options = options.subject_prefix("feed.us");
+        let sale = Event::new(
+            ".AAPL240119C150".to_string(),
+            EventData::TimeAndSale(TimeAndSaleData::default()),
+        );
+        assert_eq!(
+            options.subject(&sale),
+            "feed.us.timeandsale._AAPL240119C150"
+        );
+        let candle = Event::new(
+            "AAPL{=5m, tho=true}".to_string(),
+            EventData::Quote(QuoteData::default()),
+        );
+        assert_eq!(
+            options.subject(&candle),
+            "feed.us.quote.AAPL{=5m,_tho=true}"
+        );
+    }
+
+    #[test]
+    fn queues_while_disconnected() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            // Nothing listens on port 1, so the client keeps retrying in the background
+            let mut options = NatsOptions::new("nats://127.0.0.1:1");
+            options.queue_capacity = 1;
+            let publisher = NatsPublisher::connect(options).await.unwrap();
+            assert!(!publisher.is_connected());
+            let mut listener = publisher.listener();
+            for _ in 0..3 {
+                let quote = Event::new("AAPL".to_string(), EventData::Quote(QuoteData::default()));
+                listener(Ok(quote));
+            }
+            assert!(publisher.stats().dropped >= 1);
+        });
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{Event, EventData, QuoteData, QuoteSide};
+use std::collections::{BTreeMap, HashMap};
+
+/// One side of a best bid/offer, attributed to the exchange quoting it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BestQuote {
+    pub price: f64,
+    pub size: f64,
+    pub exchange: char,
+}
+
+/// The national best bid and offer across a symbol's regional quotes. A side is `None` while no
+/// exchange quotes it.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Nbbo {
+    pub bid: Option<BestQuote>,
+    pub ask: Option<BestQuote>,
+}
+
+/// A quoted (price, size), if any.
+type Side = Option<(f64, f64)>;
+
+#[derive(Default)]
+struct Regional {
+    // (bid, ask) by exchange, so ties go to the lowest code
+    quotes: BTreeMap<char, (Side, Side)>,
+    nbbo: Nbbo,
+}
+
+impl Regional {
+    fn compute(&self) -> Nbbo {
+        let mut nbbo = Nbbo::default();
+        for (exchange, (bid, ask)) in &self.quotes {
+            if let Some((price, size)) = *bid {
+                // Better price first, then more size
+                if nbbo
+                    .bid
+                    .is_none_or(|best| (price, size) > (best.price, best.size))
+                {
+                    nbbo.bid = Some(BestQuote {
+                        price,
+                        size,
+                        exchange: *exchange,
+                    });
+                }
+            }
+            if let Some((price, size)) = *ask {
+                if nbbo.ask.is_none_or(|best| {
+                    price < best.price || (price == best.price && size > best.size)
+                }) {
+                    nbbo.ask = Some(BestQuote {
+                        price,
+                        size,
+                        exchange: *exchange,
+                    });
+                }
+            }
+        }
+        nbbo
+    }
+}
+
+/// Computes the NBBO from regional Quote events, i.e. those for symbols like "AAPL&Q", keyed by
+/// the base symbol. Subscribe to the regional symbols of each exchange of interest.
+#[derive(Default)]
+pub struct NbboTracker {
+    symbols: HashMap<String, Regional>,
+}
+
+/// The price and size of a quoted side, if the exchange is quoting it.
+fn side(side: &QuoteSide) -> Side {
+    let size = if side.size.is_nan() { 0.0 } else { side.size };
+    side.is_quoted().then_some((side.price, size))
+}
+
+/// The base symbol and exchange code of a regional symbol like "AAPL&Q".
+fn split_regional(sym: &str) -> Option<(&str, char)> {
+    let (base, exchange) = sym.rsplit_once('&')?;
+    let mut chars = exchange.chars();
+    match (chars.next(), chars.next()) {
+        (Some(exchange), None) if !base.is_empty() => Some((base, exchange)),
+        _ => None,
+    }
+}
+
+impl NbboTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the regional `quote` for `sym`, returning the base symbol and its new NBBO if it
+    /// changed. Quotes for composite (non-regional) symbols are ignored.
+    pub fn update(&mut self, sym: &str, quote: &QuoteData) -> Option<(String, Nbbo)> {
+        let (base, exchange) = split_regional(sym)?;
+        let regional = self.symbols.entry(base.to_string()).or_default();
+        let bid = side(&quote.bid);
+        let ask = side(&quote.ask);
+        if bid.is_none() && ask.is_none() {
+            regional.quotes.remove(&exchange);
+        } else {
+            regional.quotes.insert(exchange, (bid, ask));
+        }
+        let nbbo = regional.compute();
+        if nbbo == regional.nbbo {
+            return None;
+        }
+        regional.nbbo = nbbo;
+        Some((base.to_string(), nbbo))
+    }
+
+    /// `update` with a Quote event; other events are ignored.
+    pub fn on_event(&mut self, event: &Event) -> Option<(String, Nbbo)> {
+        match &event.data {
+            EventData::Quote(quote) => self.update(&event.sym, quote),
+            _ => None,
+        }
+    }
+
+    /// The current NBBO of the base `symbol`, e.g. "AAPL".
+    pub fn nbbo(&self, symbol: &str) -> Option<Nbbo> {
+        self.symbols.get(symbol).map(|regional| regional.nbbo)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(bid: (f64, f64), ask: (f64, f64)) -> QuoteData {
+        let side = |(price, size)| QuoteSide {
+            price,
+            size,
+            ..Default::default()
+        };
+        QuoteData {
+            bid: side(bid),
+            ask: side(ask),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn best_across_exchanges() {
+        let mut tracker = NbboTracker::new();
+        let (base, nbbo) = tracker
+            .update("AAPL&Q", &quote((100.0, 5.0), (100.2, 3.0)))
+            .unwrap();
+        assert_eq!(base, "AAPL");
+        assert_eq!(nbbo.bid.unwrap().exchange, 'Q');
+
+        let (_, nbbo) = tracker
+            .update("AAPL&N", &quote((100.1, 2.0), (100.2, 7.0)))
+            .unwrap();
+        assert_eq!(
+            nbbo.bid,
+            Some(BestQuote {
+                price: 100.1,
+                size: 2.0,
+                exchange: 'N'
+            })
+        );
+        // Same ask price, more size
+        assert_eq!(nbbo.ask.unwrap().exchange, 'N');
+
+        // A worse quote elsewhere doesn't change the NBBO
+        assert!(tracker
+            .update("AAPL&Z", &quote((99.0, 1.0), (101.0, 1.0)))
+            .is_none());
+        // Composite quotes are ignored
+        assert!(tracker
+            .update("AAPL", &quote((1.0, 1.0), (2.0, 1.0)))
+            .is_none());
+
+        // N pulls its quote, so Q is best again
+        let (_, nbbo) = tracker
+            .update("AAPL&N", &quote((f64::NAN, 0.0), (f64::NAN, 0.0)))
+            .unwrap();
+        assert_eq!(nbbo.bid.unwrap().exchange, 'Q');
+        assert_eq!(tracker.nbbo("AAPL"), Some(nbbo));
+    }
+}
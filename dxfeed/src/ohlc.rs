@@ -0,0 +1,241 @@
+use crate::{Event, EventData, EventFlags};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// How long an `OhlcAggregator` bar lasts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BarInterval {
+    /// Bars of this length, aligned to the unix epoch plus the aggregator's offset
+    Fixed(Duration),
+    /// One bar per symbol until `OhlcAggregator::end_session`
+    Session,
+}
+
+/// An open-high-low-close bar built from trades.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bar {
+    pub symbol: String,
+    /// Start of the bar, in milliseconds since the unix epoch
+    pub start: i64,
+    /// End of the bar (exclusive), or the last trade's time for session bars
+    pub end: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    /// Volume-weighted average price
+    pub vwap: f64,
+    /// Number of trades
+    pub count: u64,
+}
+
+impl Bar {
+    fn new(symbol: &str, start: i64, end: i64, price: f64) -> Self {
+        Bar {
+            symbol: symbol.to_string(),
+            start,
+            end,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume: 0.0,
+            vwap: price,
+            count: 0,
+        }
+    }
+
+    fn add(&mut self, price: f64, size: f64) {
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.close = price;
+        if size > 0.0 {
+            self.vwap = (self.vwap * self.volume + price * size) / (self.volume + size);
+            self.volume += size;
+        }
+        self.count += 1;
+    }
+}
+
+/// Builds bars of a `BarInterval` per symbol from Trade or TimeAndSale events, calling back with
+/// each completed bar, for when server-side Candle events aren't available.
+///
+/// A fixed bar completes when a trade arrives after it, or on `flush`. Trades older than the
+/// symbol's open bar are dropped and counted in `late_trades`. Prefer TimeAndSale as the source:
+/// Trade events are conflated, so some trades may be missed.
+pub struct OhlcAggregator {
+    interval: BarInterval,
+    offset_millis: i64,
+    on_bar: Box<dyn FnMut(Bar) + Send>,
+    open: HashMap<String, Bar>,
+    late_trades: u64,
+}
+
+impl OhlcAggregator {
+    pub fn new<F: FnMut(Bar) + Send + 'static>(interval: BarInterval, on_bar: F) -> Self {
+        OhlcAggregator {
+            interval,
+            offset_millis: 0,
+            on_bar: Box::new(on_bar),
+            open: HashMap::new(),
+            late_trades: 0,
+        }
+    }
+
+    /// Align fixed bars to the epoch plus `offset` rather than the epoch itself, e.g.
+    /// `Duration::from_secs(30 * 60)` so hourly bars start at :30 past.
+    pub fn aligned(mut self, offset: Duration) -> Self {
+        self.offset_millis = offset.as_millis() as i64;
+        self
+    }
+
+    pub fn late_trades(&self) -> u64 {
+        self.late_trades
+    }
+
+    /// The (start, end) of the fixed bar containing `time`.
+    fn bounds(&self, time: i64, length: Duration) -> (i64, i64) {
+        let length = (length.as_millis() as i64).max(1);
+        let start = (time - self.offset_millis).div_euclid(length) * length + self.offset_millis;
+        (start, start + length)
+    }
+
+    /// Add a trade of `size` at `price` for `symbol` at `time` (milliseconds since the epoch).
+    pub fn on_trade(&mut self, symbol: &str, time: i64, price: f64, size: f64) {
+        if !price.is_finite() {
+            return;
+        }
+        let size = if size.is_finite() { size } else { 0.0 };
+        let (start, end) = match self.interval {
+            BarInterval::Fixed(length) => self.bounds(time, length),
+            BarInterval::Session => (time, time),
+        };
+        if let Some(bar) = self.open.get_mut(symbol) {
+            if time < bar.start {
+                self.late_trades += 1;
+                return;
+            }
+            if self.interval == BarInterval::Session || time < bar.end {
+                bar.end = bar.end.max(end);
+                bar.add(price, size);
+                return;
+            }
+            if let Some(done) = self.open.remove(symbol) {
+                (self.on_bar)(done);
+            }
+        }
+        let mut bar = Bar::new(symbol, start, end, price);
+        bar.add(price, size);
+        self.open.insert(symbol.to_string(), bar);
+    }
+
+    /// `on_trade` with a Trade or TimeAndSale event; other events, and removals, are ignored.
+    pub fn on_event(&mut self, event: &Event) {
+        match &event.data {
+            EventData::Trade(trade) => {
+                self.on_trade(&event.sym, trade.time, trade.price, trade.size)
+            }
+            EventData::TimeAndSale(time_and_sale)
+                if !EventFlags::from(time_and_sale.event_flags).is_remove_event() =>
+            {
+                self.on_trade(
+                    &event.sym,
+                    time_and_sale.time,
+                    time_and_sale.price,
+                    time_and_sale.size,
+                )
+            }
+            _ => {}
+        }
+    }
+
+    /// Complete the fixed bars that end by `now` (milliseconds since the epoch), for symbols that
+    /// have stopped trading.
+    pub fn flush(&mut self, now: i64) {
+        if self.interval == BarInterval::Session {
+            return;
+        }
+        let done: Vec<String> = self
+            .open
+            .iter()
+            .filter(|(_, bar)| bar.end <= now)
+            .map(|(symbol, _)| symbol.clone())
+            .collect();
+        for symbol in done {
+            if let Some(bar) = self.open.remove(&symbol) {
+                (self.on_bar)(bar);
+            }
+        }
+    }
+
+    /// Complete every open bar, e.g. at the end of a session.
+    pub fn end_session(&mut self) {
+        for (_, bar) in self.open.drain() {
+            (self.on_bar)(bar);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    fn collector() -> (Arc<Mutex<Vec<Bar>>>, impl FnMut(Bar) + Send + 'static) {
+        let bars = Arc::new(Mutex::new(Vec::new()));
+        let sink = bars.clone();
+        (bars, move |bar| sink.lock().unwrap().push(bar))
+    }
+
+    #[test]
+    fn fixed_bars() {
+        let (bars, on_bar) = collector();
+        let mut ohlc = OhlcAggregator::new(BarInterval::Fixed(Duration::from_secs(60)), on_bar);
+        ohlc.on_trade("AAPL", 60_000, 10.0, 100.0);
+        ohlc.on_trade("AAPL", 61_000, 12.0, 100.0);
+        ohlc.on_trade("AAPL", 119_999, 9.0, 200.0);
+        assert!(bars.lock().unwrap().is_empty());
+        // The next minute completes the first bar
+        ohlc.on_trade("AAPL", 120_000, 11.0, 1.0);
+        // Late, so dropped
+        ohlc.on_trade("AAPL", 100_000, 50.0, 1.0);
+        assert_eq!(ohlc.late_trades(), 1);
+        {
+            let bars = bars.lock().unwrap();
+            let bar = &bars[0];
+            assert_eq!((bar.start, bar.end), (60_000, 120_000));
+            assert_eq!(
+                (bar.open, bar.high, bar.low, bar.close),
+                (10.0, 12.0, 9.0, 9.0)
+            );
+            assert_eq!((bar.volume, bar.vwap, bar.count), (400.0, 10.0, 3));
+        }
+        ohlc.flush(180_000);
+        assert_eq!(bars.lock().unwrap()[1].open, 11.0);
+    }
+
+    #[test]
+    fn offset_alignment() {
+        let (bars, on_bar) = collector();
+        let mut ohlc = OhlcAggregator::new(BarInterval::Fixed(Duration::from_secs(3600)), on_bar)
+            .aligned(Duration::from_secs(1800));
+        ohlc.on_trade("SPY", 3_600_000, 1.0, 1.0);
+        ohlc.end_session();
+        let bar = &bars.lock().unwrap()[0];
+        assert_eq!((bar.start, bar.end), (1_800_000, 5_400_000));
+    }
+
+    #[test]
+    fn session_bars() {
+        let (bars, on_bar) = collector();
+        let mut ohlc = OhlcAggregator::new(BarInterval::Session, on_bar);
+        ohlc.on_trade("SPY", 1_000, 5.0, 1.0);
+        ohlc.on_trade("SPY", 9_000_000, 6.0, 1.0);
+        ohlc.flush(i64::MAX);
+        assert!(bars.lock().unwrap().is_empty());
+        ohlc.end_session();
+        let bar = &bars.lock().unwrap()[0];
+        assert_eq!((bar.start, bar.end, bar.high), (1_000, 9_000_000, 6.0));
+    }
+}
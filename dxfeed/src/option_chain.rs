@@ -0,0 +1,295 @@
+use crate::{Event, EventData, GreeksData, SeriesData};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OptionKind {
+    Call,
+    Put,
+}
+
+/// The parts of an option symbol like ".AAPL240119C150".
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionSymbol {
+    pub underlying: String,
+    /// Days since the unix epoch, as with `SeriesData::expiration`
+    pub expiration: i32,
+    pub kind: OptionKind,
+    pub strike: f64,
+}
+
+/// Days since the unix epoch of a proleptic Gregorian date.
+fn days_from_civil(year: i32, month: u32, day: u32) -> i32 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i32;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day as i32 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+impl OptionSymbol {
+    /// Parse a dxFeed option symbol: "." then the underlying, the expiration as YYMMDD, "C" or
+    /// "P", and the strike.
+    pub fn parse(symbol: &str) -> Option<OptionSymbol> {
+        let rest = symbol.strip_prefix('.')?;
+        let kind_at = rest.rfind(['C', 'P'])?;
+        let strike: f64 = rest[kind_at + 1..].parse().ok()?;
+        let kind = if rest.as_bytes()[kind_at] == b'C' {
+            OptionKind::Call
+        } else {
+            OptionKind::Put
+        };
+        let date_at = kind_at.checked_sub(6)?;
+        let date = &rest[date_at..kind_at];
+        if date_at == 0 || !date.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let (year, month, day) = (
+            date[..2].parse::<i32>().ok()?,
+            date[2..4].parse::<u32>().ok()?,
+            date[4..].parse::<u32>().ok()?,
+        );
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) || !strike.is_finite() {
+            return None;
+        }
+        Some(OptionSymbol {
+            underlying: rest[..date_at].to_string(),
+            expiration: days_from_civil(2000 + year, month, day),
+            kind,
+            strike,
+        })
+    }
+}
+
+/// An option contract with the latest of its events.
+#[derive(Debug, Clone)]
+pub struct OptionContract {
+    pub symbol: String,
+    pub greeks: Option<GreeksData>,
+    pub open_interest: Option<f64>,
+}
+
+impl OptionContract {
+    fn new(symbol: &str) -> Self {
+        OptionContract {
+            symbol: symbol.to_string(),
+            greeks: None,
+            open_interest: None,
+        }
+    }
+}
+
+/// The call and put at a strike.
+#[derive(Debug, Clone, Default)]
+pub struct StrikeRow {
+    pub call: Option<OptionContract>,
+    pub put: Option<OptionContract>,
+}
+
+impl StrikeRow {
+    fn contract(&mut self, kind: OptionKind, symbol: &str) -> &mut OptionContract {
+        let slot = match kind {
+            OptionKind::Call => &mut self.call,
+            OptionKind::Put => &mut self.put,
+        };
+        slot.get_or_insert_with(|| OptionContract::new(symbol))
+    }
+}
+
+/// A strike as an ordered map key, in thousandths.
+fn strike_key(strike: f64) -> i64 {
+    (strike * 1000.0).round() as i64
+}
+
+/// The contracts of an underlying expiring on one day, by strike.
+#[derive(Debug, Clone, Default)]
+pub struct Expiration {
+    /// The latest Series event of the expiration, with its volatility and forward price
+    pub series: Option<SeriesData>,
+    strikes: BTreeMap<i64, StrikeRow>,
+}
+
+impl Expiration {
+    /// The strikes, ascending, with their contracts.
+    pub fn strikes(&self) -> impl Iterator<Item = (f64, &StrikeRow)> {
+        self.strikes
+            .iter()
+            .map(|(key, row)| (*key as f64 / 1000.0, row))
+    }
+
+    pub fn strike(&self, strike: f64) -> Option<&StrikeRow> {
+        self.strikes.get(&strike_key(strike))
+    }
+
+    /// The strike nearest `price`, the lower on a tie.
+    pub fn atm_strike(&self, price: f64) -> Option<f64> {
+        let key = strike_key(price);
+        let below = self.strikes.range(..=key).next_back().map(|(k, _)| *k);
+        let above = self.strikes.range(key..).next().map(|(k, _)| *k);
+        let nearest = match (below, above) {
+            (Some(below), Some(above)) if above - key < key - below => above,
+            (Some(below), _) => below,
+            (None, above) => above?,
+        };
+        Some(nearest as f64 / 1000.0)
+    }
+}
+
+/// Option chains assembled from Series, Summary and Greeks events, organized by underlying,
+/// expiration (days since the unix epoch) and strike.
+///
+/// Contracts are found from option symbols like ".AAPL240119C150", and Series events from
+/// their underlying's symbol. Subscribe to both to fill the chain.
+#[derive(Debug, Default)]
+pub struct OptionChain {
+    underlyings: HashMap<String, BTreeMap<i32, Expiration>>,
+}
+
+impl OptionChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn contract(&mut self, symbol: &str) -> Option<&mut OptionContract> {
+        let option = OptionSymbol::parse(symbol)?;
+        Some(
+            self.underlyings
+                .entry(option.underlying)
+                .or_default()
+                .entry(option.expiration)
+                .or_default()
+                .strikes
+                .entry(strike_key(option.strike))
+                .or_default()
+                .contract(option.kind, symbol),
+        )
+    }
+
+    /// Add the contract of an option `symbol`, before any of its events arrive. Returns false
+    /// if it isn't an option symbol.
+    pub fn add_contract(&mut self, symbol: &str) -> bool {
+        self.contract(symbol).is_some()
+    }
+
+    /// Update the chain with a Series, Summary or Greeks event; others are ignored, as are
+    /// Summary and Greeks events of symbols that aren't options.
+    pub fn on_event(&mut self, event: &Event) {
+        match &event.data {
+            EventData::Series(series) => {
+                let expirations = self.underlyings.entry(event.sym.clone()).or_default();
+                if series.event_flags.is_remove_event() {
+                    if let Some(expiration) = expirations.get_mut(&series.expiration) {
+                        expiration.series = None;
+                    }
+                } else {
+                    expirations.entry(series.expiration).or_default().series = Some(*series);
+                }
+            }
+            EventData::Summary(summary) => {
+                if let Some(contract) = self.contract(&event.sym) {
+                    contract.open_interest = Some(summary.open_interest);
+                }
+            }
+            EventData::Greeks(greeks) => {
+                if greeks.is_remove_event() {
+                    return;
+                }
+                if let Some(contract) = self.contract(&event.sym) {
+                    contract.greeks = Some(*greeks);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn underlyings(&self) -> impl Iterator<Item = &str> {
+        self.underlyings.keys().map(String::as_str)
+    }
+
+    /// The expirations of `underlying`, ascending.
+    pub fn expirations(&self, underlying: &str) -> impl Iterator<Item = (i32, &Expiration)> {
+        self.underlyings
+            .get(underlying)
+            .into_iter()
+            .flat_map(|expirations| expirations.iter().map(|(day, e)| (*day, e)))
+    }
+
+    pub fn expiration(&self, underlying: &str, expiration: i32) -> Option<&Expiration> {
+        self.underlyings.get(underlying)?.get(&expiration)
+    }
+
+    /// The first expiration of `underlying` on or after `day` (days since the unix epoch).
+    pub fn nearest_expiration(&self, underlying: &str, day: i32) -> Option<(i32, &Expiration)> {
+        self.underlyings
+            .get(underlying)?
+            .range(day..)
+            .next()
+            .map(|(day, expiration)| (*day, expiration))
+    }
+
+    /// The contract of an option `symbol`, if in the chain.
+    pub fn get(&self, symbol: &str) -> Option<&OptionContract> {
+        let option = OptionSymbol::parse(symbol)?;
+        let row = self
+            .expiration(&option.underlying, option.expiration)?
+            .strike(option.strike)?;
+        match option.kind {
+            OptionKind::Call => row.call.as_ref(),
+            OptionKind::Put => row.put.as_ref(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SummaryData;
+
+    #[test]
+    fn parse_symbols() {
+        let option = OptionSymbol::parse(".SPXW240119P4512.5").unwrap();
+        assert_eq!(option.underlying, "SPXW");
+        assert_eq!(option.expiration, 19741);
+        assert_eq!(option.kind, OptionKind::Put);
+        assert_eq!(option.strike, 4512.5);
+        assert_eq!(OptionSymbol::parse(".C240119C100").unwrap().underlying, "C");
+        assert_eq!(OptionSymbol::parse("AAPL"), None);
+        assert_eq!(OptionSymbol::parse(".240119C100"), None);
+        assert_eq!(OptionSymbol::parse(".AAPL241319C100"), None);
+    }
+
+    #[test]
+    fn chain_lookups() {
+        let mut chain = OptionChain::new();
+        for symbol in [
+            ".AAPL240119C150",
+            ".AAPL240119P150",
+            ".AAPL240119C155",
+            ".AAPL240216C150",
+        ] {
+            assert!(chain.add_contract(symbol));
+        }
+        let summary = SummaryData {
+            open_interest: 1200.0,
+            ..Default::default()
+        };
+        chain.on_event(&Event {
+            sym: ".AAPL240119P150".to_string(),
+            data: EventData::Summary(summary),
+        });
+        assert_eq!(
+            chain.get(".AAPL240119P150").unwrap().open_interest,
+            Some(1200.0)
+        );
+
+        let (day, expiration) = chain.nearest_expiration("AAPL", 19700).unwrap();
+        assert_eq!(day, 19741);
+        assert_eq!(expiration.strikes().count(), 2);
+        assert_eq!(expiration.atm_strike(153.0), Some(155.0));
+        assert_eq!(expiration.atm_strike(152.5), Some(150.0));
+        assert_eq!(expiration.atm_strike(1.0), Some(150.0));
+        assert_eq!(chain.nearest_expiration("AAPL", 19742).unwrap().0, 19769);
+        assert!(chain.nearest_expiration("AAPL", 19770).is_none());
+    }
+}
@@ -0,0 +1,153 @@
+//! Catching panics in callbacks run by the C API, which must not unwind into C.
+
+use std::any::Any;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::mpsc::Sender;
+use std::sync::{Mutex, PoisonError};
+
+/// A panic caught in a callback.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallbackPanic {
+    /// The kind of callback, e.g. "event listener"
+    pub callback: &'static str,
+    pub message: String,
+}
+
+/// What to do with a panic in a listener or notifier called from a C API thread. Whatever the
+/// policy, the panic hook runs first, as usual.
+#[derive(Debug, Clone, Default)]
+pub enum PanicPolicy {
+    /// Log it (with the `log` feature, else to stderr) and drop the event.
+    #[default]
+    LogAndDrop,
+    /// Abort the process.
+    Abort,
+    /// Send it on the channel and drop the event.
+    Report(Sender<CallbackPanic>),
+}
+
+static POLICY: Mutex<Option<PanicPolicy>> = Mutex::new(None);
+
+/// Set the policy for every subsequent callback panic, process-wide.
+pub fn set_panic_policy(policy: PanicPolicy) {
+    *POLICY.lock().unwrap_or_else(PoisonError::into_inner) = Some(policy);
+}
+
+pub fn panic_policy() -> PanicPolicy {
+    POLICY
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+        .unwrap_or_default()
+}
+
+fn message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    }
+}
+
+/// Run `f`, the body of a trampoline for a `callback`, handling a panic by the current policy
+/// and returning `fallback` in its place. Public, through `__private`, for the trampolines
+/// `#[listener]` generates.
+pub fn guard<R>(callback: &'static str, fallback: R, f: impl FnOnce() -> R) -> R {
+    let payload = match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(result) => return result,
+        Err(payload) => payload,
+    };
+    let panic = CallbackPanic {
+        callback,
+        message: message(payload.as_ref()),
+    };
+    match panic_policy() {
+        PanicPolicy::LogAndDrop => {
+            #[cfg(feature = "log")]
+            log::error!("Panic in {}: {}", panic.callback, panic.message);
+            #[cfg(not(feature = "log"))]
+            eprintln!("dxfeed: panic in {}: {}", panic.callback, panic.message);
+        }
+        PanicPolicy::Abort => std::process::abort(),
+        PanicPolicy::Report(sender) => {
+            let _ = sender.send(panic);
+        }
+    }
+    fallback
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::channel;
+
+    // Tests sharing the process-wide policy run in one function
+    #[test]
+    fn policies() {
+        assert_eq!(guard("test", 0, || 1), 1);
+        assert_eq!(guard("test", 0, || panic!("dropped")), 0);
+
+        let (sender, receiver) = channel();
+        set_panic_policy(PanicPolicy::Report(sender));
+        let value: u32 = guard("event listener", 7, || panic!("bad {}", "event"));
+        assert_eq!(value, 7);
+        assert_eq!(
+            receiver.try_recv(),
+            Ok(CallbackPanic {
+                callback: "event listener",
+                message: "bad event".to_string()
+            })
+        );
+        #[cfg(feature = "macros")]
+        {
+            macro_listener_panic();
+            assert_eq!(
+                receiver.try_recv(),
+                Ok(CallbackPanic {
+                    callback: "event listener",
+                    message: "bad configuration 7".to_string()
+                })
+            );
+        }
+        set_panic_policy(PanicPolicy::LogAndDrop);
+    }
+
+    /// Call a `#[listener]` trampoline whose handler panics.
+    #[cfg(feature = "macros")]
+    fn macro_listener_panic() {
+        use crate::{
+            dxf_configuration_t, dxf_event_data_t, ConfigurationData, RawListener,
+            DXF_ET_CONFIGURATION,
+        };
+        use std::os::raw::c_void;
+        use widestring::WideCString;
+
+        struct Panicky;
+
+        #[crate::listener]
+        impl Panicky {
+            fn on_configuration(&mut self, _sym: &str, data: &ConfigurationData) {
+                panic!("bad configuration {}", data.version);
+            }
+        }
+
+        let sym = WideCString::from_str("AAPL").unwrap();
+        let object = WideCString::from_str("{}").unwrap();
+        let config = dxf_configuration_t {
+            version: 7,
+            object: object.as_ptr() as *mut _,
+        };
+        let trampoline = Panicky::trampoline().unwrap();
+        unsafe {
+            trampoline(
+                DXF_ET_CONFIGURATION,
+                sym.as_ptr() as *const _,
+                &config as *const dxf_configuration_t as *const dxf_event_data_t,
+                1,
+                &mut Panicky as *mut Panicky as *mut c_void,
+            )
+        };
+    }
+}
@@ -0,0 +1,234 @@
+//! Black-Scholes-Merton pricing, for cross-checking Greeks and TheoPrice events or filling in
+//! for them when absent.
+
+use crate::{EpochMillis, OptionKind, OptionSymbol, QuoteData, UnderlyingData};
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+const MILLIS_PER_DAY: f64 = 86_400_000.0;
+const DAYS_PER_YEAR: f64 = 365.0;
+
+/// The inputs to `black_scholes`. Rates and volatility are annualized fractions, e.g. 0.05.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PricingInputs {
+    pub kind: OptionKind,
+    /// The underlying's price
+    pub spot: f64,
+    pub strike: f64,
+    /// Time to expiration, in years
+    pub years: f64,
+    /// Risk-free interest rate, continuously compounded
+    pub rate: f64,
+    /// Continuous dividend yield
+    pub dividend_yield: f64,
+    pub volatility: f64,
+}
+
+impl PricingInputs {
+    /// Inputs for `option` at `now`, with the mid of the underlying's `quote` as the spot and
+    /// the implied volatility of its `underlying` event. `None` without a two-sided quote.
+    pub fn from_feed(
+        option: &OptionSymbol,
+        quote: &QuoteData,
+        underlying: &UnderlyingData,
+        rate: f64,
+        now: impl EpochMillis,
+    ) -> Option<Self> {
+        Some(PricingInputs {
+            kind: option.kind,
+            spot: quote.mid()?,
+            strike: option.strike,
+            years: years_to_expiration(option.expiration, now),
+            rate,
+            dividend_yield: 0.0,
+            volatility: underlying.volatility,
+        })
+    }
+}
+
+/// Years from `now` until the end (UTC) of `expiration`, in days since the unix epoch, or 0 if
+/// past.
+pub fn years_to_expiration(expiration: i32, now: impl EpochMillis) -> f64 {
+    let end = (expiration as f64 + 1.0) * MILLIS_PER_DAY;
+    ((end - now.epoch_millis() as f64) / MILLIS_PER_DAY / DAYS_PER_YEAR).max(0.0)
+}
+
+/// A theoretical price and its greeks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TheoGreeks {
+    pub price: f64,
+    pub delta: f64,
+    pub gamma: f64,
+    /// Change in price per calendar day
+    pub theta: f64,
+    /// Change in price per 1.0 of volatility
+    pub vega: f64,
+    /// Change in price per 1.0 of interest rate
+    pub rho: f64,
+}
+
+/// The complementary error function, to within 1.2e-7 (Numerical Recipes' `erfcc`).
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = [
+        -1.26551223,
+        1.00002368,
+        0.37409196,
+        0.09678418,
+        -0.18628806,
+        0.27886807,
+        -1.13520398,
+        1.48851587,
+        -0.82215223,
+        0.17087277,
+    ]
+    .iter()
+    .rev()
+    .fold(0.0, |acc, c| c + t * acc);
+    let erfc = t * (-z * z + poly).exp();
+    if x >= 0.0 {
+        erfc
+    } else {
+        2.0 - erfc
+    }
+}
+
+fn normal_cdf(x: f64) -> f64 {
+    0.5 * erfc(-x * FRAC_1_SQRT_2)
+}
+
+fn normal_pdf(x: f64) -> f64 {
+    (-0.5 * x * x).exp() / (2.0 * PI).sqrt()
+}
+
+/// The Black-Scholes-Merton price and greeks of a European option, or `None` if an input is
+/// out of range. At expiration, or with zero volatility, the price is the discounted intrinsic
+/// value.
+pub fn black_scholes(inputs: &PricingInputs) -> Option<TheoGreeks> {
+    let PricingInputs {
+        kind,
+        spot,
+        strike,
+        years,
+        rate,
+        dividend_yield,
+        volatility,
+    } = *inputs;
+    let valid = spot > 0.0
+        && strike > 0.0
+        && years >= 0.0
+        && volatility >= 0.0
+        && [spot, strike, years, rate, dividend_yield, volatility]
+            .iter()
+            .all(|x| x.is_finite());
+    if !valid {
+        return None;
+    }
+    let sign = match kind {
+        OptionKind::Call => 1.0,
+        OptionKind::Put => -1.0,
+    };
+    let spot_discount = (-dividend_yield * years).exp();
+    let strike_discount = (-rate * years).exp();
+    let forward = spot * spot_discount;
+    let strike_pv = strike * strike_discount;
+
+    let deviation = volatility * years.sqrt();
+    if deviation == 0.0 {
+        let in_the_money = sign * (forward - strike_pv) > 0.0;
+        let delta = if in_the_money {
+            sign * spot_discount
+        } else {
+            0.0
+        };
+        return Some(TheoGreeks {
+            price: (sign * (forward - strike_pv)).max(0.0),
+            delta,
+            gamma: 0.0,
+            theta: 0.0,
+            vega: 0.0,
+            rho: if in_the_money {
+                sign * strike_pv * years
+            } else {
+                0.0
+            },
+        });
+    }
+
+    let d1 = ((spot / strike).ln()
+        + (rate - dividend_yield + volatility * volatility / 2.0) * years)
+        / deviation;
+    let d2 = d1 - deviation;
+    let (nd1, nd2) = (normal_cdf(sign * d1), normal_cdf(sign * d2));
+    let theta_per_year = -forward * normal_pdf(d1) * volatility / (2.0 * years.sqrt())
+        - sign * rate * strike_pv * nd2
+        + sign * dividend_yield * forward * nd1;
+    Some(TheoGreeks {
+        price: sign * (forward * nd1 - strike_pv * nd2),
+        delta: sign * spot_discount * nd1,
+        gamma: spot_discount * normal_pdf(d1) / (spot * deviation),
+        theta: theta_per_year / DAYS_PER_YEAR,
+        vega: forward * normal_pdf(d1) * years.sqrt(),
+        rho: sign * strike_pv * years * nd2,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs(kind: OptionKind) -> PricingInputs {
+        PricingInputs {
+            kind,
+            spot: 100.0,
+            strike: 100.0,
+            years: 1.0,
+            rate: 0.05,
+            dividend_yield: 0.0,
+            volatility: 0.2,
+        }
+    }
+
+    fn assert_near(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-4, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn textbook_values() {
+        let call = black_scholes(&inputs(OptionKind::Call)).unwrap();
+        assert_near(call.price, 10.4506);
+        assert_near(call.delta, 0.6368);
+        assert_near(call.gamma, 0.018762);
+        assert_near(call.vega, 37.5240);
+        assert_near(call.theta, -6.4140 / 365.0);
+        assert_near(call.rho, 53.2325);
+
+        let put = black_scholes(&inputs(OptionKind::Put)).unwrap();
+        assert_near(put.price, 5.5735);
+        assert_near(put.delta, -0.3632);
+        // Put-call parity
+        assert_near(call.price - put.price, 100.0 - 100.0 * (-0.05f64).exp());
+    }
+
+    #[test]
+    fn expired_and_invalid() {
+        let expired = PricingInputs {
+            spot: 110.0,
+            years: 0.0,
+            ..inputs(OptionKind::Call)
+        };
+        let greeks = black_scholes(&expired).unwrap();
+        assert_eq!((greeks.price, greeks.delta), (10.0, 1.0));
+        let negative = PricingInputs {
+            volatility: -0.1,
+            ..inputs(OptionKind::Put)
+        };
+        assert_eq!(black_scholes(&negative), None);
+        // 2024-01-19, from its start
+        assert_near(
+            years_to_expiration(19741, 19741 * 86_400_000i64),
+            1.0 / 365.0,
+        );
+        assert_eq!(years_to_expiration(19741, i64::MAX), 0.0);
+    }
+}
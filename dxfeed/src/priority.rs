@@ -0,0 +1,214 @@
+use crate::{Error, Event, EventType, Subscription};
+use std::cmp::Reverse;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Condvar, Mutex};
+
+/// Counts of events a `PriorityDispatcher` could not deliver.
+#[derive(Debug, Clone, Default)]
+pub struct PriorityStats {
+    /// Events dropped, oldest first, because their class's queue was full
+    pub dropped: HashMap<EventType, u64>,
+    /// Events that failed conversion from their C representation
+    pub errors: u64,
+}
+
+struct Class {
+    weight: usize,
+    capacity: usize,
+    queue: VecDeque<Event>,
+}
+
+/// Queues events by priority class, for `Subscription::attach_prioritized`.
+///
+/// Events are queued on the connection's socket thread and handled on a separate drain thread,
+/// which takes up to `weight` events from each class in turn, highest weight first. A flood of
+/// one type therefore never holds back a class with its own queue; at worst it is delayed by
+/// other classes' weights per round.
+pub struct PriorityDispatcher {
+    classes: Vec<Class>,
+    class_of: HashMap<EventType, usize>,
+    stats: Arc<Mutex<PriorityStats>>,
+    // Set once no more events will be pushed
+    closed: bool,
+}
+
+impl PriorityDispatcher {
+    /// A dispatcher whose default class, for any type not given its own, has weight 1 and holds
+    /// up to `capacity` events.
+    pub fn new(capacity: usize) -> Self {
+        PriorityDispatcher {
+            classes: vec![Class {
+                weight: 1,
+                capacity,
+                queue: VecDeque::new(),
+            }],
+            class_of: HashMap::new(),
+            stats: Arc::default(),
+            closed: false,
+        }
+    }
+
+    /// Give `event_types` their own class, drained up to `weight` events per round and holding up
+    /// to `capacity` events. A type already in another class is moved to this one.
+    pub fn class(mut self, event_types: &[EventType], weight: usize, capacity: usize) -> Self {
+        self.classes.push(Class {
+            weight: weight.max(1),
+            capacity,
+            queue: VecDeque::new(),
+        });
+        for event_type in event_types {
+            self.class_of.insert(*event_type, self.classes.len() - 1);
+        }
+        self
+    }
+
+    /// Shared counters, updated as events are queued.
+    pub fn stats(&self) -> Arc<Mutex<PriorityStats>> {
+        self.stats.clone()
+    }
+
+    fn push(&mut self, event: Event) {
+        let event_type = EventType::from(&event);
+        let class = &mut self.classes[self.class_of.get(&event_type).copied().unwrap_or(0)];
+        if class.capacity == 0 {
+            return;
+        }
+        if class.queue.len() == class.capacity {
+            if let Some(dropped) = class.queue.pop_front() {
+                *self
+                    .stats
+                    .lock()
+                    .unwrap()
+                    .dropped
+                    .entry(EventType::from(&dropped))
+                    .or_default() += 1;
+            }
+        }
+        class.queue.push_back(event);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.classes.iter().all(|class| class.queue.is_empty())
+    }
+
+    /// Move one round of events, by descending weight, into `batch`.
+    fn drain_round(&mut self, batch: &mut Vec<Event>) {
+        let mut order: Vec<&mut Class> = self.classes.iter_mut().collect();
+        order.sort_by_key(|class| Reverse(class.weight));
+        for class in order {
+            let n = class.weight.min(class.queue.len());
+            batch.extend(class.queue.drain(..n));
+        }
+    }
+}
+
+struct Shared {
+    dispatcher: Mutex<PriorityDispatcher>,
+    ready: Condvar,
+}
+
+/// Closes the queues once the listener holding it is dropped (e.g. on `detach`).
+struct CloseOnDrop(Arc<Shared>);
+
+impl Drop for CloseOnDrop {
+    fn drop(&mut self) {
+        self.0.dispatcher.lock().unwrap().closed = true;
+        self.0.ready.notify_all();
+    }
+}
+
+impl Subscription {
+    /// Attach `dispatcher` as this subscription's listener, handling events on a new thread in
+    /// `dispatcher`'s priority order. The thread exits once the listener is detached or replaced
+    /// and the queues are empty.
+    pub fn attach_prioritized<F>(
+        &mut self,
+        dispatcher: PriorityDispatcher,
+        mut handler: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(Event) + Send + 'static,
+    {
+        let shared = Arc::new(Shared {
+            dispatcher: Mutex::new(dispatcher),
+            ready: Condvar::new(),
+        });
+        let drain = shared.clone();
+        std::thread::Builder::new()
+            .name("priority-dispatch".to_string())
+            .spawn(move || {
+                let mut batch = Vec::new();
+                loop {
+                    {
+                        let mut dispatcher = drain.dispatcher.lock().unwrap();
+                        while dispatcher.is_empty() {
+                            if dispatcher.closed {
+                                return;
+                            }
+                            dispatcher = drain.ready.wait(dispatcher).unwrap();
+                        }
+                        dispatcher.drain_round(&mut batch);
+                    }
+                    batch.drain(..).for_each(&mut handler);
+                }
+            })?;
+        let guard = CloseOnDrop(shared);
+        self.attach(move |event| {
+            let shared = &guard.0;
+            match event {
+                Ok(event) => shared.dispatcher.lock().unwrap().push(event),
+                Err(_) => {
+                    let dispatcher = shared.dispatcher.lock().unwrap();
+                    dispatcher.stats.lock().unwrap().errors += 1;
+                    return;
+                }
+            }
+            shared.ready.notify_one();
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConfigurationData, EventData, ProfileEventData};
+
+    fn profile(sym: &str) -> Event {
+        Event::new(
+            sym.to_string(),
+            EventData::Profile(ProfileEventData::default()),
+        )
+    }
+
+    fn config(sym: &str) -> Event {
+        Event::new(
+            sym.to_string(),
+            EventData::Configuration(ConfigurationData {
+                version: 0,
+                object: String::new(),
+            }),
+        )
+    }
+
+    #[test]
+    fn weighted_rounds() {
+        let mut dispatcher = PriorityDispatcher::new(3).class(&[EventType::Configuration], 2, 10);
+        let stats = dispatcher.stats();
+        for i in 0..5 {
+            dispatcher.push(profile(&format!("P{}", i)));
+        }
+        for i in 0..3 {
+            dispatcher.push(config(&format!("C{}", i)));
+        }
+        // The default class kept only its newest 3
+        assert_eq!(stats.lock().unwrap().dropped[&EventType::Profile], 2);
+
+        let mut order = Vec::new();
+        while !dispatcher.is_empty() {
+            let mut batch = Vec::new();
+            dispatcher.drain_round(&mut batch);
+            order.extend(batch.into_iter().map(|event| event.sym));
+        }
+        assert_eq!(order, ["C0", "C1", "P2", "C2", "P3", "P4"]);
+    }
+}
@@ -0,0 +1,402 @@
+//! Protobuf messages for the event payloads, generated by prost from proto/events.proto, with
+//! conversions from and to the event structs. Enabled by the `proto` feature; the `grpc` feature
+//! adds the gateway's service and messages from proto/feed.proto.
+//!
+//! ```ignore
+//! use prost::Message;
+//!
+//! let bytes = proto::Event::from(&event).encode_to_vec();
+//! let event = Event::try_from(proto::Event::decode(bytes.as_slice())?)?;
+//! ```
+//!
+//! Exchange codes are single-character strings, empty for '\0', enums are their C API values and
+//! missing values are NaN. TradeETH uses the `Trade` message, without `tick`.
+
+include!(concat!(env!("OUT_DIR"), "/dxfeed.rs"));
+
+/// The encoded descriptors of proto/feed.proto and the events it imports, for gRPC server
+/// reflection.
+#[cfg(feature = "grpc")]
+pub const FILE_DESCRIPTOR_SET: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/feed.fds"));
+
+use crate::{
+    dxf_char_t, Action, CandleData, ConfigurationData, Direction, Error, EventData, EventFlags,
+    GreeksData, OrderEventData, PriceType, ProfileEventData, QuoteData, QuoteSide, Scope,
+    SeriesData, Side, SpreadOrderData, SummaryData, TheoPriceData, TimeAndSaleData, TradeData,
+    TradeEthData, UnderlyingData,
+};
+use std::convert::TryFrom;
+
+/// A payload field, as the value of its message field.
+trait ProtoField: Sized {
+    type Repr;
+
+    fn to_proto(&self) -> Self::Repr;
+
+    fn from_proto(repr: Self::Repr) -> Result<Self, Error>;
+}
+
+macro_rules! plain_fields {
+    ($($ty:ty),*) => {
+        $(
+            impl ProtoField for $ty {
+                type Repr = $ty;
+
+                fn to_proto(&self) -> $ty {
+                    self.clone()
+                }
+
+                fn from_proto(repr: $ty) -> Result<Self, Error> {
+                    Ok(repr)
+                }
+            }
+        )*
+    };
+}
+
+plain_fields!(bool, i32, i64, u32, f64, String);
+
+macro_rules! enum_fields {
+    ($($ty:ident),*) => {
+        $(
+            impl ProtoField for $ty {
+                type Repr = u32;
+
+                fn to_proto(&self) -> u32 {
+                    (*self).into()
+                }
+
+                fn from_proto(repr: u32) -> Result<Self, Error> {
+                    $ty::try_from(repr)
+                }
+            }
+        )*
+    };
+}
+
+enum_fields!(Action, Direction, PriceType, Scope, Side);
+
+impl ProtoField for Option<f64> {
+    type Repr = f64;
+
+    fn to_proto(&self) -> f64 {
+        self.unwrap_or(f64::NAN)
+    }
+
+    fn from_proto(repr: f64) -> Result<Self, Error> {
+        Ok(crate::non_nan(repr))
+    }
+}
+
+impl ProtoField for char {
+    type Repr = String;
+
+    fn to_proto(&self) -> String {
+        if *self == '\0' {
+            String::new()
+        } else {
+            self.to_string()
+        }
+    }
+
+    fn from_proto(repr: String) -> Result<Self, Error> {
+        Ok(repr.chars().next().unwrap_or('\0'))
+    }
+}
+
+impl ProtoField for EventFlags {
+    type Repr = u32;
+
+    fn to_proto(&self) -> u32 {
+        self.bits()
+    }
+
+    fn from_proto(repr: u32) -> Result<Self, Error> {
+        Ok(EventFlags(repr))
+    }
+}
+
+/// A raw `dxf_char_t` field, which is an integer type, as a character.
+mod wide_char {
+    use super::*;
+
+    pub fn to_proto(c: &dxf_char_t) -> String {
+        char::from_u32(*c as u32).unwrap_or('\0').to_proto()
+    }
+
+    pub fn from_proto(repr: String) -> Result<dxf_char_t, Error> {
+        Ok(char::from_proto(repr)? as dxf_char_t)
+    }
+}
+
+macro_rules! to_proto {
+    ($value:expr) => {
+        ProtoField::to_proto($value)
+    };
+    ($value:expr, $codec:ident) => {
+        $codec::to_proto($value)
+    };
+}
+
+macro_rules! from_proto {
+    ($value:expr) => {
+        ProtoField::from_proto($value)?
+    };
+    ($value:expr, $codec:ident) => {
+        $codec::from_proto($value)?
+    };
+}
+
+// `From<&$data> for $message` and `TryFrom<$message> for $data`, for payloads whose fields have
+// the same names as their message's. A field may name a module to convert it with instead of
+// `ProtoField`.
+macro_rules! proto_messages {
+    ($(
+        $data:ident => $message:ident { $($field:ident $(($codec:ident))?),* $(,)? }
+    )*) => {
+        $(
+            impl From<&$data> for $message {
+                // TradeETH has no `tick`
+                #[allow(clippy::needless_update)]
+                fn from(data: &$data) -> Self {
+                    $message {
+                        $($field: to_proto!(&data.$field $(, $codec)?),)*
+                        ..Default::default()
+                    }
+                }
+            }
+
+            impl TryFrom<$message> for $data {
+                type Error = Error;
+
+                fn try_from(message: $message) -> Result<Self, Error> {
+                    Ok($data {
+                        $($field: from_proto!(message.$field $(, $codec)?),)*
+                    })
+                }
+            }
+        )*
+    };
+}
+
+proto_messages! {
+    TradeData => Trade {
+        time, sequence, time_nanos, exchange_code, price, size, tick, change, day_id, day_volume,
+        day_turnover, raw_flags, direction, is_eth, scope,
+    }
+    TradeEthData => Trade {
+        time, sequence, time_nanos, exchange_code, price, size, change, day_id, day_volume,
+        day_turnover, raw_flags, direction, is_eth, scope,
+    }
+    SummaryData => Summary {
+        day_id, day_open_price, day_high_price, day_low_price, day_close_price, prev_day_id,
+        prev_day_close_price, prev_day_volume, open_interest, raw_flags, exchange_code,
+        day_close_price_type, prev_day_close_price_type, scope,
+    }
+    ProfileEventData => Profile {
+        beta, eps, div_freq, exd_div_amount, exd_div_date, high_52_week_price, low_52_week_price,
+        shares, free_float, high_limit_price, low_limit_price, halt_start_time, halt_end_time,
+        raw_flags, description, status_reason, trading_status, ssr,
+    }
+    OrderEventData => Order {
+        source, event_flags, index, time, sequence, time_nanos, action, action_time, order_id,
+        aux_order_id, price, size, executed_size, count, trade_id, trade_price, trade_size,
+        exchange_code, side, scope, mm_or_spread,
+    }
+    TimeAndSaleData => TimeAndSale {
+        event_flags, index, time, exchange_code, price, size, bid_price, ask_price,
+        exchange_sale_conditions, raw_flags, buyer, seller, side, kind, is_valid_tick,
+        is_eth_trade, trade_through_exempt(wide_char), is_spread_leg, scope,
+    }
+    CandleData => Candle {
+        event_flags, index, time, sequence, count, open, high, low, close, volume, vwap,
+        bid_volume, ask_volume, open_interest, imp_volatility,
+    }
+    SpreadOrderData => SpreadOrder {
+        index, time, time_nanos, sequence, action_time, order_id, aux_order_id, price, size,
+        executed_size, count, flags, trade_id, trade_price, trade_size, spread_symbol, action,
+        exchange_code, side, scope,
+    }
+    GreeksData => Greeks {
+        event_flags, index, time, price, volatility, delta, gamma, theta, rho, vega,
+    }
+    TheoPriceData => TheoPrice {
+        time, price, underlying_price, delta, gamma, dividend, interest,
+    }
+    UnderlyingData => Underlying {
+        volatility, front_volatility, back_volatility, call_volume, put_volume, option_volume,
+        put_call_ratio,
+    }
+    SeriesData => Series {
+        event_flags, index, time, sequence, expiration, volatility, call_volume, put_volume,
+        option_volume, put_call_ratio, forward_price, dividend, interest,
+    }
+    ConfigurationData => Configuration {
+        version, object,
+    }
+}
+
+impl From<&QuoteData> for Quote {
+    fn from(quote: &QuoteData) -> Self {
+        Quote {
+            time: quote.time,
+            sequence: quote.sequence,
+            time_nanos: quote.time_nanos,
+            bid_time: quote.bid.time,
+            bid_exchange_code: quote.bid.exchange.to_proto(),
+            bid_price: quote.bid.price,
+            bid_size: quote.bid.size,
+            ask_time: quote.ask.time,
+            ask_exchange_code: quote.ask.exchange.to_proto(),
+            ask_price: quote.ask.price,
+            ask_size: quote.ask.size,
+            scope: quote.scope.to_proto(),
+        }
+    }
+}
+
+impl TryFrom<Quote> for QuoteData {
+    type Error = Error;
+
+    fn try_from(quote: Quote) -> Result<Self, Error> {
+        Ok(QuoteData {
+            time: quote.time,
+            sequence: quote.sequence,
+            time_nanos: quote.time_nanos,
+            bid: QuoteSide {
+                price: quote.bid_price,
+                size: quote.bid_size,
+                exchange: char::from_proto(quote.bid_exchange_code)?,
+                time: quote.bid_time,
+            },
+            ask: QuoteSide {
+                price: quote.ask_price,
+                size: quote.ask_size,
+                exchange: char::from_proto(quote.ask_exchange_code)?,
+                time: quote.ask_time,
+            },
+            scope: Scope::from_proto(quote.scope)?,
+        })
+    }
+}
+
+// `From<&EventData> for event::Data` and back, variant by variant
+macro_rules! event_data {
+    ($($variant:ident => $data:ident),* $(,)?) => {
+        impl From<&EventData> for event::Data {
+            fn from(data: &EventData) -> Self {
+                match data {
+                    $(EventData::$variant(data) => event::Data::$data(data.into()),)*
+                }
+            }
+        }
+
+        impl TryFrom<event::Data> for EventData {
+            type Error = Error;
+
+            fn try_from(data: event::Data) -> Result<Self, Error> {
+                Ok(match data {
+                    $(event::Data::$data(data) => EventData::$variant(data.try_into()?),)*
+                })
+            }
+        }
+    };
+}
+
+event_data! {
+    Trade => Trade,
+    Quote => Quote,
+    Summary => Summary,
+    Profile => Profile,
+    Order => Order,
+    TimeAndSale => TimeAndSale,
+    Candle => Candle,
+    TradeETH => TradeEth,
+    SpreadOrder => SpreadOrder,
+    Greeks => Greeks,
+    TheoPrice => TheoPrice,
+    Underlying => Underlying,
+    Series => Series,
+    Configuration => Configuration,
+}
+
+impl From<&crate::Event> for Event {
+    fn from(event: &crate::Event) -> Self {
+        Event {
+            symbol: event.sym.clone(),
+            data: Some((&event.data).into()),
+        }
+    }
+}
+
+impl TryFrom<Event> for crate::Event {
+    type Error = Error;
+
+    fn try_from(event: Event) -> Result<Self, Error> {
+        let data = event.data.ok_or(Error::MissingProtoField("data"))?;
+        Ok(crate::Event::new(event.symbol, data.try_into()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost::Message;
+
+    #[test]
+    fn proto_round_trips() {
+        let mut quote = QuoteData {
+            time: 1_700_000_000_123,
+            scope: Scope::Regional,
+            ..Default::default()
+        };
+        quote.bid.exchange = 'Q';
+        quote.ask.price = 101.25;
+        let tns = TimeAndSaleData {
+            trade_through_exempt: 'X' as dxf_char_t,
+            side: Side::Sell,
+            ..Default::default()
+        };
+        let candle = CandleData {
+            vwap: Some(10.5),
+            ..Default::default()
+        };
+        let trade = TradeEthData {
+            exchange_code: 'Z',
+            price: 99.5,
+            ..Default::default()
+        };
+        let events = [
+            EventData::Quote(quote),
+            EventData::TimeAndSale(tns),
+            EventData::Candle(candle),
+            EventData::TradeETH(trade),
+            EventData::Configuration(ConfigurationData {
+                version: 2,
+                object: "{}".to_string(),
+            }),
+        ];
+        for data in events {
+            let event = crate::Event::new("AAPL".to_string(), data);
+            let bytes = Event::from(&event).encode_to_vec();
+            let back = crate::Event::try_from(Event::decode(bytes.as_slice()).unwrap()).unwrap();
+            assert_eq!(back.sym, "AAPL");
+            assert_eq!(format!("{:?}", back.data), format!("{:?}", event.data));
+        }
+
+        let message = Quote::from(&quote);
+        assert_eq!(message.bid_exchange_code, "Q");
+        assert_eq!(message.ask_exchange_code, "");
+        assert!(Candle::from(&candle).bid_volume.is_nan());
+        let invalid = Quote {
+            scope: 9,
+            ..message
+        };
+        assert!(QuoteData::try_from(invalid).is_err());
+        let empty = Event {
+            symbol: "AAPL".to_string(),
+            data: None,
+        };
+        assert!(crate::Event::try_from(empty).is_err());
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{
+    dx_spread_order, dxf_candle_t, dxf_configuration_t, dxf_const_string_t, dxf_event_data_t,
+    dxf_greeks_t, dxf_order_t, dxf_profile_t, dxf_quote_t, dxf_series_t, dxf_summary_t,
+    dxf_theo_price_t, dxf_time_and_sale_t, dxf_trade_eth_t, dxf_trade_t, dxf_underlying_t, utf,
+    Error, DXF_ET_CANDLE, DXF_ET_CONFIGURATION, DXF_ET_GREEKS, DXF_ET_ORDER, DXF_ET_PROFILE,
+    DXF_ET_QUOTE, DXF_ET_SERIES, DXF_ET_SPREAD_ORDER, DXF_ET_SUMMARY, DXF_ET_THEO_PRICE,
+    DXF_ET_TIME_AND_SALE, DXF_ET_TRADE, DXF_ET_TRADE_ETH, DXF_ET_UNDERLYING,
+};
+use std::os::raw::c_int;
+
+/// An event's C struct, borrowed for the duration of a listener callback. Unlike `EventData`,
+/// no strings are decoded or copied.
+#[derive(Clone, Copy)]
+pub enum RawEventData<'a> {
+    Trade(&'a dxf_trade_t),
+    Quote(&'a dxf_quote_t),
+    Summary(&'a dxf_summary_t),
+    Profile(&'a dxf_profile_t),
+    Order(&'a dxf_order_t),
+    TimeAndSale(&'a dxf_time_and_sale_t),
+    Candle(&'a dxf_candle_t),
+    TradeETH(&'a dxf_trade_eth_t),
+    SpreadOrder(&'a dx_spread_order),
+    Greeks(&'a dxf_greeks_t),
+    TheoPrice(&'a dxf_theo_price_t),
+    Underlying(&'a dxf_underlying_t),
+    Series(&'a dxf_series_t),
+    Configuration(&'a dxf_configuration_t),
+}
+
+impl<'a> RawEventData<'a> {
+    /// # Safety
+    /// `data` must be null, failing with `Error::NullPointer`, or point to a valid struct of the
+    /// type `event_type` names, which outlives `'a`.
+    pub unsafe fn from_c(
+        event_type: c_int,
+        data: *const dxf_event_data_t,
+    ) -> Result<RawEventData<'a>, Error> {
+        if data.is_null() {
+            return Err(Error::NullPointer);
+        }
+        let data = data as *const u8;
+        Ok(match event_type {
+            DXF_ET_TRADE => RawEventData::Trade(&*(data as *const _)),
+            DXF_ET_QUOTE => RawEventData::Quote(&*(data as *const _)),
+            DXF_ET_SUMMARY => RawEventData::Summary(&*(data as *const _)),
+            DXF_ET_PROFILE => RawEventData::Profile(&*(data as *const _)),
+            DXF_ET_ORDER => RawEventData::Order(&*(data as *const _)),
+            DXF_ET_TIME_AND_SALE => RawEventData::TimeAndSale(&*(data as *const _)),
+            DXF_ET_CANDLE => RawEventData::Candle(&*(data as *const _)),
+            DXF_ET_TRADE_ETH => RawEventData::TradeETH(&*(data as *const _)),
+            DXF_ET_SPREAD_ORDER => RawEventData::SpreadOrder(&*(data as *const _)),
+            DXF_ET_GREEKS => RawEventData::Greeks(&*(data as *const _)),
+            DXF_ET_THEO_PRICE => RawEventData::TheoPrice(&*(data as *const _)),
+            DXF_ET_UNDERLYING => RawEventData::Underlying(&*(data as *const _)),
+            DXF_ET_SERIES => RawEventData::Series(&*(data as *const _)),
+            DXF_ET_CONFIGURATION => RawEventData::Configuration(&*(data as *const _)),
+            _ => return Err(Error::Invalid(event_type)),
+        })
+    }
+}
+
+/// An event as delivered to `Subscription::attach_borrowed`: the decoded symbol and the
+/// borrowed C struct.
+#[derive(Clone, Copy)]
+pub struct RawEvent<'a> {
+    pub sym: &'a str,
+    pub data: RawEventData<'a>,
+}
+
+/// Decode `raw_sym` into `buf` and borrow `data`, reusing `buf`'s allocation across calls.
+///
+/// # Safety
+/// As for `RawEventData::from_c`, and `raw_sym` must be null or a valid nul-terminated wide string.
+pub(crate) unsafe fn decode<'a>(
+    buf: &'a mut String,
+    event_type: c_int,
+    raw_sym: dxf_const_string_t,
+    data: *const dxf_event_data_t,
+) -> Result<RawEvent<'a>, Error> {
+    let data = RawEventData::from_c(event_type, data)?;
+    utf::decode_into(buf, raw_sym)?;
+    Ok(RawEvent { sym: buf, data })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use widestring::WideCString;
+
+    #[test]
+    fn borrow_quote() {
+        let sym = WideCString::from_str("AAPL").unwrap();
+        let mut quote: dxf_quote_t = unsafe { std::mem::zeroed() };
+        quote.bid_price = 189.5;
+        let mut buf = String::new();
+        let event = unsafe {
+            decode(
+                &mut buf,
+                DXF_ET_QUOTE,
+                sym.as_ptr() as dxf_const_string_t,
+                &quote as *const dxf_quote_t as *const dxf_event_data_t,
+            )
+        }
+        .unwrap();
+        assert_eq!(event.sym, "AAPL");
+        assert!(matches!(event.data, RawEventData::Quote(q) if q.bid_price == 189.5));
+
+        let invalid = unsafe {
+            decode(
+                &mut buf,
+                0,
+                sym.as_ptr() as dxf_const_string_t,
+                &quote as *const dxf_quote_t as *const dxf_event_data_t,
+            )
+        };
+        assert!(matches!(invalid, Err(Error::Invalid(0))));
+    }
+}
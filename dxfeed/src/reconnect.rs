@@ -0,0 +1,390 @@
+use crate::{
+    dxf_connection_status_t, Connection, ConnectionBuilder, Error, Event, EventTypeMask,
+    Subscription,
+};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+
+type SharedListener = Arc<Mutex<Box<dyn FnMut(Result<Event, Error>) + Send>>>;
+
+/// Exponential backoff between reconnect attempts.
+#[derive(Debug, Clone, Copy)]
+pub struct Backoff {
+    /// The delay before the first attempt
+    pub initial: Duration,
+    /// The longest delay between attempts
+    pub max: Duration,
+    /// The factor the delay grows by after each failed attempt
+    pub multiplier: f64,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Backoff {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(60),
+            multiplier: 2.0,
+        }
+    }
+}
+
+impl Backoff {
+    /// The delay before attempt `attempt`, counting from 0.
+    fn delay(&self, attempt: u32) -> Duration {
+        let factor = self
+            .multiplier
+            .max(1.0)
+            .powi(attempt.min(i32::MAX as u32) as i32);
+        // Capped in f64, as the factor may overflow a `Duration`
+        Duration::from_secs_f64((self.initial.as_secs_f64() * factor).min(self.max.as_secs_f64()))
+    }
+}
+
+/// A subscription as `ReconnectingConnection` re-creates it, along with the live one.
+struct Entry {
+    event_types: EventTypeMask,
+    symbols: BTreeSet<String>,
+    listener: SharedListener,
+    live: Option<Subscription>,
+}
+
+impl Entry {
+    fn open(&mut self, connection: &Connection) -> Result<(), Error> {
+        let mut subscription = Subscription::new(connection, self.event_types)?;
+        let listener = self.listener.clone();
+        subscription.attach(move |event| (listener.lock().unwrap())(event))?;
+        let symbols: Vec<&str> = self.symbols.iter().map(String::as_str).collect();
+        if !symbols.is_empty() {
+            subscription.add_symbols(&symbols)?;
+        }
+        self.live = Some(subscription);
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct State {
+    connection: Option<Connection>,
+    // Incremented per connection, so a stale termination is ignored
+    generation: u64,
+    subscriptions: BTreeMap<u64, Entry>,
+    next_id: u64,
+}
+
+struct Inner {
+    /// In order of preference, the primary first
+    addresses: Vec<String>,
+    /// Index into `addresses` of the current, or last, connection
+    active: AtomicUsize,
+    backoff: Backoff,
+    state: Mutex<State>,
+    status: Arc<AtomicU32>,
+    reconnects: AtomicU64,
+    resubscribe_failures: AtomicU64,
+}
+
+impl Inner {
+    /// Open a connection to the first address that accepts one, reporting its termination as
+    /// `generation` on `terminated`.
+    fn establish(&self, generation: u64, terminated: &Sender<u64>) -> Result<Connection, Error> {
+        let (active, connection) = first_ok(&self.addresses, |address| {
+            let terminated = terminated.clone();
+            let status = self.status.clone();
+            ConnectionBuilder::new(address)
+                .on_termination(move || {
+                    let _ = terminated.send(generation);
+                })
+                .on_status_change(move |_, new| status.store(new, Ordering::Relaxed))
+                .build()
+        })?;
+        self.active.store(active, Ordering::Relaxed);
+        Ok(connection)
+    }
+
+    /// Replace the terminated connection, retrying with backoff until one is established or
+    /// the `ReconnectingConnection` is dropped.
+    fn reconnect(self: &Arc<Self>, terminated: &Sender<u64>) {
+        // Closed outside the lock, and off the socket thread that reported the termination
+        let (old_connection, old_subscriptions, generation) = {
+            let mut state = self.state.lock().unwrap();
+            state.generation += 1;
+            let old: Vec<Subscription> = state
+                .subscriptions
+                .values_mut()
+                .filter_map(|entry| entry.live.take())
+                .collect();
+            (state.connection.take(), old, state.generation)
+        };
+        drop(old_subscriptions);
+        drop(old_connection);
+
+        for attempt in 0.. {
+            std::thread::sleep(self.backoff.delay(attempt));
+            if Arc::strong_count(self) == 1 {
+                return;
+            }
+            let Ok(connection) = self.establish(generation, terminated) else {
+                continue;
+            };
+            self.state.lock().unwrap().connection = Some(connection);
+            self.reconnects.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+    }
+
+    /// Open the subscriptions that are not live on the current connection, returning false if
+    /// any failed, to be retried.
+    fn resubscribe(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+        let Some(connection) = &state.connection else {
+            return true;
+        };
+        let mut all_open = true;
+        for entry in state.subscriptions.values_mut() {
+            if entry.live.is_none() && entry.open(connection).is_err() {
+                self.resubscribe_failures.fetch_add(1, Ordering::Relaxed);
+                all_open = false;
+            }
+        }
+        all_open
+    }
+}
+
+/// The index and result of the first of `addresses` that `connect` succeeds with, or the last
+/// error.
+fn first_ok<T, F>(addresses: &[String], mut connect: F) -> Result<(usize, T), Error>
+where
+    F: FnMut(&str) -> Result<T, Error>,
+{
+    let mut last_error = Error::Unknown;
+    for (i, address) in addresses.iter().enumerate() {
+        match connect(address) {
+            Ok(connection) => return Ok((i, connection)),
+            Err(e) => last_error = e,
+        }
+    }
+    Err(last_error)
+}
+
+/// A connection that re-establishes itself with exponential backoff when terminated, and
+/// re-creates its subscriptions, with their listeners and symbols, on the new connection.
+///
+/// Given several addresses, as by `connect_failover`, each attempt tries them in order, so a
+/// backup takes over when the primary is unreachable, and the primary is preferred again at the
+/// next reconnect.
+///
+/// A subscription that fails to be re-created is retried with the same backoff, and counted by
+/// `failed_resubscriptions`. Reconnecting runs on a supervisor thread, which exits once this and
+/// its subscriptions are dropped.
+pub struct ReconnectingConnection {
+    inner: Arc<Inner>,
+}
+
+impl ReconnectingConnection {
+    /// Connect to `address`. The first connection must succeed; later ones are retried.
+    pub fn connect(address: &str, backoff: Backoff) -> Result<Self, Error> {
+        Self::connect_failover(&[address], backoff)
+    }
+
+    /// Connect to the first reachable of `addresses`, the primary first and then the backups,
+    /// e.g. endpoints in different data centers. One of them must accept the first connection.
+    pub fn connect_failover(addresses: &[&str], backoff: Backoff) -> Result<Self, Error> {
+        if addresses.is_empty() {
+            return Err(Error::InvalidValue("address count", 0));
+        }
+        let (terminated, terminations) = channel();
+        let inner = Arc::new(Inner {
+            addresses: addresses
+                .iter()
+                .map(|address| address.to_string())
+                .collect(),
+            active: AtomicUsize::new(0),
+            backoff,
+            state: Mutex::default(),
+            status: Arc::default(),
+            reconnects: AtomicU64::new(0),
+            resubscribe_failures: AtomicU64::new(0),
+        });
+        let connection = inner.establish(0, &terminated)?;
+        inner.state.lock().unwrap().connection = Some(connection);
+
+        let weak: Weak<Inner> = Arc::downgrade(&inner);
+        std::thread::Builder::new()
+            .name("dxfeed-reconnect".to_string())
+            .spawn(move || {
+                // The attempt at re-creating the subscriptions that failed to, if any did
+                let mut retry: Option<u32> = None;
+                loop {
+                    let timeout =
+                        retry.map_or(Duration::from_secs(1), |attempt| backoff.delay(attempt));
+                    match terminations.recv_timeout(timeout) {
+                        Ok(generation) => {
+                            let Some(inner) = weak.upgrade() else { return };
+                            if generation == inner.state.lock().unwrap().generation {
+                                inner.reconnect(&terminated);
+                                retry = (!inner.resubscribe()).then_some(0);
+                            }
+                        }
+                        Err(RecvTimeoutError::Timeout) => {
+                            let Some(inner) = weak.upgrade() else { return };
+                            if let Some(attempt) = retry {
+                                retry = (!inner.resubscribe()).then_some(attempt.saturating_add(1));
+                            }
+                        }
+                        Err(_) => return,
+                    }
+                }
+            })?;
+        Ok(ReconnectingConnection { inner })
+    }
+
+    /// Subscribe to `event_types`, an `EventTypeMask` or `DXF_ET_*` mask, delivering to `listener`
+    /// across reconnects. Events are missed while disconnected.
+    pub fn subscribe<F>(
+        &self,
+        event_types: impl Into<EventTypeMask>,
+        listener: F,
+    ) -> Result<ReconnectingSubscription, Error>
+    where
+        F: FnMut(Result<Event, Error>) + Send + 'static,
+    {
+        let mut entry = Entry {
+            event_types: event_types.into(),
+            symbols: BTreeSet::new(),
+            listener: Arc::new(Mutex::new(Box::new(listener))),
+            live: None,
+        };
+        let mut state = self.inner.state.lock().unwrap();
+        if let Some(connection) = &state.connection {
+            entry.open(connection)?;
+        }
+        let id = state.next_id;
+        state.next_id += 1;
+        state.subscriptions.insert(id, entry);
+        Ok(ReconnectingSubscription {
+            id,
+            inner: self.inner.clone(),
+        })
+    }
+
+    /// The last status the C API reported, e.g. `dxf_connection_status_t_dxf_cs_connected`.
+    pub fn status(&self) -> dxf_connection_status_t {
+        self.inner.status.load(Ordering::Relaxed)
+    }
+
+    /// The address of the current connection, or of the last one while reconnecting.
+    pub fn address(&self) -> &str {
+        &self.inner.addresses[self.inner.active.load(Ordering::Relaxed)]
+    }
+
+    /// The number of times the connection has been re-established.
+    pub fn reconnects(&self) -> u64 {
+        self.inner.reconnects.load(Ordering::Relaxed)
+    }
+
+    /// The number of times re-creating a subscription on a new connection failed. Each that
+    /// fails is retried with the same backoff as reconnecting, until it is live again.
+    pub fn failed_resubscriptions(&self) -> u64 {
+        self.inner.resubscribe_failures.load(Ordering::Relaxed)
+    }
+}
+
+/// A subscription of a `ReconnectingConnection`, closed when dropped.
+pub struct ReconnectingSubscription {
+    id: u64,
+    inner: Arc<Inner>,
+}
+
+impl ReconnectingSubscription {
+    /// Add `symbols`, kept for re-creating the subscription.
+    pub fn add_symbols(&self, symbols: &[&str]) -> Result<(), Error> {
+        let mut state = self.inner.state.lock().unwrap();
+        let Some(entry) = state.subscriptions.get_mut(&self.id) else {
+            return Ok(());
+        };
+        entry.symbols.extend(symbols.iter().map(|s| s.to_string()));
+        match &entry.live {
+            Some(live) => live.add_symbols(symbols),
+            None => Ok(()),
+        }
+    }
+
+    pub fn remove_symbols(&self, symbols: &[&str]) -> Result<(), Error> {
+        let mut state = self.inner.state.lock().unwrap();
+        let Some(entry) = state.subscriptions.get_mut(&self.id) else {
+            return Ok(());
+        };
+        for symbol in symbols {
+            entry.symbols.remove(*symbol);
+        }
+        match &entry.live {
+            Some(live) => live.remove_symbols(symbols),
+            None => Ok(()),
+        }
+    }
+
+    /// The symbols kept for this subscription, whether or not it is currently live.
+    pub fn symbols(&self) -> Vec<String> {
+        let state = self.inner.state.lock().unwrap();
+        state
+            .subscriptions
+            .get(&self.id)
+            .map_or_else(Vec::new, |entry| entry.symbols.iter().cloned().collect())
+    }
+}
+
+impl Drop for ReconnectingSubscription {
+    fn drop(&mut self) {
+        let entry = self
+            .inner
+            .state
+            .lock()
+            .unwrap()
+            .subscriptions
+            .remove(&self.id);
+        // Closed outside the lock
+        drop(entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_grows_to_max() {
+        let backoff = Backoff {
+            initial: Duration::from_millis(100),
+            max: Duration::from_secs(1),
+            multiplier: 2.0,
+        };
+        let delays: Vec<Duration> = (0..6).map(|attempt| backoff.delay(attempt)).collect();
+        assert_eq!(
+            delays,
+            [100, 200, 400, 800, 1000, 1000].map(Duration::from_millis)
+        );
+        assert_eq!(backoff.delay(u32::MAX), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn fails_over_in_order() {
+        let addresses = ["primary:7300", "backup:7300", "dr:7300"].map(String::from);
+        let mut tried = Vec::new();
+        let connected = first_ok(&addresses, |address| {
+            tried.push(address.to_string());
+            match address {
+                "primary:7300" => Err(Error::CallFailed("dxf_create_connection")),
+                _ => Ok(address.to_string()),
+            }
+        });
+        assert_eq!(connected.unwrap(), (1, "backup:7300".to_string()));
+        assert_eq!(tried, ["primary:7300", "backup:7300"]);
+
+        let unreachable = first_ok(&addresses, |_| -> Result<(), Error> { Err(Error::Timeout) });
+        assert!(matches!(unreachable, Err(Error::Timeout)));
+    }
+}
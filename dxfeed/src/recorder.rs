@@ -0,0 +1,312 @@
+//! Recording events as newline-delimited JSON, one line of `Event`'s serde serialization per
+//! event, into files that rotate by size and age. Enabled by the `recorder` feature.
+//!
+//! ```ignore
+//! let options = RecorderOptions {
+//!     gzip: true,
+//!     ..Default::default()
+//! };
+//! let mut recorder = NdjsonRecorder::new("captures", "quotes", options)?;
+//! subscription.attach(move |event| {
+//!     if let Ok(event) = event {
+//!         let _ = recorder.write(&event);
+//!     }
+//! })?;
+//! ```
+//!
+//! Files are named `{prefix}-{millis}.ndjson`, or `.ndjson.gz`, by when they were opened. Only
+//! whole lines are written, and every `flush_interval` they are flushed and synced to disk, so a
+//! crash loses at most that interval. Gzipped files are sync-flushed too: a crashed file is
+//! missing its trailer, but everything up to the last flush decompresses.
+
+use crate::{Error, Event};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Options for `NdjsonRecorder`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecorderOptions {
+    /// Rotate once a file holds this many bytes of JSON, before compression
+    pub max_bytes: Option<u64>,
+    /// Rotate once a file has been open this long
+    pub max_age: Option<Duration>,
+    /// Gzip each file
+    pub gzip: bool,
+    /// How often written lines are flushed and synced to disk
+    pub flush_interval: Duration,
+}
+
+impl Default for RecorderOptions {
+    fn default() -> Self {
+        RecorderOptions {
+            max_bytes: Some(256 * 1024 * 1024),
+            max_age: Some(Duration::from_secs(3600)),
+            gzip: false,
+            flush_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+enum Output {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+}
+
+impl Output {
+    fn writer(&mut self) -> &mut dyn Write {
+        match self {
+            Output::Plain(writer) => writer,
+            Output::Gzip(encoder) => encoder,
+        }
+    }
+
+    /// Flush everything written to the file, and sync it to disk.
+    fn sync(&mut self) -> io::Result<()> {
+        let file = match self {
+            Output::Plain(writer) => {
+                writer.flush()?;
+                writer.get_ref()
+            }
+            Output::Gzip(encoder) => {
+                encoder.flush()?;
+                encoder.get_ref().get_ref()
+            }
+        };
+        file.sync_data()
+    }
+
+    fn finish(self) -> io::Result<()> {
+        let writer = match self {
+            Output::Plain(writer) => writer,
+            Output::Gzip(encoder) => encoder.finish()?,
+        };
+        writer
+            .into_inner()
+            .map_err(|err| err.into_error())?
+            .sync_all()
+    }
+}
+
+struct RecordingFile {
+    output: Output,
+    bytes: u64,
+    opened: Instant,
+}
+
+/// Writes events to rotating newline-delimited JSON files in a directory. The current file is
+/// finished when the recorder is closed or dropped.
+pub struct NdjsonRecorder {
+    directory: PathBuf,
+    prefix: String,
+    options: RecorderOptions,
+    file: Option<RecordingFile>,
+    last_flush: Instant,
+    last_millis: u128,
+    unsynced: bool,
+}
+
+impl NdjsonRecorder {
+    /// Record into `directory`, creating it if needed, in files named after `prefix`.
+    pub fn new<P: AsRef<Path>>(
+        directory: P,
+        prefix: &str,
+        options: RecorderOptions,
+    ) -> Result<Self, Error> {
+        fs::create_dir_all(directory.as_ref())?;
+        Ok(NdjsonRecorder {
+            directory: directory.as_ref().to_path_buf(),
+            prefix: prefix.to_string(),
+            options,
+            file: None,
+            last_flush: Instant::now(),
+            last_millis: 0,
+            unsynced: false,
+        })
+    }
+
+    /// The file being written, if any.
+    pub fn current_path(&self) -> Option<PathBuf> {
+        self.file.as_ref().map(|_| self.path(self.last_millis))
+    }
+
+    /// Write `event` as a line, rotating first if the current file is full or old enough.
+    pub fn write(&mut self, event: &Event) -> Result<(), Error> {
+        let mut line = serde_json::to_vec(event).map_err(io::Error::from)?;
+        line.push(b'\n');
+        if self.file.as_ref().is_some_and(|file| self.is_due(file)) {
+            self.rotate()?;
+        }
+        if self.file.is_none() {
+            self.file = Some(self.open()?);
+        }
+        let file = self.file.as_mut().expect("opened above");
+        file.output.writer().write_all(&line)?;
+        file.bytes += line.len() as u64;
+        self.unsynced = true;
+        if self.last_flush.elapsed() >= self.options.flush_interval {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Flush written lines and sync them to disk.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.last_flush = Instant::now();
+        if let Some(file) = self.file.as_mut().filter(|_| self.unsynced) {
+            file.output.sync()?;
+            self.unsynced = false;
+        }
+        Ok(())
+    }
+
+    /// Finish the current file. The next write opens a new one.
+    pub fn rotate(&mut self) -> Result<(), Error> {
+        self.unsynced = false;
+        if let Some(file) = self.file.take() {
+            file.output.finish()?;
+        }
+        Ok(())
+    }
+
+    /// Finish the current file.
+    pub fn close(mut self) -> Result<(), Error> {
+        self.rotate()
+    }
+
+    fn is_due(&self, file: &RecordingFile) -> bool {
+        self.options.max_bytes.is_some_and(|max| file.bytes >= max)
+            || self
+                .options
+                .max_age
+                .is_some_and(|max| file.opened.elapsed() >= max)
+    }
+
+    fn path(&self, millis: u128) -> PathBuf {
+        let extension = if self.options.gzip {
+            "ndjson.gz"
+        } else {
+            "ndjson"
+        };
+        self.directory
+            .join(format!("{}-{}.{}", self.prefix, millis, extension))
+    }
+
+    fn open(&mut self) -> Result<RecordingFile, Error> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        // Files rotated within the same millisecond still get distinct names
+        self.last_millis = now.max(self.last_millis + 1);
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(self.path(self.last_millis))?;
+        let writer = BufWriter::new(file);
+        let output = if self.options.gzip {
+            Output::Gzip(GzEncoder::new(writer, Compression::default()))
+        } else {
+            Output::Plain(writer)
+        };
+        Ok(RecordingFile {
+            output,
+            bytes: 0,
+            opened: Instant::now(),
+        })
+    }
+}
+
+impl Drop for NdjsonRecorder {
+    fn drop(&mut self) {
+        let _ = self.rotate();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    fn files_in(directory: &Path) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = fs::read_dir(directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn rotates_by_size() {
+        let directory =
+            std::env::temp_dir().join(format!("dxfeed-recorder-{}", std::process::id()));
+        let line_len = serde_json::to_vec(&testing::quote().sequence(0).event("AAPL"))
+            .unwrap()
+            .len() as u64
+            + 1;
+        let options = RecorderOptions {
+            max_bytes: Some(2 * line_len),
+            max_age: None,
+            gzip: false,
+            flush_interval: Duration::ZERO,
+        };
+        let mut recorder = NdjsonRecorder::new(&directory, "quotes", options).unwrap();
+        for sequence in 0..5 {
+            recorder
+                .write(&testing::quote().sequence(sequence).event("AAPL"))
+                .unwrap();
+        }
+        // Flushed on every write
+        let current = recorder.current_path().unwrap();
+        assert_eq!(fs::read_to_string(current).unwrap().lines().count(), 1);
+        recorder.close().unwrap();
+
+        let files = files_in(&directory);
+        assert_eq!(files.len(), 3);
+        let lines: Vec<String> = files
+            .iter()
+            .flat_map(|path| {
+                let text = fs::read_to_string(path).unwrap();
+                text.lines().map(str::to_string).collect::<Vec<_>>()
+            })
+            .collect();
+        assert_eq!(lines.len(), 5);
+        let last: Event = serde_json::from_str(&lines[4]).unwrap();
+        assert_eq!(last.data.as_quote().map(|quote| quote.sequence), Some(4));
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn gzipped_files() {
+        let directory =
+            std::env::temp_dir().join(format!("dxfeed-recorder-gz-{}", std::process::id()));
+        let options = RecorderOptions {
+            gzip: true,
+            ..Default::default()
+        };
+        let mut recorder = NdjsonRecorder::new(&directory, "quotes", options).unwrap();
+        recorder
+            .write(&testing::quote().sequence(1).event("AAPL"))
+            .unwrap();
+        recorder
+            .write(&testing::quote().sequence(2).event("AAPL"))
+            .unwrap();
+        drop(recorder);
+
+        let files = files_in(&directory);
+        assert_eq!(files.len(), 1);
+        assert!(files[0].to_string_lossy().ends_with(".ndjson.gz"));
+        let mut text = String::new();
+        GzDecoder::new(File::open(&files[0]).unwrap())
+            .read_to_string(&mut text)
+            .unwrap();
+        assert_eq!(text.lines().count(), 2);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
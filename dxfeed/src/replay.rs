@@ -0,0 +1,281 @@
+//! Replaying a capture file through the `FeedBackend` API, so code written against a live feed
+//! can be run offline against recorded data. Enabled by the `capture` feature.
+//!
+//! ```ignore
+//! let replay = ReplayConnection::open("session.dxcap", ReplaySpeed::Fastest)?;
+//! let (subscription, events) = replay.subscribe_stream(&[EventType::Quote])?;
+//! subscription.add_symbols(&["AAPL"])?;
+//! for event in events {
+//!     strategy.on_event(event?);
+//! }
+//! ```
+//!
+//! As with a live feed, a subscription receives the events of its types and symbols that are
+//! replayed while it is alive. Replay starts when a symbol is first added to a subscription, or
+//! on `start`, and runs on its own thread until the capture ends or the connection is dropped.
+//! The channel of `subscribe_stream` disconnects once replay has ended.
+
+use crate::{CaptureReader, Error, Event, EventType, FeedBackend, FeedSubscription};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// How fast a capture is replayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReplaySpeed {
+    /// Keep the spacing between events as they were received
+    #[default]
+    Original,
+    /// Deliver events as fast as listeners take them
+    Fastest,
+}
+
+type Listener = Box<dyn FnMut(Result<Event, Error>) + Send>;
+
+struct ReplayListener {
+    event_types: HashSet<EventType>,
+    symbols: Mutex<HashSet<String>>,
+    listener: Mutex<Listener>,
+}
+
+impl ReplayListener {
+    fn matches(&self, event: &Event) -> bool {
+        self.event_types.contains(&EventType::from(event))
+            && self.symbols.lock().unwrap().contains(&event.sym)
+    }
+}
+
+type Reader = CaptureReader<BufReader<File>>;
+
+struct Shared {
+    listeners: Mutex<Vec<Weak<ReplayListener>>>,
+    /// The capture, until replay starts
+    pending: Mutex<Option<Reader>>,
+    thread: Mutex<Option<JoinHandle<()>>>,
+    speed: ReplaySpeed,
+    closed: AtomicBool,
+}
+
+impl Shared {
+    /// The listeners of live subscriptions, forgetting dropped ones.
+    fn listeners(&self) -> Vec<Arc<ReplayListener>> {
+        let mut listeners = self.listeners.lock().unwrap();
+        listeners.retain(|listener| listener.strong_count() > 0);
+        listeners.iter().filter_map(Weak::upgrade).collect()
+    }
+
+    fn start(self: &Arc<Self>) -> Result<(), Error> {
+        let reader = match self.pending.lock().unwrap().take() {
+            Some(reader) => reader,
+            None => return Ok(()),
+        };
+        let shared = self.clone();
+        let thread = std::thread::Builder::new()
+            .name("dxfeed-replay".to_string())
+            .spawn(move || replay(reader, &shared))?;
+        *self.thread.lock().unwrap() = Some(thread);
+        Ok(())
+    }
+}
+
+/// A `FeedBackend` delivering the events of a capture file.
+pub struct ReplayConnection {
+    shared: Arc<Shared>,
+}
+
+impl ReplayConnection {
+    /// Open the capture file at `path`, to replay at `speed`.
+    pub fn open<P: AsRef<Path>>(path: P, speed: ReplaySpeed) -> Result<Self, Error> {
+        let reader = CaptureReader::open(path)?;
+        let shared = Shared {
+            listeners: Mutex::default(),
+            pending: Mutex::new(Some(reader)),
+            thread: Mutex::default(),
+            speed,
+            closed: AtomicBool::new(false),
+        };
+        Ok(ReplayConnection {
+            shared: Arc::new(shared),
+        })
+    }
+
+    /// Start replaying, if it hasn't started.
+    pub fn start(&self) -> Result<(), Error> {
+        self.shared.start()
+    }
+
+    /// Block until replay has ended. Returns immediately if it hasn't started.
+    pub fn wait(&self) {
+        let thread = self.shared.thread.lock().unwrap().take();
+        if let Some(thread) = thread {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for ReplayConnection {
+    fn drop(&mut self) {
+        self.shared.closed.store(true, Ordering::SeqCst);
+    }
+}
+
+fn replay(mut reader: Reader, shared: &Shared) {
+    // The reader times records from when it was opened; shift them to start now
+    let mut delay: Option<Duration> = None;
+    for (at, event) in reader.by_ref() {
+        if shared.closed.load(Ordering::SeqCst) {
+            return;
+        }
+        if shared.speed == ReplaySpeed::Original {
+            let delay = *delay.get_or_insert_with(|| Instant::now().saturating_duration_since(at));
+            if let Some(wait) = (at + delay).checked_duration_since(Instant::now()) {
+                std::thread::sleep(wait);
+            }
+        }
+        for listener in shared.listeners() {
+            if listener.matches(&event) {
+                (listener.listener.lock().unwrap())(Ok(event.clone()));
+            }
+        }
+    }
+    if let Some(err) = reader.take_error() {
+        for listener in shared.listeners() {
+            let err = io::Error::new(io::ErrorKind::InvalidData, err.to_string());
+            (listener.listener.lock().unwrap())(Err(Error::Io(err)));
+        }
+    }
+    // Dropping the listeners disconnects `subscribe_stream` channels
+    for listener in shared.listeners() {
+        *listener.listener.lock().unwrap() = Box::new(|_| {});
+    }
+}
+
+/// A subscription to a `ReplayConnection`, receiving events while it is alive.
+pub struct ReplaySubscription {
+    listener: Arc<ReplayListener>,
+    shared: Arc<Shared>,
+}
+
+impl FeedBackend for ReplayConnection {
+    type Subscription = ReplaySubscription;
+
+    /// Opens `address` as a capture file path, to replay at its original speed.
+    fn connect(address: &str) -> Result<Self, Error> {
+        ReplayConnection::open(address, ReplaySpeed::Original)
+    }
+
+    fn subscribe<F>(
+        &self,
+        event_types: &[EventType],
+        listener: F,
+    ) -> Result<ReplaySubscription, Error>
+    where
+        F: FnMut(Result<Event, Error>) + Send + 'static,
+    {
+        let listener = Arc::new(ReplayListener {
+            event_types: event_types.iter().copied().collect(),
+            symbols: Mutex::default(),
+            listener: Mutex::new(Box::new(listener)),
+        });
+        self.shared
+            .listeners
+            .lock()
+            .unwrap()
+            .push(Arc::downgrade(&listener));
+        Ok(ReplaySubscription {
+            listener,
+            shared: self.shared.clone(),
+        })
+    }
+}
+
+impl FeedSubscription for ReplaySubscription {
+    fn add_symbols(&self, symbols: &[&str]) -> Result<(), Error> {
+        self.listener
+            .symbols
+            .lock()
+            .unwrap()
+            .extend(symbols.iter().map(|symbol| symbol.to_string()));
+        // Replay starts with the first symbol, as a live feed's events would
+        self.shared.start()
+    }
+
+    fn remove_symbols(&self, symbols: &[&str]) -> Result<(), Error> {
+        let mut subscribed = self.listener.symbols.lock().unwrap();
+        for symbol in symbols {
+            subscribed.remove(*symbol);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CaptureWriter, EventData, QuoteData, TradeData};
+    use std::time::SystemTime;
+
+    fn capture_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("dxfeed-{}-{}.dxcap", name, std::process::id()))
+    }
+
+    #[test]
+    fn replays_subscribed_events() {
+        let path = capture_path("replay");
+        let mut writer = CaptureWriter::create(&path).unwrap();
+        let received = SystemTime::now();
+        for (i, symbol) in ["AAPL", "MSFT", "AAPL"].iter().enumerate() {
+            let quote = QuoteData {
+                sequence: i as i32,
+                ..Default::default()
+            };
+            let at = received + Duration::from_millis(i as u64);
+            writer
+                .write_at(at, &Event::new(symbol.to_string(), EventData::Quote(quote)))
+                .unwrap();
+        }
+        let trade = Event::new("AAPL".to_string(), EventData::Trade(TradeData::default()));
+        writer.write_at(received, &trade).unwrap();
+        writer.into_inner().unwrap();
+
+        let replay = ReplayConnection::open(&path, ReplaySpeed::Fastest).unwrap();
+        let (subscription, events) = replay.subscribe_stream(&[EventType::Quote]).unwrap();
+        subscription.add_symbols(&["AAPL"]).unwrap();
+        let sequences: Vec<i32> = events
+            .iter()
+            .map(|event| event.unwrap().data.as_quote().unwrap().sequence)
+            .collect();
+        assert_eq!(sequences, [0, 2]);
+        replay.wait();
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn keeps_spacing_when_started_late() {
+        let path = capture_path("replay-spacing");
+        let mut writer = CaptureWriter::create(&path).unwrap();
+        let received = SystemTime::now();
+        for i in 0..3 {
+            let quote = Event::new("AAPL".to_string(), EventData::Quote(QuoteData::default()));
+            let at = received + Duration::from_millis(100 * i);
+            writer.write_at(at, &quote).unwrap();
+        }
+        writer.into_inner().unwrap();
+
+        let replay = ReplayConnection::open(&path, ReplaySpeed::Original).unwrap();
+        let (subscription, events) = replay.subscribe_stream(&[EventType::Quote]).unwrap();
+        // Records due before replay starts must not arrive in a burst
+        std::thread::sleep(Duration::from_millis(300));
+        subscription.add_symbols(&["AAPL"]).unwrap();
+        let arrivals: Vec<Instant> = events.iter().map(|_| Instant::now()).collect();
+        assert_eq!(arrivals.len(), 3);
+        assert!(arrivals[2] - arrivals[0] >= Duration::from_millis(180));
+        replay.wait();
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,205 @@
+//! Delivery through a pre-allocated, lock-free single-producer/single-consumer ring buffer, for
+//! latency-sensitive consumers that poll on a dedicated thread.
+//!
+//! ```ignore
+//! let (subscription, mut ring) = connection.subscribe_ring(&[EventType::Quote], 65_536)?;
+//! subscription.add_symbols(&["AAPL"])?;
+//! while let Some(event) = ring.recv_spin() {
+//!     strategy.on_quote(event);
+//! }
+//! ```
+//!
+//! Delivery takes no lock and allocates nothing beyond the event itself. The producer never
+//! waits: events arriving while the ring is full are dropped and counted.
+
+use crate::Event;
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Keeps the producer's and consumer's positions on separate cache lines.
+#[repr(align(64))]
+struct Padded<T>(T);
+
+struct Shared {
+    slots: Box<[UnsafeCell<MaybeUninit<Event>>]>,
+    mask: usize,
+    /// Position of the next event to read, written only by the consumer
+    head: Padded<AtomicUsize>,
+    /// Position of the next event to write, written only by the producer
+    tail: Padded<AtomicUsize>,
+    dropped: AtomicU64,
+    producer_alive: AtomicBool,
+}
+
+// Each slot is accessed by one side at a time, handed over by the release/acquire of `head`
+// and `tail`
+unsafe impl Send for Shared {}
+unsafe impl Sync for Shared {}
+
+impl Drop for Shared {
+    fn drop(&mut self) {
+        let mut position = *self.head.0.get_mut();
+        let tail = *self.tail.0.get_mut();
+        while position != tail {
+            unsafe {
+                self.slots[position & self.mask]
+                    .get_mut()
+                    .assume_init_drop()
+            };
+            position = position.wrapping_add(1);
+        }
+    }
+}
+
+/// A ring of `capacity` events, rounded up to a power of two, and its producer side.
+pub(crate) fn ring(capacity: usize) -> (RingProducer, EventRing) {
+    let capacity = capacity.max(1).next_power_of_two();
+    let slots = (0..capacity)
+        .map(|_| UnsafeCell::new(MaybeUninit::uninit()))
+        .collect();
+    let shared = Arc::new(Shared {
+        slots,
+        mask: capacity - 1,
+        head: Padded(AtomicUsize::new(0)),
+        tail: Padded(AtomicUsize::new(0)),
+        dropped: AtomicU64::new(0),
+        producer_alive: AtomicBool::new(true),
+    });
+    (RingProducer(shared.clone()), EventRing(shared))
+}
+
+/// The writing side of an `EventRing`, disconnecting it when dropped.
+pub(crate) struct RingProducer(Arc<Shared>);
+
+impl RingProducer {
+    /// Write `event`, or drop it if the ring is full.
+    pub(crate) fn push(&mut self, event: Event) {
+        let shared = &*self.0;
+        let tail = shared.tail.0.load(Ordering::Relaxed);
+        let head = shared.head.0.load(Ordering::Acquire);
+        if tail.wrapping_sub(head) > shared.mask {
+            shared.dropped.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        unsafe { (*shared.slots[tail & shared.mask].get()).write(event) };
+        shared.tail.0.store(tail.wrapping_add(1), Ordering::Release);
+    }
+}
+
+impl Drop for RingProducer {
+    fn drop(&mut self) {
+        self.0.producer_alive.store(false, Ordering::Release);
+    }
+}
+
+/// Receives a subscription's events through a lock-free ring buffer. There is one consumer, so
+/// reading takes `&mut self`; move the ring to the thread that polls it.
+pub struct EventRing(Arc<Shared>);
+
+impl EventRing {
+    /// The next event, if one is waiting.
+    pub fn try_recv(&mut self) -> Option<Event> {
+        let shared = &*self.0;
+        let head = shared.head.0.load(Ordering::Relaxed);
+        let tail = shared.tail.0.load(Ordering::Acquire);
+        if head == tail {
+            return None;
+        }
+        let event = unsafe { (*shared.slots[head & shared.mask].get()).assume_init_read() };
+        shared.head.0.store(head.wrapping_add(1), Ordering::Release);
+        Some(event)
+    }
+
+    /// Busy-wait for the next event, returning None once the subscription is dropped and the
+    /// ring is drained. This keeps a core busy, as polling consumers do.
+    pub fn recv_spin(&mut self) -> Option<Event> {
+        loop {
+            if let Some(event) = self.try_recv() {
+                return Some(event);
+            }
+            if self.is_disconnected() {
+                // An event may have been written just before the producer was dropped
+                return self.try_recv();
+            }
+            std::hint::spin_loop();
+        }
+    }
+
+    /// Whether the subscription feeding the ring has been dropped.
+    pub fn is_disconnected(&self) -> bool {
+        !self.0.producer_alive.load(Ordering::Acquire)
+    }
+
+    /// Number of events waiting.
+    pub fn len(&self) -> usize {
+        let tail = self.0.tail.0.load(Ordering::Acquire);
+        tail.wrapping_sub(self.0.head.0.load(Ordering::Relaxed))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.0.slots.len()
+    }
+
+    /// Total events dropped because the ring was full.
+    pub fn dropped(&self) -> u64 {
+        self.0.dropped.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn sequence(event: Event) -> i32 {
+        event.data.as_quote().unwrap().sequence
+    }
+
+    #[test]
+    fn wraps_and_drops_when_full() {
+        let (mut producer, mut ring) = ring(3);
+        assert_eq!(ring.capacity(), 4);
+        for round in 0..3 {
+            for i in 0..5 {
+                producer.push(testing::quote().sequence(round * 10 + i).event("AAPL"));
+            }
+            assert_eq!(ring.len(), 4);
+            let received: Vec<i32> = std::iter::from_fn(|| ring.try_recv())
+                .map(sequence)
+                .collect();
+            assert_eq!(received, [0, 1, 2, 3].map(|i| round * 10 + i));
+        }
+        assert_eq!(ring.dropped(), 3);
+
+        // Events left in the ring are dropped with it
+        producer.push(testing::quote().sequence(0).event("AAPL"));
+        drop(producer);
+        assert!(ring.is_disconnected());
+        drop(ring);
+    }
+
+    #[test]
+    fn delivers_across_threads_in_order() {
+        let (mut producer, mut ring) = ring(64);
+        let thread = std::thread::spawn(move || {
+            for i in 0..10_000 {
+                producer.push(testing::quote().sequence(i).event("AAPL"));
+            }
+        });
+        let (mut last, mut received) = (-1, 0);
+        while let Some(event) = ring.recv_spin() {
+            let sequence = sequence(event);
+            assert!(sequence > last);
+            last = sequence;
+            received += 1;
+        }
+        thread.join().unwrap();
+        assert_eq!(received + ring.dropped(), 10_000);
+    }
+}
@@ -0,0 +1,8 @@
+//! Servers that expose one upstream feed to clients in other processes and languages.
+
+#[cfg(any(feature = "jsonl", feature = "ws"))]
+pub(crate) mod clients;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(feature = "ws")]
+pub mod ws;
@@ -0,0 +1,214 @@
+//! What the servers sending JSON events to TCP clients share: each client's filter, the
+//! registry events are published to, and the thread accepting connections.
+
+use crate::{Error, Event, EventType};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+
+/// Messages buffered per client. A client that falls further behind is disconnected.
+const CLIENT_BUFFER: usize = 4096;
+
+/// The events a client asks for. Omitted or empty fields match everything.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct ClientFilter {
+    #[serde(default)]
+    symbols: HashSet<String>,
+    #[serde(default)]
+    event_types: HashSet<EventType>,
+}
+
+impl ClientFilter {
+    fn matches(&self, event: &Event) -> bool {
+        (self.symbols.is_empty() || self.symbols.contains(&event.sym))
+            && (self.event_types.is_empty() || self.event_types.contains(&EventType::from(event)))
+    }
+}
+
+/// A client's filter, which may change while it is connected. None matches nothing.
+pub(crate) type SharedFilter = Arc<Mutex<Option<ClientFilter>>>;
+
+struct Client {
+    filter: SharedFilter,
+    messages: SyncSender<Arc<str>>,
+}
+
+#[derive(Default)]
+struct Registry {
+    clients: Vec<Client>,
+    closed: bool,
+}
+
+/// The clients of a server, each sent the events its filter matches, encoded by `encode`.
+#[derive(Clone)]
+pub(crate) struct Clients {
+    registry: Arc<Mutex<Registry>>,
+    encode: fn(&Event) -> String,
+}
+
+impl Clients {
+    pub(crate) fn new(encode: fn(&Event) -> String) -> Self {
+        Clients {
+            registry: Arc::default(),
+            encode,
+        }
+    }
+
+    /// Add a client with `filter`, returning the messages to send it, or None once the server
+    /// is closed. The messages end when the client is removed, falls behind, or the server
+    /// closes.
+    pub(crate) fn add(&self, filter: SharedFilter) -> Option<Receiver<Arc<str>>> {
+        let mut registry = self.registry.lock().unwrap();
+        if registry.closed {
+            return None;
+        }
+        let (messages, receiver) = sync_channel(CLIENT_BUFFER);
+        registry.clients.push(Client { filter, messages });
+        Some(receiver)
+    }
+
+    /// Remove the client added with `filter`.
+    pub(crate) fn remove(&self, filter: &SharedFilter) {
+        self.registry
+            .lock()
+            .unwrap()
+            .clients
+            .retain(|client| !Arc::ptr_eq(&client.filter, filter));
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.registry.lock().unwrap().clients.len()
+    }
+
+    /// Send `event` to every client whose filter matches it, encoding it at most once.
+    pub(crate) fn publish(&self, event: &Event) {
+        let mut registry = self.registry.lock().unwrap();
+        if registry.clients.is_empty() {
+            return;
+        }
+        let mut message: Option<Arc<str>> = None;
+        registry.clients.retain(|client| {
+            let matches = client
+                .filter
+                .lock()
+                .unwrap()
+                .as_ref()
+                .is_some_and(|filter| filter.matches(event));
+            if !matches {
+                return true;
+            }
+            let message = message.get_or_insert_with(|| (self.encode)(event).into());
+            match client.messages.try_send(message.clone()) {
+                Ok(()) => true,
+                // Too slow, or gone
+                Err(TrySendError::Full(_)) | Err(TrySendError::Disconnected(_)) => false,
+            }
+        });
+    }
+
+    /// A listener for `Subscription::attach` that publishes each event.
+    pub(crate) fn listener(&self) -> impl FnMut(Result<Event, Error>) + Send + 'static {
+        let clients = self.clone();
+        move |event| {
+            if let Ok(event) = event {
+                clients.publish(&event);
+            }
+        }
+    }
+
+    /// End every client's messages, and refuse new clients.
+    fn close(&self) {
+        let mut registry = self.registry.lock().unwrap();
+        registry.closed = true;
+        registry.clients.clear();
+    }
+}
+
+/// Accepts connections on a thread, serving each on a thread of its own. When dropped, stops
+/// accepting and ends every client's messages, so their threads exit.
+pub(crate) struct Acceptor {
+    local_addr: SocketAddr,
+    closed: Arc<AtomicBool>,
+    clients: Clients,
+}
+
+impl Acceptor {
+    /// Listen on `addr`, serving each connection with `serve` on threads named after `name`.
+    pub(crate) fn bind<A: ToSocketAddrs>(
+        addr: A,
+        name: &str,
+        clients: Clients,
+        serve: fn(TcpStream, Clients),
+    ) -> Result<Self, Error> {
+        let listener = TcpListener::bind(addr)?;
+        let local_addr = listener.local_addr()?;
+        let closed = Arc::new(AtomicBool::new(false));
+        let accept_clients = clients.clone();
+        let accept_closed = closed.clone();
+        let client_name = format!("{}-client", name);
+        std::thread::Builder::new()
+            .name(format!("{}-accept", name))
+            .spawn(move || {
+                for stream in listener.incoming() {
+                    if accept_closed.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    if let Ok(stream) = stream {
+                        let clients = accept_clients.clone();
+                        let _ = std::thread::Builder::new()
+                            .name(client_name.clone())
+                            .spawn(move || serve(stream, clients));
+                    }
+                }
+            })?;
+        Ok(Acceptor {
+            local_addr,
+            closed,
+            clients,
+        })
+    }
+
+    pub(crate) fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+impl Drop for Acceptor {
+    fn drop(&mut self) {
+        self.closed.store(true, Ordering::SeqCst);
+        // Wake the accept thread so it sees `closed`
+        let _ = TcpStream::connect(self.local_addr);
+        self.clients.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EventData, ProfileEventData};
+
+    #[test]
+    fn closing_ends_messages() {
+        let clients = Clients::new(|event| event.sym.clone());
+        let filter = SharedFilter::default();
+        let messages = clients.add(filter.clone()).unwrap();
+        let profile = || {
+            let data = EventData::Profile(ProfileEventData::default());
+            Event::new("AAPL".to_string(), data)
+        };
+
+        // Nothing is sent until the client has a filter
+        clients.publish(&profile());
+        *filter.lock().unwrap() = Some(ClientFilter::default());
+        clients.publish(&profile());
+        assert_eq!(messages.try_iter().collect::<Vec<_>>(), [Arc::from("AAPL")]);
+
+        clients.close();
+        assert_eq!(clients.len(), 0);
+        assert!(messages.recv().is_err());
+        assert!(clients.add(SharedFilter::default()).is_none());
+    }
+}
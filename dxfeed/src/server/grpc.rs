@@ -0,0 +1,216 @@
+//! A tonic gRPC gateway, enabled by the `grpc` feature, that fans one shared upstream
+//! subscription out to any number of streaming clients. See proto/feed.proto for the service:
+//! `Feed.Subscribe` takes the symbols and event types to stream and returns a stream of events,
+//! so clients in any language with gRPC support can consume the feed.
+//!
+//! ```ignore
+//! let connection = dxfeed::Connection::new("demo.dxfeed.com:7300")?;
+//! let gateway = FeedGateway::new(&connection, &[EventType::Quote, EventType::Trade], 4096)?;
+//! let shutdown = async {
+//!     let _ = tokio::signal::ctrl_c().await;
+//! };
+//! gateway.serve("0.0.0.0:50051".parse()?, shutdown).await?;
+//! ```
+//!
+//! `serve` also answers gRPC server reflection, so e.g.
+//! `grpcurl -plaintext -d '{"symbols": ["AAPL"]}' localhost:50051 dxfeed.Feed/Subscribe` works
+//! without the protos. To add other services, serve `into_service` with your own
+//! `tonic::transport::Server`.
+
+use crate::{Error, Event, EventType, FeedBackend, FeedSubscription};
+use std::collections::HashSet;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Request, Response, Status};
+
+pub use crate::proto;
+
+use proto::feed_server::{Feed, FeedServer};
+
+/// Streams events from a single upstream subscription to gRPC clients, filtered by each
+/// request's symbols and event types.
+///
+/// Requested symbols are added to the upstream subscription and stay subscribed after the
+/// requesting stream ends. Clients that fall more than `capacity` events behind skip the events
+/// they missed.
+pub struct FeedGateway<S> {
+    subscription: Mutex<S>,
+    event_types: Vec<EventType>,
+    events: broadcast::Sender<Arc<Event>>,
+}
+
+impl<S: FeedSubscription + Send + 'static> FeedGateway<S> {
+    /// Subscribe to `event_types` on `backend`, buffering up to `capacity` events per client,
+    /// at least one.
+    pub fn new<B>(backend: &B, event_types: &[EventType], capacity: usize) -> Result<Self, Error>
+    where
+        B: FeedBackend<Subscription = S>,
+    {
+        let (events, _) = broadcast::channel(capacity.max(1));
+        let sender = events.clone();
+        let subscription = backend.subscribe(event_types, move |event| {
+            if let Ok(event) = event {
+                // Fails only when no client is streaming
+                let _ = sender.send(Arc::new(event));
+            }
+        })?;
+        Ok(FeedGateway {
+            subscription: Mutex::new(subscription),
+            event_types: event_types.to_vec(),
+            events,
+        })
+    }
+
+    pub fn into_service(self) -> FeedServer<Self> {
+        FeedServer::new(self)
+    }
+
+    /// Serve the `Feed` service and server reflection on `address` until `shutdown` completes.
+    pub async fn serve<F>(self, address: SocketAddr, shutdown: F) -> Result<(), Error>
+    where
+        F: Future<Output = ()>,
+    {
+        let reflection = tonic_reflection::server::Builder::configure()
+            .register_encoded_file_descriptor_set(proto::FILE_DESCRIPTOR_SET)
+            .build()
+            .map_err(|e| Error::Grpc(e.to_string()))?;
+        tonic::transport::Server::builder()
+            .add_service(self.into_service())
+            .add_service(reflection)
+            .serve_with_shutdown(address, shutdown)
+            .await
+            .map_err(|e| Error::Grpc(e.to_string()))
+    }
+}
+
+type ProtoEventStream = Pin<Box<dyn Stream<Item = Result<proto::Event, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl<S: FeedSubscription + Send + 'static> Feed for FeedGateway<S> {
+    type SubscribeStream = ProtoEventStream;
+
+    async fn subscribe(
+        &self,
+        request: Request<proto::SubscribeRequest>,
+    ) -> Result<Response<ProtoEventStream>, Status> {
+        let request = request.into_inner();
+        let filter =
+            StreamFilter::new(&request, &self.event_types).map_err(Status::invalid_argument)?;
+        // Subscribe to the broadcast before adding symbols, so initial events aren't missed
+        let events = BroadcastStream::new(self.events.subscribe());
+        let symbols: Vec<&str> = request.symbols.iter().map(String::as_str).collect();
+        self.subscription
+            .lock()
+            .unwrap()
+            .add_symbols(&symbols)
+            .map_err(|e| Status::internal(e.to_string()))?;
+        let stream = events.filter_map(move |event| match event {
+            Ok(event) if filter.matches(&event) => Some(Ok(proto::Event::from(event.as_ref()))),
+            _ => None,
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+struct StreamFilter {
+    symbols: HashSet<String>,
+    event_types: HashSet<EventType>,
+}
+
+impl StreamFilter {
+    /// The filter for `request`, which may ask only for types in `served`.
+    fn new(request: &proto::SubscribeRequest, served: &[EventType]) -> Result<Self, String> {
+        if request.symbols.is_empty() {
+            return Err("No symbols requested".to_string());
+        }
+        let event_types = if request.event_types.is_empty() {
+            served.iter().copied().collect()
+        } else {
+            request
+                .event_types
+                .iter()
+                .map(|name| match EventType::from_str(name) {
+                    Ok(event_type) if served.contains(&event_type) => Ok(event_type),
+                    _ => Err(format!("Event type not served: {}", name)),
+                })
+                .collect::<Result<_, _>>()?
+        };
+        Ok(StreamFilter {
+            symbols: request.symbols.iter().cloned().collect(),
+            event_types,
+        })
+    }
+
+    fn matches(&self, event: &Event) -> bool {
+        self.event_types.contains(&EventType::from(event)) && self.symbols.contains(&event.sym)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConfigurationData, EventData, ProfileEventData};
+
+    #[test]
+    fn filter_by_symbol_and_type() {
+        let served = [EventType::Profile, EventType::Configuration];
+        let request = proto::SubscribeRequest {
+            symbols: vec!["AAPL".to_string()],
+            event_types: vec!["Profile".to_string()],
+        };
+        let filter = StreamFilter::new(&request, &served).unwrap();
+        let profile = EventData::Profile(ProfileEventData::default());
+        let config = EventData::Configuration(ConfigurationData {
+            version: 0,
+            object: String::new(),
+        });
+        assert!(filter.matches(&Event::new("AAPL".to_string(), profile.clone())));
+        assert!(!filter.matches(&Event::new("MSFT".to_string(), profile)));
+        assert!(!filter.matches(&Event::new("AAPL".to_string(), config)));
+
+        let request = proto::SubscribeRequest {
+            symbols: vec!["AAPL".to_string()],
+            event_types: vec!["Quote".to_string()],
+        };
+        assert!(StreamFilter::new(&request, &served).is_err());
+    }
+
+    #[test]
+    fn zero_capacity_gateway() {
+        let feed = crate::MockFeed::new();
+        assert!(FeedGateway::new(&feed, &[EventType::Profile], 0).is_ok());
+    }
+
+    #[test]
+    fn reflection_builds() {
+        assert!(tonic_reflection::server::Builder::configure()
+            .register_encoded_file_descriptor_set(proto::FILE_DESCRIPTOR_SET)
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn profile_to_proto() {
+        let profile = ProfileEventData {
+            description: "Apple Inc.".to_string(),
+            beta: 1.2,
+            ..Default::default()
+        };
+        let event = Event::new("AAPL".to_string(), EventData::Profile(profile));
+        let message = proto::Event::from(&event);
+        assert_eq!(message.symbol, "AAPL");
+        match message.data {
+            Some(proto::event::Data::Profile(profile)) => {
+                assert_eq!(profile.description, "Apple Inc.");
+                assert_eq!(profile.beta, 1.2);
+            }
+            other => panic!("Unexpected {:?}", other),
+        }
+    }
+}
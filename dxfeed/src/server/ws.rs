@@ -0,0 +1,210 @@
+//! A WebSocket server, enabled by the `ws` feature, that fans events out to any number of
+//! clients, e.g. internal dashboards, each with its own filter.
+//!
+//! ```ignore
+//! let server = WebSocketServer::bind("0.0.0.0:7701")?;
+//! let types = [EventType::Quote, EventType::Trade];
+//! let subscription = connection.subscribe(&types, server.listener())?;
+//! subscription.add_symbols(&["AAPL", "MSFT", "SPY"])?;
+//! ```
+//!
+//! A client receives nothing until it sends a text message setting its filter, e.g.
+//! `{"symbols": ["AAPL"], "event_types": ["Quote"]}`, and may send another at any time to
+//! replace it. Omitted or empty fields match everything, so `{}` receives every event. Each
+//! event is then sent as a text message of `Event`'s serde serialization.
+//!
+//! The server only filters what is published to it: the symbols clients ask for must be
+//! subscribed upstream, as above.
+
+use crate::server::clients::{Acceptor, Clients, SharedFilter};
+use crate::{Error, Event};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::Duration;
+use tungstenite::{Message, WebSocket};
+
+/// How often an idle client is checked for filter changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A WebSocket server sending published events to each connected client that wants them.
+/// Stops accepting connections, and closes its clients' connections, when dropped.
+pub struct WebSocketServer {
+    clients: Clients,
+    acceptor: Acceptor,
+}
+
+impl WebSocketServer {
+    /// Listen on `addr`, e.g. "127.0.0.1:7701".
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> Result<Self, Error> {
+        let clients = Clients::new(|event| serde_json::to_string(event).unwrap_or_default());
+        let acceptor = Acceptor::bind(addr, "ws", clients.clone(), serve_client)?;
+        Ok(WebSocketServer { clients, acceptor })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.acceptor.local_addr()
+    }
+
+    /// Number of clients connected, whether or not they have sent a filter.
+    pub fn client_count(&self) -> usize {
+        self.clients.len()
+    }
+
+    /// Send `event` to every client whose filter matches it.
+    pub fn publish(&self, event: &Event) {
+        self.clients.publish(event);
+    }
+
+    /// A listener for `Subscription::attach` that publishes each event.
+    pub fn listener(&self) -> impl FnMut(Result<Event, Error>) + Send + 'static {
+        self.clients.listener()
+    }
+}
+
+fn serve_client(stream: TcpStream, clients: Clients) {
+    let Ok(socket) = tungstenite::accept(stream) else {
+        return;
+    };
+    let filter = SharedFilter::default();
+    let Some(messages) = clients.add(filter.clone()) else {
+        return;
+    };
+    send_messages(socket, &messages, &filter);
+    clients.remove(&filter);
+}
+
+/// Send what is published to the client, between reading its filter changes, until either side
+/// disconnects. The server disconnects by ending `messages`, when the socket is closed.
+fn send_messages(
+    mut socket: WebSocket<TcpStream>,
+    messages: &Receiver<Arc<str>>,
+    filter: &SharedFilter,
+) {
+    loop {
+        match messages.recv_timeout(POLL_INTERVAL) {
+            Ok(message) => {
+                let sent = std::iter::once(message)
+                    .chain(messages.try_iter())
+                    .all(|message| socket.write(Message::Text(message.to_string())).is_ok());
+                if !sent || socket.flush().is_err() {
+                    return;
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => {
+                let _ = socket.close(None);
+                let _ = socket.flush();
+                return;
+            }
+        }
+        if !read_filters(&mut socket, filter) {
+            return;
+        }
+    }
+}
+
+/// Apply the filters the client has sent, without waiting for more. Returns whether the client
+/// is still connected.
+fn read_filters(socket: &mut WebSocket<TcpStream>, filter: &SharedFilter) -> bool {
+    if socket.get_mut().set_nonblocking(true).is_err() {
+        return false;
+    }
+    let connected = loop {
+        match socket.read() {
+            // Malformed filters are ignored, keeping the previous one
+            Ok(Message::Text(text)) => {
+                if let Ok(new_filter) = serde_json::from_str(&text) {
+                    *filter.lock().unwrap() = Some(new_filter);
+                }
+            }
+            Ok(Message::Close(_)) => break false,
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e)) => break e.kind() == std::io::ErrorKind::WouldBlock,
+            Err(_) => break false,
+        }
+    };
+    connected && socket.get_mut().set_nonblocking(false).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConfigurationData, EventData, ProfileEventData};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Instant;
+
+    fn wait_for_clients(server: &WebSocketServer, n: usize) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while server.client_count() < n {
+            assert!(Instant::now() < deadline, "client never registered");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn filtered_per_client() {
+        let server = Arc::new(WebSocketServer::bind("127.0.0.1:0").unwrap());
+        let url = format!("ws://{}", server.local_addr());
+        let (mut client, _) = tungstenite::connect(&url).unwrap();
+        let filter = r#"{"symbols": ["AAPL"], "event_types": ["Profile"]}"#;
+        client.send(Message::Text(filter.to_string())).unwrap();
+        wait_for_clients(&server, 1);
+
+        // Until the filter is read, the client receives nothing, so publish until it arrives
+        let done = Arc::new(AtomicBool::new(false));
+        let publisher = {
+            let (server, done) = (server.clone(), done.clone());
+            std::thread::spawn(move || {
+                let config = EventData::Configuration(ConfigurationData {
+                    version: 0,
+                    object: String::new(),
+                });
+                let profile = EventData::Profile(ProfileEventData::default());
+                while !done.load(Ordering::SeqCst) {
+                    server.publish(&Event::new("AAPL".to_string(), config.clone()));
+                    server.publish(&Event::new("MSFT".to_string(), profile.clone()));
+                    server.publish(&Event::new("AAPL".to_string(), profile.clone()));
+                    std::thread::sleep(Duration::from_millis(10));
+                }
+            })
+        };
+        let message = client.read().unwrap();
+        done.store(true, Ordering::SeqCst);
+        publisher.join().unwrap();
+        let event: Event = serde_json::from_str(message.to_text().unwrap()).unwrap();
+        assert_eq!(event.sym, "AAPL");
+        assert!(matches!(event.data, EventData::Profile(_)));
+
+        // A closed client is dropped
+        client.close(None).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while server.client_count() > 0 {
+            assert!(Instant::now() < deadline, "client never dropped");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn dropping_closes_clients() {
+        let server = WebSocketServer::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", server.local_addr());
+        let (mut client, _) = tungstenite::connect(&url).unwrap();
+        wait_for_clients(&server, 1);
+        if let tungstenite::stream::MaybeTlsStream::Plain(stream) = client.get_mut() {
+            stream
+                .set_read_timeout(Some(Duration::from_secs(5)))
+                .unwrap();
+        }
+
+        drop(server);
+        let closed = loop {
+            match client.read() {
+                Ok(Message::Close(_)) | Err(tungstenite::Error::ConnectionClosed) => break true,
+                Ok(_) => {}
+                Err(_) => break false,
+            }
+        };
+        assert!(closed, "client never closed");
+    }
+}
@@ -0,0 +1,302 @@
+//! A single-producer, multi-consumer ring of events in a memory-mapped file, enabled by the
+//! `shm` feature, for the lowest-latency fan-out to other local processes.
+//!
+//! # Layout
+//! All integers are native-endian; offsets are in bytes.
+//!
+//! | offset | size | field |
+//! |---|---|---|
+//! | 0 | 8 | magic, `b"DXFRING1"` |
+//! | 8 | 4 | `slot_size`, a multiple of 8 |
+//! | 12 | 4 | `slot_count`, a power of two |
+//! | 64 | 8 | `write_seq`, the number of events written so far |
+//! | 128 | `slot_size * slot_count` | slots |
+//!
+//! Event `i` is written to slot `i % slot_count`:
+//!
+//! | offset | size | field |
+//! |---|---|---|
+//! | 0 | 8 | `seq`: `2i + 1` while being written, `2i + 2` once complete |
+//! | 8 | 4 | payload length |
+//! | 16 | `slot_size - 16` | payload: `Event`'s JSON serialization |
+//!
+//! A reader of event `i` checks `seq == 2i + 2` before and after copying the payload; any other
+//! value means the writer has lapped it.
+
+use crate::{Error, Event};
+use memmap2::{Mmap, MmapMut, MmapOptions};
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{fence, AtomicU64, Ordering};
+
+const MAGIC: &[u8; 8] = b"DXFRING1";
+const WRITE_SEQ_OFFSET: usize = 64;
+const SLOTS_OFFSET: usize = 128;
+const SLOT_HEADER: usize = 16;
+
+fn invalid_data(message: &str) -> Error {
+    Error::Io(io::Error::new(io::ErrorKind::InvalidData, message))
+}
+
+/// Geometry of a mapped ring, and atomic access to its sequence numbers.
+struct Ring {
+    base: *mut u8,
+    slot_size: usize,
+    slot_count: u64,
+}
+
+// `base` points into a mapping owned alongside the `Ring`, and is only accessed atomically or by
+// its single writer.
+unsafe impl Send for Ring {}
+
+impl Ring {
+    fn atomic(&self, offset: usize) -> &AtomicU64 {
+        // Offsets are 8-aligned within the page-aligned mapping
+        unsafe { &*(self.base.add(offset) as *const AtomicU64) }
+    }
+
+    fn write_seq(&self) -> &AtomicU64 {
+        self.atomic(WRITE_SEQ_OFFSET)
+    }
+
+    fn slot_offset(&self, index: u64) -> usize {
+        SLOTS_OFFSET + (index % self.slot_count) as usize * self.slot_size
+    }
+}
+
+/// The length of a ring file with `slot_count` slots of `slot_size` bytes, checking both are
+/// valid.
+fn ring_len(slot_count: u32, slot_size: u32) -> Result<usize, Error> {
+    if !slot_count.is_power_of_two() {
+        return Err(invalid_data("slot_count must be a power of two"));
+    }
+    if !slot_size.is_multiple_of(8) || slot_size as usize <= SLOT_HEADER {
+        return Err(invalid_data("slot_size must be a multiple of 8 above 16"));
+    }
+    (slot_count as usize)
+        .checked_mul(slot_size as usize)
+        .and_then(|slots| slots.checked_add(SLOTS_OFFSET))
+        .ok_or_else(|| invalid_data("Event ring too large"))
+}
+
+/// Writes events into the ring. There must be only one writer per file.
+pub struct ShmRingWriter {
+    ring: Ring,
+    next: u64,
+    _map: MmapMut,
+}
+
+impl ShmRingWriter {
+    /// Create (or truncate) the ring at `path` with `slot_count` slots of `slot_size` bytes.
+    /// Events whose JSON exceeds `slot_size - 16` bytes can't be published.
+    pub fn create<P: AsRef<Path>>(path: P, slot_count: u32, slot_size: u32) -> Result<Self, Error> {
+        let len = ring_len(slot_count, slot_size)?;
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len(len as u64)?;
+        let mut map = unsafe { MmapOptions::new().map_mut(&file)? };
+        map[8..12].copy_from_slice(&slot_size.to_ne_bytes());
+        map[12..16].copy_from_slice(&slot_count.to_ne_bytes());
+        // Written last, so readers never see a partially initialized header
+        map[0..8].copy_from_slice(MAGIC);
+        Ok(ShmRingWriter {
+            ring: Ring {
+                base: map.as_mut_ptr(),
+                slot_size: slot_size as usize,
+                slot_count: slot_count as u64,
+            },
+            next: 0,
+            _map: map,
+        })
+    }
+
+    pub fn publish(&mut self, event: &Event) -> Result<(), Error> {
+        let json = serde_json::to_vec(event)
+            .map_err(|e| Error::Io(io::Error::new(io::ErrorKind::InvalidData, e)))?;
+        if json.len() > self.ring.slot_size - SLOT_HEADER {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "event larger than a ring slot",
+            )));
+        }
+        let index = self.next;
+        let offset = self.ring.slot_offset(index);
+        self.ring
+            .atomic(offset)
+            .store(2 * index + 1, Ordering::Relaxed);
+        fence(Ordering::Release);
+        unsafe {
+            let slot = self.ring.base.add(offset);
+            std::ptr::copy_nonoverlapping(
+                (json.len() as u32).to_ne_bytes().as_ptr(),
+                slot.add(8),
+                4,
+            );
+            std::ptr::copy_nonoverlapping(json.as_ptr(), slot.add(SLOT_HEADER), json.len());
+        }
+        self.ring
+            .atomic(offset)
+            .store(2 * index + 2, Ordering::Release);
+        self.ring.write_seq().store(index + 1, Ordering::Release);
+        self.next += 1;
+        Ok(())
+    }
+
+    /// A listener for `Subscription::attach` that publishes each event, dropping any that don't
+    /// fit in a slot.
+    pub fn listener(mut self) -> impl FnMut(Result<Event, Error>) + Send + 'static {
+        move |event| {
+            if let Ok(event) = event {
+                let _ = self.publish(&event);
+            }
+        }
+    }
+}
+
+/// Reads events from a ring written by a `ShmRingWriter`, possibly in another process.
+pub struct ShmRingReader {
+    ring: Ring,
+    next: u64,
+    skipped: u64,
+    buf: Vec<u8>,
+    _map: Mmap,
+}
+
+impl ShmRingReader {
+    /// Open the ring at `path`. Reading starts from the next event written.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let file = File::open(path)?;
+        let map = unsafe { MmapOptions::new().map(&file)? };
+        if map.len() < SLOTS_OFFSET || &map[0..8] != MAGIC {
+            return Err(invalid_data("Not an event ring"));
+        }
+        let slot_size = u32::from_ne_bytes(map[8..12].try_into().unwrap());
+        let slot_count = u32::from_ne_bytes(map[12..16].try_into().unwrap());
+        if map.len() < ring_len(slot_count, slot_size)? {
+            return Err(invalid_data("Truncated event ring"));
+        }
+        // Only ever read, atomically for the sequence numbers
+        let ring = Ring {
+            base: map.as_ptr() as *mut u8,
+            slot_size: slot_size as usize,
+            slot_count: slot_count as u64,
+        };
+        let next = ring.write_seq().load(Ordering::Acquire);
+        Ok(ShmRingReader {
+            ring,
+            next,
+            skipped: 0,
+            buf: Vec::new(),
+            _map: map,
+        })
+    }
+
+    /// Total events overwritten before this reader could read them.
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+
+    /// The next event, or None if the writer hasn't written one yet. Events the writer has
+    /// lapped are skipped (and counted by `skipped`).
+    pub fn try_read(&mut self) -> Result<Option<Event>, Error> {
+        loop {
+            let written = self.ring.write_seq().load(Ordering::Acquire);
+            if self.next >= written {
+                return Ok(None);
+            }
+            let oldest = written.saturating_sub(self.ring.slot_count);
+            if self.next < oldest {
+                self.skipped += oldest - self.next;
+                self.next = oldest;
+            }
+            let index = self.next;
+            let offset = self.ring.slot_offset(index);
+            let complete = 2 * index + 2;
+            if self.ring.atomic(offset).load(Ordering::Acquire) != complete {
+                // Lapped since reading `write_seq`
+                self.skipped += 1;
+                self.next += 1;
+                continue;
+            }
+            unsafe {
+                let slot = self.ring.base.add(offset);
+                let mut len = [0u8; 4];
+                std::ptr::copy_nonoverlapping(slot.add(8), len.as_mut_ptr(), 4);
+                let len = (u32::from_ne_bytes(len) as usize).min(self.ring.slot_size - SLOT_HEADER);
+                self.buf.resize(len, 0);
+                std::ptr::copy_nonoverlapping(slot.add(SLOT_HEADER), self.buf.as_mut_ptr(), len);
+            }
+            fence(Ordering::Acquire);
+            self.next += 1;
+            if self.ring.atomic(offset).load(Ordering::Relaxed) != complete {
+                self.skipped += 1;
+                continue;
+            }
+            return serde_json::from_slice(&self.buf)
+                .map(Some)
+                .map_err(|e| Error::Io(io::Error::new(io::ErrorKind::InvalidData, e)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EventData, ProfileEventData};
+
+    fn profile(sym: &str) -> Event {
+        Event::new(
+            sym.to_string(),
+            EventData::Profile(ProfileEventData::default()),
+        )
+    }
+
+    #[test]
+    fn read_and_lap() {
+        let path = std::env::temp_dir().join(format!("dxfeed-ring-{}", std::process::id()));
+        let mut writer = ShmRingWriter::create(&path, 4, 1024).unwrap();
+        let mut reader = ShmRingReader::open(&path).unwrap();
+        assert!(reader.try_read().unwrap().is_none());
+
+        writer.publish(&profile("AAPL")).unwrap();
+        assert_eq!(reader.try_read().unwrap().unwrap().sym, "AAPL");
+        assert!(reader.try_read().unwrap().is_none());
+
+        // Lap the reader: only the newest 4 of 6 remain
+        for i in 0..6 {
+            writer.publish(&profile(&format!("SYM{}", i))).unwrap();
+        }
+        assert_eq!(reader.try_read().unwrap().unwrap().sym, "SYM2");
+        assert_eq!(reader.skipped(), 2);
+
+        let mut big = profile("BIG");
+        if let EventData::Profile(profile) = &mut big.data {
+            profile.description = "x".repeat(2048);
+        }
+        assert!(writer.publish(&big).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn rejects_bad_headers() {
+        let path = std::env::temp_dir().join(format!("dxfeed-ring-bad-{}", std::process::id()));
+        // No slots, slots too small for their header, and slots far beyond the file
+        for (slot_size, slot_count) in [(1024u32, 0u32), (8, 4), (1 << 31, 1 << 31)] {
+            let mut header = vec![0u8; SLOTS_OFFSET + 4096];
+            header[0..8].copy_from_slice(MAGIC);
+            header[8..12].copy_from_slice(&slot_size.to_ne_bytes());
+            header[12..16].copy_from_slice(&slot_count.to_ne_bytes());
+            std::fs::write(&path, &header).unwrap();
+            assert!(matches!(
+                ShmRingReader::open(&path),
+                Err(Error::Io(err)) if err.kind() == io::ErrorKind::InvalidData
+            ));
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+}
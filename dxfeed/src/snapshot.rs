@@ -0,0 +1,129 @@
+use crate::connection::ConnectionHandle;
+use crate::last_error::call_failed;
+use crate::panics;
+use crate::{
+    dxf_attach_snapshot_listener, dxf_close_snapshot, dxf_connection_t, dxf_const_string_t,
+    dxf_create_snapshot, dxf_event_data_t, dxf_snapshot_data_ptr_t, dxf_snapshot_t, Error, Event,
+    EventData, EventType, DXF_SUCCESS,
+};
+use std::ffi::CString;
+use std::os::raw::{c_int, c_void};
+use std::sync::mpsc::{sync_channel, RecvTimeoutError, SyncSender};
+use std::sync::Arc;
+use std::time::Duration;
+use widestring::WideCString;
+
+/// A safe wrapper around `dxf_snapshot_t`. The snapshot is closed when dropped.
+///
+/// The C API assembles the snapshot itself (SNAPSHOT_BEGIN ... SNAPSHOT_END) and only notifies
+/// listeners once it is complete, so callers see a consistent view of the indexed events
+/// (Order, Candle, TimeAndSale, Greeks, Series, SpreadOrder) for a single symbol.
+#[derive(Debug)]
+pub struct Snapshot {
+    handle: dxf_snapshot_t,
+    // Set when created via `Connection::snapshot` so the connection outlives the snapshot.
+    pub(crate) connection: Option<Arc<ConnectionHandle>>,
+}
+
+impl Snapshot {
+    /// Create a snapshot of `event_type` for `symbol` on an open connection. `source` selects the
+    /// order source (e.g. "NTV") for Order snapshots and is ignored otherwise. `time` is the
+    /// unix time in milliseconds from which to request history (0 for none).
+    ///
+    /// # Safety
+    /// `connection` must be a valid handle from `dxf_create_connection` that outlives the snapshot.
+    pub unsafe fn new(
+        connection: dxf_connection_t,
+        event_type: EventType,
+        symbol: &str,
+        source: Option<&str>,
+        time: i64,
+    ) -> Result<Self, Error> {
+        let c_symbol = WideCString::from_str(symbol).map_err(|_| Error::ContainsNul)?;
+        let c_source = source
+            .map(CString::new)
+            .transpose()
+            .map_err(|_| Error::ContainsNul)?;
+        let mut handle: dxf_snapshot_t = std::ptr::null_mut();
+        let result = dxf_create_snapshot(
+            connection,
+            event_type.event_id(),
+            c_symbol.as_ptr() as dxf_const_string_t,
+            c_source.as_ref().map_or(std::ptr::null(), |s| s.as_ptr()),
+            time,
+            &mut handle,
+        );
+        if result != DXF_SUCCESS as c_int {
+            return Err(call_failed("dxf_create_snapshot"));
+        }
+        Ok(Snapshot {
+            handle,
+            connection: None,
+        })
+    }
+
+    /// Wait up to `timeout` for the first complete snapshot and return its events. The snapshot
+    /// is closed afterwards.
+    pub fn collect(self, timeout: Duration) -> Result<Vec<Event>, Error> {
+        let (sender, receiver) = sync_channel::<Result<Vec<Event>, Error>>(1);
+        let sender = Box::new(sender);
+        let sender_ptr = &*sender as *const SyncSender<_> as *mut c_void;
+        let result = unsafe {
+            dxf_attach_snapshot_listener(self.handle, Some(collect_listener), sender_ptr)
+        };
+        if result != DXF_SUCCESS as c_int {
+            return Err(call_failed("dxf_attach_snapshot_listener"));
+        }
+        let received = receiver.recv_timeout(timeout);
+        // Close the snapshot (and with it the listener) before `sender` is freed.
+        drop(self);
+        match received {
+            Ok(events) => events,
+            Err(RecvTimeoutError::Timeout) => Err(Error::Timeout),
+            Err(RecvTimeoutError::Disconnected) => Err(Error::Unknown),
+        }
+    }
+}
+
+impl Drop for Snapshot {
+    fn drop(&mut self) {
+        if !self.handle.is_null() {
+            unsafe { dxf_close_snapshot(self.handle) };
+        }
+    }
+}
+
+extern "C" fn collect_listener(snapshot_data: dxf_snapshot_data_ptr_t, user_data: *mut c_void) {
+    panics::guard("snapshot listener", (), || {
+        collect(snapshot_data, user_data)
+    })
+}
+
+fn collect(snapshot_data: dxf_snapshot_data_ptr_t, user_data: *mut c_void) {
+    let sender = unsafe { &*(user_data as *const SyncSender<Result<Vec<Event>, Error>>) };
+    let data = unsafe { &*snapshot_data };
+    let events = (|| {
+        let sym = unsafe { crate::utf::decode(data.symbol as *const _) }?;
+        (0..data.records_count)
+            .map(|i| {
+                let record = unsafe { record_at(data.event_type, data.records, i) }?;
+                let event_data = EventData::try_get_event_data(data.event_type, record)?;
+                Ok(Event::new(sym.clone(), event_data))
+            })
+            .collect()
+    })();
+    // Only the first complete snapshot is wanted; later updates are dropped while the channel
+    // is full.
+    let _ = sender.try_send(events);
+}
+
+/// Pointer to the `index`th record in a snapshot's records array, whose stride depends on the
+/// event type.
+unsafe fn record_at(
+    event_type: c_int,
+    records: *const dxf_event_data_t,
+    index: usize,
+) -> Result<*const dxf_event_data_t, Error> {
+    let size = EventType::try_from(event_type)?.record_size();
+    Ok((records as *const u8).add(index * size) as *const dxf_event_data_t)
+}
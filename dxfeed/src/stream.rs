@@ -0,0 +1,52 @@
+//! A runtime-agnostic async `Stream` of a subscription's events, enabled by the `futures`
+//! feature (and by `tokio`, which builds on it). It needs no executor, so it works under tokio,
+//! async-std, smol, or `futures::executor` alike.
+
+use crate::{Event, Subscription};
+use futures_channel::mpsc::{unbounded, UnboundedReceiver};
+use futures_core::Stream;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// The events of a subscription it owns. Dropping the stream closes the subscription.
+pub struct SubscriptionStream {
+    events: UnboundedReceiver<Event>,
+    subscription: Subscription,
+}
+
+impl SubscriptionStream {
+    pub fn subscription(&self) -> &Subscription {
+        &self.subscription
+    }
+
+    /// E.g. to add symbols after the stream is created.
+    pub fn subscription_mut(&mut self) -> &mut Subscription {
+        &mut self.subscription
+    }
+}
+
+impl Stream for SubscriptionStream {
+    type Item = Event;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Event>> {
+        Pin::new(&mut self.events).poll_next(cx)
+    }
+}
+
+impl Subscription {
+    /// Deliver this subscription's events to an async `Stream` instead, replacing any attached
+    /// listener. Events that fail conversion are dropped. The channel is unbounded, since the C
+    /// callback can't wait for a slow consumer.
+    pub fn into_stream(mut self) -> Result<SubscriptionStream, crate::Error> {
+        let (sender, events) = unbounded();
+        self.attach(move |event| {
+            if let Ok(event) = event {
+                let _ = sender.unbounded_send(event);
+            }
+        })?;
+        Ok(SubscriptionStream {
+            events,
+            subscription: self,
+        })
+    }
+}
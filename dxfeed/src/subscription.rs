@@ -0,0 +1,333 @@
+use crate::connection::ConnectionHandle;
+use crate::last_error::call_failed;
+use crate::panics;
+use crate::{
+    dxf_add_symbol, dxf_add_symbols, dxf_attach_event_listener, dxf_clear_symbols,
+    dxf_close_subscription, dxf_const_string_t, dxf_create_subscription,
+    dxf_create_subscription_timed, dxf_detach_event_listener, dxf_event_data_t,
+    dxf_event_listener_t, dxf_remove_symbol, dxf_remove_symbols, dxf_set_symbols,
+    dxf_subscription_t, inventory, raw, Connection, EpochMillis, Error, Event, EventRef,
+    EventTypeMask, RawEvent, DXF_SUCCESS,
+};
+use std::os::raw::{c_int, c_void};
+#[cfg(feature = "tokio")]
+use std::sync::Mutex;
+use std::sync::{Arc, PoisonError};
+use widestring::WideCString;
+
+type SymbolsCall =
+    unsafe extern "C" fn(dxf_subscription_t, *mut dxf_const_string_t, c_int) -> c_int;
+
+/// Symbols as wide C strings, owned alongside the pointer array the C API takes.
+struct WideSymbols {
+    _strings: Vec<WideCString>,
+    pointers: Vec<dxf_const_string_t>,
+}
+
+impl WideSymbols {
+    fn new(symbols: &[&str]) -> Result<Self, Error> {
+        let strings = symbols
+            .iter()
+            .map(|symbol| WideCString::from_str(symbol).map_err(|_| Error::ContainsNul))
+            .collect::<Result<Vec<_>, Error>>()?;
+        // The pointers stay valid as the strings' buffers don't move with the Vec
+        let pointers = strings
+            .iter()
+            .map(|string| string.as_ptr() as dxf_const_string_t)
+            .collect();
+        Ok(WideSymbols {
+            _strings: strings,
+            pointers,
+        })
+    }
+}
+
+type Listener = Box<dyn FnMut(Result<Event, Error>) + Send>;
+type BorrowedListener = Box<dyn for<'a> FnMut(Result<RawEvent<'a>, Error>) + Send>;
+
+/// The attached listener, boxed twice so `user_data` stays a thin pointer.
+// Only held to keep the box alive; the C API calls it through `user_data`.
+#[allow(dead_code)]
+enum Attached {
+    Owned(Box<Listener>),
+    // Keeps the symbol buffer alongside, reused across events
+    Borrowed(Box<(BorrowedListener, String)>),
+}
+
+impl Attached {
+    fn trampoline(&self) -> dxf_event_listener_t {
+        match self {
+            Attached::Owned(_) => Some(listener_trampoline),
+            Attached::Borrowed(_) => Some(borrowed_trampoline),
+        }
+    }
+}
+
+/// A safe wrapper around `dxf_subscription_t`. The subscription is closed when dropped.
+pub struct Subscription {
+    handle: dxf_subscription_t,
+    listener: Option<Attached>,
+    #[cfg(feature = "tokio")]
+    pub(crate) watches: Option<Arc<Mutex<crate::watch::Watches>>>,
+    // Declared last so the connection outlives the subscription (and its listener).
+    pub(crate) connection: Arc<ConnectionHandle>,
+}
+
+// The C API synchronizes access to subscription handles internally, and the listener is `Send`.
+unsafe impl Send for Subscription {}
+
+impl Subscription {
+    /// Create a subscription for `event_types`, e.g. `EventTypeMask::QUOTE | EventTypeMask::TRADE`
+    /// or a mask of `DXF_ET_*` constants.
+    pub fn new(
+        connection: &Connection,
+        event_types: impl Into<EventTypeMask>,
+    ) -> Result<Self, Error> {
+        let event_types = event_types.into();
+        let mut handle: dxf_subscription_t = std::ptr::null_mut();
+        let result = unsafe {
+            dxf_create_subscription(connection.as_raw(), event_types.bits(), &mut handle)
+        };
+        if result != DXF_SUCCESS as c_int {
+            return Err(call_failed("dxf_create_subscription"));
+        }
+        Ok(Self::register(connection, handle, event_types))
+    }
+
+    /// Create a subscription for time-series `event_types` (e.g. Candle, TimeAndSale, Greeks)
+    /// that also receives the events since `from`, e.g. a `SystemTime`.
+    pub fn new_timed<T: EpochMillis>(
+        connection: &Connection,
+        event_types: impl Into<EventTypeMask>,
+        from: T,
+    ) -> Result<Self, Error> {
+        let event_types = event_types.into();
+        let mut handle: dxf_subscription_t = std::ptr::null_mut();
+        let result = unsafe {
+            dxf_create_subscription_timed(
+                connection.as_raw(),
+                event_types.bits(),
+                from.epoch_millis(),
+                &mut handle,
+            )
+        };
+        if result != DXF_SUCCESS as c_int {
+            return Err(call_failed("dxf_create_subscription_timed"));
+        }
+        Ok(Self::register(connection, handle, event_types))
+    }
+
+    /// Wrap a newly created `handle`, recording it on `connection`.
+    fn register(
+        connection: &Connection,
+        handle: dxf_subscription_t,
+        event_types: EventTypeMask,
+    ) -> Self {
+        connection
+            .handle
+            .subscriptions
+            .lock()
+            .unwrap()
+            .push((handle, event_types.bits()));
+        Subscription {
+            handle,
+            listener: None,
+            #[cfg(feature = "tokio")]
+            watches: None,
+            connection: connection.handle.clone(),
+        }
+    }
+
+    /// The raw handle, for calling into `libdxfeed_sys` directly.
+    pub fn as_raw(&self) -> dxf_subscription_t {
+        self.handle
+    }
+
+    pub fn add_symbol(&self, symbol: &str) -> Result<(), Error> {
+        let c_symbol = WideCString::from_str(symbol).map_err(|_| Error::ContainsNul)?;
+        let result =
+            unsafe { dxf_add_symbol(self.handle, c_symbol.as_ptr() as dxf_const_string_t) };
+        if result != DXF_SUCCESS as c_int {
+            return Err(call_failed("dxf_add_symbol"));
+        }
+        Ok(())
+    }
+
+    pub fn remove_symbol(&self, symbol: &str) -> Result<(), Error> {
+        let c_symbol = WideCString::from_str(symbol).map_err(|_| Error::ContainsNul)?;
+        let result =
+            unsafe { dxf_remove_symbol(self.handle, c_symbol.as_ptr() as dxf_const_string_t) };
+        if result != DXF_SUCCESS as c_int {
+            return Err(call_failed("dxf_remove_symbol"));
+        }
+        Ok(())
+    }
+
+    /// Add all of `symbols` in one call.
+    pub fn add_symbols(&self, symbols: &[&str]) -> Result<(), Error> {
+        self.call_with_symbols(symbols, dxf_add_symbols, "dxf_add_symbols")
+    }
+
+    pub fn remove_symbols(&self, symbols: &[&str]) -> Result<(), Error> {
+        self.call_with_symbols(symbols, dxf_remove_symbols, "dxf_remove_symbols")
+    }
+
+    /// Replace the subscribed symbols with `symbols`.
+    pub fn set_symbols(&self, symbols: &[&str]) -> Result<(), Error> {
+        self.call_with_symbols(symbols, dxf_set_symbols, "dxf_set_symbols")
+    }
+
+    pub fn clear_symbols(&self) -> Result<(), Error> {
+        if unsafe { dxf_clear_symbols(self.handle) } != DXF_SUCCESS as c_int {
+            return Err(call_failed("dxf_clear_symbols"));
+        }
+        Ok(())
+    }
+
+    /// The symbols currently subscribed.
+    pub fn symbols(&self) -> Result<Vec<String>, Error> {
+        unsafe { inventory::subscribed_symbols(self.handle) }
+    }
+
+    fn call_with_symbols(
+        &self,
+        symbols: &[&str],
+        call: SymbolsCall,
+        name: &'static str,
+    ) -> Result<(), Error> {
+        let mut symbols = WideSymbols::new(symbols)?;
+        let count = symbols.pointers.len() as c_int;
+        if unsafe { call(self.handle, symbols.pointers.as_mut_ptr(), count) }
+            != DXF_SUCCESS as c_int
+        {
+            return Err(call_failed(name));
+        }
+        Ok(())
+    }
+
+    /// Deliver every event (or conversion error) to `listener`, replacing any previously
+    /// attached listener. It is called on the connection's socket thread.
+    pub fn attach<F>(&mut self, listener: F) -> Result<(), Error>
+    where
+        F: FnMut(Result<Event, Error>) + Send + 'static,
+    {
+        let mut listener: Box<Listener> = Box::new(Box::new(listener));
+        let user_data = &mut *listener as *mut Listener as *mut c_void;
+        self.attach_with(Attached::Owned(listener), user_data)
+    }
+
+    /// Like `attach`, but `listener` borrows each event's C struct instead of receiving a
+    /// converted `Event`, for picking out a few fields without decoding the rest. Only the
+    /// symbol is decoded, into a buffer reused across events.
+    pub fn attach_borrowed<F>(&mut self, listener: F) -> Result<(), Error>
+    where
+        F: for<'a> FnMut(Result<RawEvent<'a>, Error>) + Send + 'static,
+    {
+        let mut listener: Box<(BorrowedListener, String)> =
+            Box::new((Box::new(listener), String::new()));
+        let user_data = &mut *listener as *mut (BorrowedListener, String) as *mut c_void;
+        self.attach_with(Attached::Borrowed(listener), user_data)
+    }
+
+    /// Like `attach_borrowed`, but `listener` gets an `EventRef`, with typed accessors that read
+    /// fields, strings included, straight from the C struct.
+    pub fn attach_ref<F>(&mut self, mut listener: F) -> Result<(), Error>
+    where
+        F: for<'a> FnMut(Result<EventRef<'a>, Error>) + Send + 'static,
+    {
+        // The C API delivered the struct, so its strings are valid for the callback
+        self.attach_borrowed(move |event| listener(event.map(|raw| unsafe { EventRef::new(raw) })))
+    }
+
+    fn attach_with(&mut self, listener: Attached, user_data: *mut c_void) -> Result<(), Error> {
+        self.detach()?;
+        let result =
+            unsafe { dxf_attach_event_listener(self.handle, listener.trampoline(), user_data) };
+        if result != DXF_SUCCESS as c_int {
+            return Err(call_failed("dxf_attach_event_listener"));
+        }
+        self.listener = Some(listener);
+        Ok(())
+    }
+
+    /// Detach the current listener, if any.
+    pub fn detach(&mut self) -> Result<(), Error> {
+        let trampoline = match &self.listener {
+            Some(listener) => listener.trampoline(),
+            None => return Ok(()),
+        };
+        let result = unsafe { dxf_detach_event_listener(self.handle, trampoline) };
+        if result != DXF_SUCCESS as c_int {
+            return Err(call_failed("dxf_detach_event_listener"));
+        }
+        self.listener = None;
+        #[cfg(feature = "tokio")]
+        {
+            self.watches = None;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.connection
+            .subscriptions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .retain(|(handle, _)| *handle != self.handle);
+        // Closed without the lock held, as closing waits for listeners, which may take it
+        if !self.handle.is_null() {
+            unsafe { dxf_close_subscription(self.handle) };
+        }
+    }
+}
+
+extern "C" fn listener_trampoline(
+    event_type: c_int,
+    sym: dxf_const_string_t,
+    data: *const dxf_event_data_t,
+    _data_count: c_int, // always 1, and deprecated
+    user_data: *mut c_void,
+) {
+    panics::guard("event listener", (), || {
+        let listener = unsafe { &mut *(user_data as *mut Listener) };
+        listener(Event::try_from_c(event_type, sym, data));
+    })
+}
+
+extern "C" fn borrowed_trampoline(
+    event_type: c_int,
+    sym: dxf_const_string_t,
+    data: *const dxf_event_data_t,
+    _data_count: c_int,
+    user_data: *mut c_void,
+) {
+    panics::guard("event listener", (), || {
+        let (listener, buf) = unsafe { &mut *(user_data as *mut (BorrowedListener, String)) };
+        listener(unsafe { raw::decode(buf, event_type, sym, data) });
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use widestring::WideCStr;
+
+    #[test]
+    fn wide_symbols_point_at_strings() {
+        let symbols = WideSymbols::new(&["AAPL", "SPY"]).unwrap();
+        let decoded: Vec<String> = symbols
+            .pointers
+            .iter()
+            .map(|pointer| {
+                unsafe { WideCStr::from_ptr_str(*pointer as *const _) }.to_string_lossy()
+            })
+            .collect();
+        assert_eq!(decoded, ["AAPL", "SPY"]);
+        assert!(matches!(
+            WideSymbols::new(&["AA\0PL"]),
+            Err(Error::ContainsNul)
+        ));
+    }
+}
@@ -0,0 +1,112 @@
+//! Tagged serde representations of `EventData`, for JSON consumers that want the event type as
+//! a field rather than the default externally tagged `{"Quote":{...}}`:
+//! - internally tagged, `{"type":"Quote","time":...}`
+//! - adjacently tagged, `{"type":"Quote","data":{...}}`
+//!
+//! Wrap an `EventData` in `InternallyTagged` or `AdjacentlyTagged`, or use the `internal` and
+//! `adjacent` modules as `#[serde(with = "dxfeed::tagged::adjacent")]` on an `EventData` field.
+
+use crate::{
+    CandleData, ConfigurationData, EventData, GreeksData, OrderEventData, ProfileEventData,
+    QuoteData, SeriesData, SpreadOrderData, SummaryData, TheoPriceData, TimeAndSaleData, TradeData,
+    TradeEthData, UnderlyingData,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+// Defines a remote serde definition of `EventData` with the given container attributes, and a
+// `with` module serializing through it
+macro_rules! tagged_def {
+    ($module:ident, $def:ident, $($attr:tt)*) => {
+        #[derive(Serialize, Deserialize)]
+        #[serde(remote = "EventData", $($attr)*)]
+        enum $def {
+            Trade(TradeData),
+            Quote(QuoteData),
+            Summary(SummaryData),
+            Profile(ProfileEventData),
+            Order(OrderEventData),
+            TimeAndSale(TimeAndSaleData),
+            Candle(CandleData),
+            TradeETH(TradeEthData),
+            SpreadOrder(SpreadOrderData),
+            Greeks(GreeksData),
+            TheoPrice(TheoPriceData),
+            Underlying(UnderlyingData),
+            Series(SeriesData),
+            Configuration(ConfigurationData),
+        }
+
+        pub mod $module {
+            use super::*;
+
+            pub fn serialize<S: Serializer>(
+                data: &EventData,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error> {
+                $def::serialize(data, serializer)
+            }
+
+            pub fn deserialize<'de, D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<EventData, D::Error> {
+                $def::deserialize(deserializer)
+            }
+        }
+    };
+}
+
+tagged_def!(internal, InternalDef, tag = "type");
+tagged_def!(adjacent, AdjacentDef, tag = "type", content = "data");
+
+/// An `EventData` serialized internally tagged, e.g. `{"type":"Quote","time":...}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct InternallyTagged(#[serde(with = "internal")] pub EventData);
+
+/// An `EventData` serialized adjacently tagged, e.g. `{"type":"Quote","data":{...}}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AdjacentlyTagged(#[serde(with = "adjacent")] pub EventData);
+
+impl From<EventData> for InternallyTagged {
+    fn from(data: EventData) -> Self {
+        InternallyTagged(data)
+    }
+}
+
+impl From<EventData> for AdjacentlyTagged {
+    fn from(data: EventData) -> Self {
+        AdjacentlyTagged(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn tagged_round_trips() {
+        let quote = QuoteData {
+            sequence: 3,
+            ..Default::default()
+        };
+        let data = EventData::Quote(quote);
+
+        let internal = serde_json::to_value(InternallyTagged(data.clone())).unwrap();
+        assert_eq!(internal["type"], "Quote");
+        assert_eq!(internal["sequence"], 3);
+        let back: InternallyTagged = serde_json::from_value(internal).unwrap();
+        assert_eq!(back.0.as_quote(), Some(&quote));
+
+        let adjacent = serde_json::to_value(AdjacentlyTagged(data)).unwrap();
+        assert_eq!(adjacent["type"], "Quote");
+        assert_eq!(adjacent["data"]["sequence"], 3);
+        let back: AdjacentlyTagged = serde_json::from_value(adjacent).unwrap();
+        assert_eq!(back.0.as_quote(), Some(&quote));
+
+        let config = json!({"type": "Configuration", "version": 7, "object": "{}"});
+        let back: InternallyTagged = serde_json::from_value(config).unwrap();
+        assert_eq!(back.0.as_configuration().map(|c| c.version), Some(7));
+    }
+}
@@ -0,0 +1,522 @@
+//! Realistic event fixtures for tests, enabled by the `testing` feature. Each constructor returns
+//! a builder starting from a plausible event for AAPL during the regular session of 2024-01-19,
+//! with setters to override fields:
+//!
+//! ```ignore
+//! let quote = testing::quote().bid(101.0, 500.0).sequence(7).build();
+//! let event = testing::trade().price(190.5).size(200.0).event("AAPL");
+//! ```
+//!
+//! `build` keeps the packed `raw_flags` of Trade, TradeETH, TimeAndSale and Profile, and the
+//! `flags` of SpreadOrder, consistent with the fields they are unpacked into, so set those
+//! fields rather than the packed ones.
+
+use crate::{
+    dxf_char_t, dxf_tns_type_t, Action, CandleData, ConfigurationData, Direction, Event, EventData,
+    EventFlags, GreeksData, OrderEventData, OrderFlags, PriceType, ProfileEventData, QuoteData,
+    QuoteSide, Scope, SeriesData, ShortSaleRestriction, Side, SpreadOrderData, SummaryData,
+    TheoPriceData, TimeAndSaleData, TnsFlags, TnsType, TradeData, TradeEthData, TradeFlags,
+    TradingStatus, UnderlyingData,
+};
+use std::convert::TryFrom;
+
+/// 2024-01-19 14:30:00 UTC, the fixtures' event time, in milliseconds since the Unix epoch.
+pub const TIME: i64 = 1_705_674_600_000;
+
+/// 2024-01-19, the fixtures' trading day, in days since the Unix epoch.
+pub const DAY_ID: i32 = 19_741;
+
+/// Brings a payload's packed fields in line with their unpacked ones.
+trait Finish: Sized {
+    fn finish(self) -> Self {
+        self
+    }
+}
+
+impl Finish for QuoteData {}
+impl Finish for SummaryData {}
+impl Finish for OrderEventData {}
+impl Finish for CandleData {}
+impl Finish for GreeksData {}
+impl Finish for TheoPriceData {}
+impl Finish for UnderlyingData {}
+impl Finish for SeriesData {}
+impl Finish for ConfigurationData {}
+
+impl Finish for TradeData {
+    fn finish(mut self) -> Self {
+        self.raw_flags = TradeFlags {
+            direction: self.direction,
+            is_eth: self.is_eth,
+        }
+        .to_raw();
+        self
+    }
+}
+
+impl Finish for TradeEthData {
+    fn finish(mut self) -> Self {
+        self.raw_flags = TradeFlags {
+            direction: self.direction,
+            is_eth: self.is_eth,
+        }
+        .to_raw();
+        self
+    }
+}
+
+impl Finish for SpreadOrderData {
+    fn finish(mut self) -> Self {
+        self.flags = OrderFlags {
+            scope: self.scope,
+            side: self.side,
+            exchange_code: self.exchange_code,
+            action: self.action,
+        }
+        .to_raw();
+        self
+    }
+}
+
+impl Finish for TimeAndSaleData {
+    fn finish(mut self) -> Self {
+        self.raw_flags = TnsFlags {
+            kind: TnsType::try_from(self.kind).unwrap_or_default(),
+            is_valid_tick: self.is_valid_tick,
+            is_eth_trade: self.is_eth_trade,
+            is_spread_leg: self.is_spread_leg,
+            side: self.side,
+            trade_through_exempt: char::from_u32(self.trade_through_exempt as u32).unwrap_or('\0'),
+        }
+        .to_raw();
+        self
+    }
+}
+
+impl Finish for ProfileEventData {
+    // Profile raw_flags: bits 0-1 the trading status, bits 2-3 the short sale restriction
+    fn finish(mut self) -> Self {
+        self.raw_flags = (self.trading_status & 0x3 | (self.ssr & 0x3) << 2) as i32;
+        self
+    }
+}
+
+// A fixture constructor per payload, returning a builder with a setter per listed field
+macro_rules! fixtures {
+    ($(
+        $(#[$doc:meta])*
+        $name:ident => $builder:ident($data:ident, $variant:ident) = $default:expr;
+        { $($field:ident: $ty:ty),* $(,)? }
+    )*) => {
+        $(
+            $(#[$doc])*
+            pub fn $name() -> $builder {
+                $builder($default)
+            }
+
+            #[doc = concat!(
+                "Builds a `", stringify!($data), "` fixture; see [`", stringify!($name), "`]."
+            )]
+            #[derive(Debug, Clone)]
+            pub struct $builder($data);
+
+            impl $builder {
+                $(
+                    pub fn $field(mut self, $field: $ty) -> Self {
+                        self.0.$field = $field;
+                        self
+                    }
+                )*
+
+                /// Override fields without a setter.
+                pub fn with<F: FnOnce(&mut $data)>(mut self, update: F) -> Self {
+                    update(&mut self.0);
+                    self
+                }
+
+                pub fn build(self) -> $data {
+                    self.0.finish()
+                }
+
+                /// The fixture as an event for `symbol`.
+                pub fn event(self, symbol: &str) -> Event {
+                    Event::new(symbol.to_string(), EventData::$variant(self.build()))
+                }
+            }
+
+            impl From<$builder> for $data {
+                fn from(builder: $builder) -> Self {
+                    builder.build()
+                }
+            }
+        )*
+    };
+}
+
+fixtures! {
+    /// A 100 share uptick trade at 190.25 on Nasdaq.
+    trade => TradeBuilder(TradeData, Trade) = TradeData {
+        time: TIME,
+        sequence: 1,
+        exchange_code: 'Q',
+        price: 190.25,
+        size: 100.0,
+        tick: 1,
+        change: 1.25,
+        day_id: DAY_ID,
+        day_volume: 52_164_530.0,
+        day_turnover: 9_912_000_000.0,
+        direction: Direction::Up,
+        scope: Scope::Composite,
+        ..Default::default()
+    };
+    {
+        time: i64, sequence: i32, time_nanos: i32, exchange_code: char, price: f64, size: f64,
+        tick: i32, change: f64, day_id: i32, day_volume: f64, day_turnover: f64,
+        direction: Direction, is_eth: bool, scope: Scope,
+    }
+
+    /// A 50 share pre-market trade at 189.9 on Nasdaq.
+    trade_eth => TradeEthBuilder(TradeEthData, TradeETH) = TradeEthData {
+        time: TIME - 2 * 3_600_000,
+        sequence: 1,
+        exchange_code: 'Q',
+        price: 189.9,
+        size: 50.0,
+        change: 0.9,
+        day_id: DAY_ID,
+        day_volume: 1_250_000.0,
+        day_turnover: 237_000_000.0,
+        direction: Direction::ZeroUp,
+        is_eth: true,
+        scope: Scope::Composite,
+        ..Default::default()
+    };
+    {
+        time: i64, sequence: i32, time_nanos: i32, exchange_code: char, price: f64, size: f64,
+        change: f64, day_id: i32, day_volume: f64, day_turnover: f64, direction: Direction,
+        is_eth: bool, scope: Scope,
+    }
+
+    /// A composite 190.24 x 190.26 quote, 300 x 200 shares, from Nasdaq.
+    quote => QuoteBuilder(QuoteData, Quote) = QuoteData {
+        time: TIME,
+        sequence: 1,
+        time_nanos: 0,
+        bid: QuoteSide {
+            price: 190.24,
+            size: 300.0,
+            exchange: 'Q',
+            time: TIME,
+        },
+        ask: QuoteSide {
+            price: 190.26,
+            size: 200.0,
+            exchange: 'Q',
+            time: TIME,
+        },
+        scope: Scope::Composite,
+    };
+    { time: i64, sequence: i32, time_nanos: i32, scope: Scope }
+
+    /// The day's summary so far, after a 189.0 previous close.
+    summary => SummaryBuilder(SummaryData, Summary) = SummaryData {
+        day_id: DAY_ID,
+        day_open_price: 189.33,
+        day_high_price: 191.95,
+        day_low_price: 188.82,
+        day_close_price: f64::NAN,
+        prev_day_id: DAY_ID - 1,
+        prev_day_close_price: 189.0,
+        prev_day_volume: 55_692_000.0,
+        open_interest: f64::NAN,
+        exchange_code: '\0',
+        day_close_price_type: PriceType::Regular,
+        prev_day_close_price_type: PriceType::Final,
+        scope: Scope::Composite,
+        ..Default::default()
+    };
+    {
+        day_id: i32, day_open_price: f64, day_high_price: f64, day_low_price: f64,
+        day_close_price: f64, prev_day_id: i32, prev_day_close_price: f64,
+        prev_day_volume: f64, open_interest: f64, exchange_code: char,
+        day_close_price_type: PriceType, prev_day_close_price_type: PriceType, scope: Scope,
+    }
+
+    /// Apple Inc., actively trading without a short sale restriction.
+    profile => ProfileBuilder(ProfileEventData, Profile) = ProfileEventData {
+        beta: 1.29,
+        eps: 6.13,
+        div_freq: 4.0,
+        exd_div_amount: 0.24,
+        exd_div_date: DAY_ID - 70,
+        high_52_week_price: 199.62,
+        low_52_week_price: 133.89,
+        shares: 15_552_752_000.0,
+        free_float: 15_535_000_000.0,
+        high_limit_price: f64::NAN,
+        low_limit_price: f64::NAN,
+        description: "Apple Inc. - Common Stock".to_string(),
+        trading_status: TradingStatus::Active.into(),
+        ssr: ShortSaleRestriction::Inactive.into(),
+        ..Default::default()
+    };
+    {
+        beta: f64, eps: f64, div_freq: f64, exd_div_amount: f64, exd_div_date: i32,
+        high_52_week_price: f64, low_52_week_price: f64, shares: f64, free_float: f64,
+        high_limit_price: f64, low_limit_price: f64, halt_start_time: i64, halt_end_time: i64,
+        description: String, status_reason: String, trading_status: u32, ssr: u32,
+    }
+
+    /// A new 100 share bid at 190.24 on Nasdaq's order book.
+    order => OrderBuilder(OrderEventData, Order) = OrderEventData {
+        source: "NTV".to_string(),
+        index: 1,
+        time: TIME,
+        sequence: 1,
+        action: Action::New,
+        action_time: TIME,
+        order_id: 1,
+        price: 190.24,
+        size: 100.0,
+        executed_size: 0.0,
+        count: 1.0,
+        trade_price: f64::NAN,
+        trade_size: f64::NAN,
+        exchange_code: 'Q',
+        side: Side::Buy,
+        scope: Scope::Order,
+        ..Default::default()
+    };
+    {
+        source: String, event_flags: u32, index: i64, time: i64, sequence: i32,
+        time_nanos: i32, action: Action, action_time: i64, order_id: i64, aux_order_id: i64,
+        price: f64, size: f64, executed_size: f64, count: f64, trade_id: i64,
+        trade_price: f64, trade_size: f64, exchange_code: char, side: Side, scope: Scope,
+        mm_or_spread: String,
+    }
+
+    /// A regular 100 share sale at 190.25 on Nasdaq, bought at the ask.
+    time_and_sale => TimeAndSaleBuilder(TimeAndSaleData, TimeAndSale) = TimeAndSaleData {
+        index: 1,
+        time: TIME,
+        exchange_code: 'Q',
+        price: 190.25,
+        size: 100.0,
+        bid_price: 190.24,
+        ask_price: 190.26,
+        exchange_sale_conditions: "@".to_string(),
+        side: Side::Buy,
+        kind: TnsType::New.into(),
+        is_valid_tick: true,
+        scope: Scope::Composite,
+        ..Default::default()
+    };
+    {
+        event_flags: u32, index: i64, time: i64, exchange_code: char, price: f64, size: f64,
+        bid_price: f64, ask_price: f64, exchange_sale_conditions: String, buyer: String,
+        seller: String, side: Side, kind: dxf_tns_type_t, is_valid_tick: bool,
+        is_eth_trade: bool, trade_through_exempt: dxf_char_t, is_spread_leg: bool,
+        scope: Scope,
+    }
+
+    /// A one minute candle from 14:30.
+    candle => CandleBuilder(CandleData, Candle) = CandleData {
+        index: TIME << 22,
+        time: TIME,
+        sequence: 0,
+        count: 1_823.0,
+        open: 190.1,
+        high: 190.4,
+        low: 190.02,
+        close: 190.25,
+        volume: 412_300.0,
+        vwap: Some(190.22),
+        bid_volume: Some(198_000.0),
+        ask_volume: Some(214_300.0),
+        open_interest: None,
+        imp_volatility: Some(0.21),
+        ..Default::default()
+    };
+    {
+        event_flags: EventFlags, index: i64, time: i64, sequence: i32, count: f64, open: f64,
+        high: f64, low: f64, close: f64, volume: f64, vwap: Option<f64>,
+        bid_volume: Option<f64>, ask_volume: Option<f64>, open_interest: Option<f64>,
+        imp_volatility: Option<f64>,
+    }
+
+    /// A new bid on an options calendar spread.
+    spread_order => SpreadOrderBuilder(SpreadOrderData, SpreadOrder) = SpreadOrderData {
+        index: 1,
+        time: (TIME / 1000) as i32,
+        sequence: 1,
+        action_time: TIME,
+        order_id: 1,
+        price: 1.45,
+        size: 10.0,
+        count: 1.0,
+        trade_price: f64::NAN,
+        trade_size: f64::NAN,
+        spread_symbol: "=.AAPL240216C190-.AAPL240119C190".to_string(),
+        action: Action::New,
+        exchange_code: 'C',
+        side: Side::Buy,
+        scope: Scope::Order,
+        ..Default::default()
+    };
+    {
+        index: i32, time: i32, time_nanos: i32, sequence: i32, action_time: i64,
+        order_id: i64, aux_order_id: i64, price: f64, size: f64, executed_size: f64,
+        count: f64, trade_id: i64, trade_price: f64, trade_size: f64, spread_symbol: String,
+        action: Action, exchange_code: char, side: Side, scope: Scope,
+    }
+
+    /// The greeks of an at-the-money call a month from expiration.
+    greeks => GreeksBuilder(GreeksData, Greeks) = GreeksData {
+        index: TIME << 22,
+        time: TIME,
+        price: 5.35,
+        volatility: 0.2,
+        delta: 0.52,
+        gamma: 0.041,
+        theta: -0.085,
+        rho: 0.078,
+        vega: 0.215,
+        ..Default::default()
+    };
+    {
+        event_flags: EventFlags, index: i64, time: i64, price: f64, volatility: f64,
+        delta: f64, gamma: f64, theta: f64, rho: f64, vega: f64,
+    }
+
+    /// The theoretical price of an at-the-money call a month from expiration.
+    theo_price => TheoPriceBuilder(TheoPriceData, TheoPrice) = TheoPriceData {
+        time: TIME,
+        price: 5.35,
+        underlying_price: 190.25,
+        delta: 0.52,
+        gamma: 0.041,
+        dividend: Some(0.0),
+        interest: Some(0.053),
+    };
+    {
+        time: i64, price: f64, underlying_price: f64, delta: f64, gamma: f64,
+        dividend: Option<f64>, interest: Option<f64>,
+    }
+
+    /// The underlying's implied volatility and option volumes.
+    underlying => UnderlyingBuilder(UnderlyingData, Underlying) = UnderlyingData {
+        volatility: 0.21,
+        front_volatility: Some(0.2),
+        back_volatility: Some(0.23),
+        call_volume: 612_000.0,
+        put_volume: 458_000.0,
+        option_volume: 1_070_000.0,
+        put_call_ratio: 0.75,
+    };
+    {
+        volatility: f64, front_volatility: Option<f64>, back_volatility: Option<f64>,
+        call_volume: f64, put_volume: f64, option_volume: f64, put_call_ratio: f64,
+    }
+
+    /// The option series expiring 2024-02-16.
+    series => SeriesBuilder(SeriesData, Series) = SeriesData {
+        index: 1,
+        time: TIME,
+        expiration: DAY_ID + 28,
+        volatility: 0.2,
+        call_volume: 98_000.0,
+        put_volume: 71_000.0,
+        option_volume: 169_000.0,
+        put_call_ratio: 0.72,
+        forward_price: 190.9,
+        dividend: 0.0,
+        interest: 0.053,
+        ..Default::default()
+    };
+    {
+        event_flags: EventFlags, index: i64, time: i64, sequence: i32, expiration: i32,
+        volatility: f64, call_volume: f64, put_volume: f64, option_volume: f64,
+        put_call_ratio: f64, forward_price: f64, dividend: f64, interest: f64,
+    }
+
+    /// An empty configuration object.
+    configuration => ConfigurationBuilder(ConfigurationData, Configuration) = ConfigurationData {
+        version: 1,
+        object: "{}".to_string(),
+    };
+    { version: i32, object: String }
+}
+
+impl QuoteBuilder {
+    /// Set the bid's price and size.
+    pub fn bid(mut self, price: f64, size: f64) -> Self {
+        self.0.bid.price = price;
+        self.0.bid.size = size;
+        self
+    }
+
+    /// Set the ask's price and size.
+    pub fn ask(mut self, price: f64, size: f64) -> Self {
+        self.0.ask.price = price;
+        self.0.ask.size = size;
+        self
+    }
+
+    /// Set both sides' exchange.
+    pub fn exchange(mut self, exchange: char) -> Self {
+        self.0.bid.exchange = exchange;
+        self.0.ask.exchange = exchange;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EventType;
+
+    #[test]
+    fn fixtures_are_consistent() {
+        let traded = trade().direction(Direction::Down).is_eth(true).build();
+        assert_eq!(traded.flags().direction, Direction::Down);
+        assert!(traded.flags().is_eth);
+
+        let tns = time_and_sale().side(Side::Sell).build();
+        assert_eq!(tns.flags().side, Side::Sell);
+        assert_eq!(tns.flags().kind, TnsType::New);
+        assert!(tns.flags().is_valid_tick);
+
+        let active = profile().build();
+        assert_eq!(active.status(), TradingStatus::Active);
+        assert_eq!(
+            active.short_sale_restriction(),
+            ShortSaleRestriction::Inactive
+        );
+
+        let quoted = quote().bid(101.0, 500.0).exchange('Z').sequence(7).build();
+        assert_eq!((quoted.bid.price, quoted.bid.size), (101.0, 500.0));
+        assert_eq!((quoted.ask.exchange, quoted.sequence), ('Z', 7));
+
+        let events = [
+            trade().event("AAPL"),
+            quote().event("AAPL"),
+            summary().event("AAPL"),
+            profile().event("AAPL"),
+            order().event("AAPL"),
+            time_and_sale().event("AAPL"),
+            candle().event("AAPL{=1m}"),
+            trade_eth().event("AAPL"),
+            spread_order().event("AAPL"),
+            greeks().event(".AAPL240216C190"),
+            theo_price().event(".AAPL240216C190"),
+            underlying().event("AAPL"),
+            series().event("AAPL"),
+            configuration()
+                .with(|config| config.version = 2)
+                .event("AAPL"),
+        ];
+        let types: Vec<EventType> = events.iter().map(EventType::from).collect();
+        assert_eq!(types, EventType::all());
+    }
+}
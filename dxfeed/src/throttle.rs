@@ -0,0 +1,240 @@
+//! Rate limiting of symbol changes, for programs that churn their symbol lists faster than a
+//! connection should be asked to resubscribe.
+//!
+//! ```ignore
+//! let (subscription, events) = connection.subscribe_stream(&[EventType::Quote])?;
+//! let subscription = ThrottledSubscription::new(subscription, 20)?;
+//! for symbols in scanner.results() {
+//!     subscription.add_symbols(&symbols)?; // queued, applied at most 20 calls a second
+//! }
+//! eprintln!("{} changes queued", subscription.stats().queued_ops);
+//! ```
+
+use crate::{Error, FeedSubscription};
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OpKind {
+    Add,
+    Remove,
+}
+
+struct Op {
+    kind: OpKind,
+    symbols: Vec<String>,
+}
+
+/// Counts describing a `ThrottledSubscription`'s queue.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ThrottleStats {
+    /// Symbol changes waiting to be applied
+    pub queued_ops: usize,
+    /// Symbols in the waiting changes
+    pub queued_symbols: usize,
+    /// Calls made to the subscription, each applying one or more adjacent changes of a kind
+    pub applied_calls: u64,
+    /// Calls that failed
+    pub errors: u64,
+    /// The error of the last failed call
+    pub last_error: Option<String>,
+}
+
+struct State {
+    ops: VecDeque<Op>,
+    stats: ThrottleStats,
+    /// Whether the worker is applying changes it has taken from `ops`
+    applying: bool,
+    closed: bool,
+}
+
+struct Shared<S> {
+    subscription: Mutex<S>,
+    state: Mutex<State>,
+    /// Signalled when changes are queued, when the queue drains, and on close
+    changed: Condvar,
+    interval: Duration,
+}
+
+/// Wraps a subscription so symbol changes are queued and applied on a worker thread, at most
+/// `calls_per_sec` calls a second. Adjacent changes of the same kind are applied in one call.
+/// `add_symbols` and `remove_symbols` return once the change is queued; failures are counted
+/// in `stats`. Changes still queued when it is dropped are discarded.
+pub struct ThrottledSubscription<S: FeedSubscription + Send + 'static> {
+    shared: Arc<Shared<S>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl<S: FeedSubscription + Send + 'static> ThrottledSubscription<S> {
+    pub fn new(subscription: S, calls_per_sec: u32) -> Result<Self, Error> {
+        let shared = Arc::new(Shared {
+            subscription: Mutex::new(subscription),
+            state: Mutex::new(State {
+                ops: VecDeque::new(),
+                stats: ThrottleStats::default(),
+                applying: false,
+                closed: false,
+            }),
+            changed: Condvar::new(),
+            interval: Duration::from_secs(1) / calls_per_sec.max(1),
+        });
+        let worker_shared = shared.clone();
+        let worker = std::thread::Builder::new()
+            .name("dxfeed-throttle".to_string())
+            .spawn(move || apply_ops(&worker_shared))?;
+        Ok(ThrottledSubscription {
+            shared,
+            worker: Some(worker),
+        })
+    }
+
+    /// The wrapped subscription, e.g. to attach a listener, locked against the worker.
+    /// Changing its symbols directly bypasses the throttle.
+    pub fn subscription(&self) -> MutexGuard<'_, S> {
+        self.shared.subscription.lock().unwrap()
+    }
+
+    pub fn stats(&self) -> ThrottleStats {
+        self.shared.state.lock().unwrap().stats.clone()
+    }
+
+    /// Block until every queued change has been applied, or `timeout` passes. Returns whether
+    /// the queue drained.
+    pub fn wait_idle(&self, timeout: Duration) -> bool {
+        let state = self.shared.state.lock().unwrap();
+        let (state, _) = self
+            .shared
+            .changed
+            .wait_timeout_while(state, timeout, |state| {
+                !state.ops.is_empty() || state.applying
+            })
+            .unwrap();
+        state.ops.is_empty() && !state.applying
+    }
+
+    fn enqueue(&self, kind: OpKind, symbols: &[&str]) {
+        if symbols.is_empty() {
+            return;
+        }
+        let mut state = self.shared.state.lock().unwrap();
+        state.ops.push_back(Op {
+            kind,
+            symbols: symbols.iter().map(|symbol| symbol.to_string()).collect(),
+        });
+        state.stats.queued_ops += 1;
+        state.stats.queued_symbols += symbols.len();
+        self.shared.changed.notify_all();
+    }
+}
+
+impl<S: FeedSubscription + Send + 'static> FeedSubscription for ThrottledSubscription<S> {
+    fn add_symbols(&self, symbols: &[&str]) -> Result<(), Error> {
+        self.enqueue(OpKind::Add, symbols);
+        Ok(())
+    }
+
+    fn remove_symbols(&self, symbols: &[&str]) -> Result<(), Error> {
+        self.enqueue(OpKind::Remove, symbols);
+        Ok(())
+    }
+}
+
+impl<S: FeedSubscription + Send + 'static> Drop for ThrottledSubscription<S> {
+    fn drop(&mut self) {
+        self.shared.state.lock().unwrap().closed = true;
+        self.shared.changed.notify_all();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn apply_ops<S: FeedSubscription>(shared: &Shared<S>) {
+    let mut last_call: Option<Instant> = None;
+    loop {
+        let mut state = shared.state.lock().unwrap();
+        state.applying = false;
+        shared.changed.notify_all();
+        state = shared
+            .changed
+            .wait_while(state, |state| state.ops.is_empty() && !state.closed)
+            .unwrap();
+        if state.closed {
+            return;
+        }
+        // Wait out the interval since the last call, taking changes queued meanwhile with it
+        let due = last_call.map(|at| at + shared.interval);
+        if let Some(wait) = due.and_then(|due| due.checked_duration_since(Instant::now())) {
+            state = shared
+                .changed
+                .wait_timeout_while(state, wait, |state| !state.closed)
+                .unwrap()
+                .0;
+            if state.closed {
+                return;
+            }
+        }
+        let first = state.ops.pop_front().expect("queue is non-empty");
+        let mut symbols = first.symbols;
+        let mut taken = 1;
+        while state.ops.front().is_some_and(|op| op.kind == first.kind) {
+            symbols.extend(state.ops.pop_front().expect("front exists").symbols);
+            taken += 1;
+        }
+        state.stats.queued_ops -= taken;
+        state.stats.queued_symbols -= symbols.len();
+        state.applying = true;
+        drop(state);
+
+        let symbols: Vec<&str> = symbols.iter().map(String::as_str).collect();
+        let subscription = shared.subscription.lock().unwrap();
+        let result = match first.kind {
+            OpKind::Add => subscription.add_symbols(&symbols),
+            OpKind::Remove => subscription.remove_symbols(&symbols),
+        };
+        drop(subscription);
+        last_call = Some(Instant::now());
+
+        let mut state = shared.state.lock().unwrap();
+        state.stats.applied_calls += 1;
+        if let Err(e) = result {
+            state.stats.errors += 1;
+            state.stats.last_error = Some(e.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EventType, FeedBackend, MockFeed};
+    use std::collections::HashSet;
+
+    #[test]
+    fn applies_queued_changes_at_rate() {
+        let feed = MockFeed::new();
+        let (subscription, _events) = feed.subscribe_stream(&[EventType::Quote]).unwrap();
+        let throttled = ThrottledSubscription::new(subscription, 20).unwrap();
+        let start = Instant::now();
+        throttled.add_symbols(&["AAPL"]).unwrap();
+        throttled.add_symbols(&["MSFT", "SPY"]).unwrap();
+        throttled.add_symbols(&["QQQ"]).unwrap();
+        // Can't be merged with the adds, so waits for a later call
+        throttled.remove_symbols(&["SPY"]).unwrap();
+        assert!(throttled.stats().queued_ops >= 1);
+
+        assert!(throttled.wait_idle(Duration::from_secs(5)));
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        let stats = throttled.stats();
+        assert_eq!((stats.queued_ops, stats.queued_symbols), (0, 0));
+        // The adds queued while waiting are merged
+        assert!((2..=3).contains(&stats.applied_calls));
+        assert_eq!(stats.errors, 0);
+        assert_eq!(
+            feed.subscribed_symbols(),
+            HashSet::from(["AAPL", "MSFT", "QQQ"].map(String::from))
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use crate::{
+    CandleData, Event, EventData, GreeksData, OrderEventData, QuoteData, SeriesData,
+    SpreadOrderData, TheoPriceData, TimeAndSaleData, TradeData, TradeEthData,
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A point in time, converted to milliseconds since the unix epoch as the C API's `time`
+/// parameters expect. Implemented for `SystemTime`, raw millis (`i64`), and with the `chrono`
+/// feature, `chrono::DateTime`.
+pub trait EpochMillis {
+    fn epoch_millis(&self) -> i64;
+}
+
+impl EpochMillis for SystemTime {
+    /// Times before the epoch are clamped to it.
+    fn epoch_millis(&self) -> i64 {
+        self.duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_millis() as i64)
+    }
+}
+
+impl EpochMillis for i64 {
+    fn epoch_millis(&self) -> i64 {
+        *self
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> EpochMillis for chrono::DateTime<Tz> {
+    fn epoch_millis(&self) -> i64 {
+        self.timestamp_millis()
+    }
+}
+
+/// An event payload's timestamp: `time` in milliseconds since the unix epoch, plus for some
+/// types, the sub-millisecond part in `time_nanos`.
+pub trait EventTime {
+    /// Milliseconds since the unix epoch.
+    fn time_millis(&self) -> i64;
+
+    /// Nanoseconds within the millisecond, 0 where the type has no `time_nanos`.
+    fn time_nanos_part(&self) -> i32 {
+        0
+    }
+
+    /// Nanoseconds since the unix epoch.
+    fn time_nanos(&self) -> i64 {
+        self.time_millis()
+            .saturating_mul(1_000_000)
+            .saturating_add(self.time_nanos_part() as i64)
+    }
+
+    /// The time as a UTC `DateTime`, or `None` if out of chrono's range.
+    #[cfg(feature = "chrono")]
+    fn time_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        let nanos = self.time_nanos_part().clamp(0, 999_999) as i64;
+        chrono::DateTime::from_timestamp_millis(self.time_millis())?
+            .checked_add_signed(chrono::TimeDelta::nanoseconds(nanos))
+    }
+}
+
+// Implements `EventTime` from a type's `time` and, optionally, `time_nanos` fields
+macro_rules! event_time {
+    ($($ty:ty),* $(; nanos $($nanos_ty:ty),*)?) => {
+        $(
+            impl EventTime for $ty {
+                fn time_millis(&self) -> i64 {
+                    self.time as i64
+                }
+            }
+        )*
+        $($(
+            impl EventTime for $nanos_ty {
+                fn time_millis(&self) -> i64 {
+                    self.time as i64
+                }
+
+                fn time_nanos_part(&self) -> i32 {
+                    self.time_nanos
+                }
+            }
+        )*)?
+    };
+}
+
+event_time!(
+    TimeAndSaleData, CandleData, GreeksData, TheoPriceData, SeriesData;
+    nanos TradeData, TradeEthData, QuoteData, OrderEventData, SpreadOrderData
+);
+
+/// The date of a day id, i.e. days since the unix epoch, or `None` if unset (0) or out of
+/// range.
+#[cfg(feature = "chrono")]
+pub(crate) fn date_of_day_id(day_id: i32) -> Option<chrono::NaiveDate> {
+    if day_id == 0 {
+        return None;
+    }
+    chrono::NaiveDate::from_ymd_opt(1970, 1, 1)?
+        .checked_add_signed(chrono::TimeDelta::try_days(day_id as i64)?)
+}
+
+impl EventData {
+    /// The payload's timestamp, for types that have one (not Summary, Profile, Underlying or
+    /// Configuration).
+    pub fn event_time(&self) -> Option<&dyn EventTime> {
+        match self {
+            EventData::Trade(trade) => Some(trade),
+            EventData::TradeETH(trade) => Some(trade),
+            EventData::Quote(quote) => Some(quote),
+            EventData::Order(order) => Some(order),
+            EventData::TimeAndSale(time_and_sale) => Some(time_and_sale),
+            EventData::Candle(candle) => Some(candle),
+            EventData::SpreadOrder(spread_order) => Some(spread_order),
+            EventData::Greeks(greeks) => Some(greeks),
+            EventData::TheoPrice(theo_price) => Some(theo_price),
+            EventData::Series(series) => Some(series),
+            EventData::Summary(_)
+            | EventData::Profile(_)
+            | EventData::Underlying(_)
+            | EventData::Configuration(_) => None,
+        }
+    }
+
+    /// Milliseconds since the unix epoch, if the type has a timestamp.
+    pub fn time_millis(&self) -> Option<i64> {
+        self.event_time().map(EventTime::time_millis)
+    }
+
+    /// Nanoseconds since the unix epoch, if the type has a timestamp.
+    pub fn time_nanos(&self) -> Option<i64> {
+        self.event_time().map(EventTime::time_nanos)
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn time_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.event_time()?.time_utc()
+    }
+}
+
+impl Event {
+    #[cfg(feature = "chrono")]
+    pub fn time_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.data.time_utc()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn system_time_millis() {
+        let time = UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+        assert_eq!(time.epoch_millis(), 1_700_000_000_123);
+        assert_eq!((UNIX_EPOCH - Duration::from_secs(1)).epoch_millis(), 0);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_millis() {
+        let time = chrono::DateTime::from_timestamp_millis(1_700_000_000_123).unwrap();
+        assert_eq!(time.epoch_millis(), 1_700_000_000_123);
+    }
+
+    #[test]
+    fn event_times() {
+        let quote = QuoteData {
+            time: 1_700_000_000_123,
+            time_nanos: 456_789,
+            ..Default::default()
+        };
+        let data = EventData::Quote(quote);
+        assert_eq!(data.time_millis(), Some(1_700_000_000_123));
+        assert_eq!(data.time_nanos(), Some(1_700_000_000_123_456_789));
+        let candle = CandleData {
+            time: 5,
+            ..Default::default()
+        };
+        assert_eq!(EventData::Candle(candle).time_nanos(), Some(5_000_000));
+        assert_eq!(EventData::Summary(Default::default()).time_millis(), None);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_event_time() {
+        let trade = TradeData {
+            time: 1_700_000_000_123,
+            time_nanos: 456_789,
+            ..Default::default()
+        };
+        let event = Event::new("AAPL".to_string(), EventData::Trade(trade));
+        let time = event.time_utc().unwrap();
+        assert_eq!(time.timestamp_nanos_opt(), Some(1_700_000_000_123_456_789));
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn day_ids() {
+        use crate::SummaryData;
+
+        let summary = SummaryData {
+            // 2024-01-19
+            day_id: 19741,
+            ..Default::default()
+        };
+        assert_eq!(summary.day(), chrono::NaiveDate::from_ymd_opt(2024, 1, 19));
+        assert_eq!(summary.prev_day(), None);
+        let series = crate::SeriesData {
+            expiration: 19741,
+            ..Default::default()
+        };
+        assert_eq!(series.expiration_date(), summary.day());
+    }
+}
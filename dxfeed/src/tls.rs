@@ -0,0 +1,78 @@
+//! TLS connections, enabled by the `tls` feature (which builds the C API with TLS).
+
+use crate::ConnectionBuilder;
+
+/// Key and trust stores for a TLS connection. With none set, the C API's defaults are used.
+///
+/// The C API takes these as address properties, e.g.
+/// `tls[trustStore=/certs/ca.pem,trustStorePassword=secret]+demo.dxfeed.com:7300`, which
+/// `ConnectionBuilder::tls` renders.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TlsConfig {
+    pub key_store: Option<String>,
+    pub key_store_password: Option<String>,
+    pub trust_store: Option<String>,
+    pub trust_store_password: Option<String>,
+}
+
+impl TlsConfig {
+    /// The client certificate store, and its password if any.
+    pub fn key_store(mut self, path: &str, password: Option<&str>) -> Self {
+        self.key_store = Some(path.to_string());
+        self.key_store_password = password.map(str::to_string);
+        self
+    }
+
+    /// The store of trusted server certificates, and its password if any.
+    pub fn trust_store(mut self, path: &str, password: Option<&str>) -> Self {
+        self.trust_store = Some(path.to_string());
+        self.trust_store_password = password.map(str::to_string);
+        self
+    }
+
+    /// `address` with the `tls` prefix and these properties.
+    pub(crate) fn address(&self, address: &str) -> String {
+        let properties: Vec<String> = [
+            ("keyStore", &self.key_store),
+            ("keyStorePassword", &self.key_store_password),
+            ("trustStore", &self.trust_store),
+            ("trustStorePassword", &self.trust_store_password),
+        ]
+        .iter()
+        .filter_map(|(key, value)| value.as_ref().map(|value| format!("{}={}", key, value)))
+        .collect();
+        if properties.is_empty() {
+            format!("tls+{}", address)
+        } else {
+            format!("tls[{}]+{}", properties.join(","), address)
+        }
+    }
+}
+
+impl ConnectionBuilder {
+    /// Connect over TLS, configured by `config`.
+    pub fn tls(mut self, config: TlsConfig) -> Self {
+        self.tls = Some(config);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn address_properties() {
+        assert_eq!(
+            TlsConfig::default().address("demo.dxfeed.com:7300"),
+            "tls+demo.dxfeed.com:7300"
+        );
+        let config = TlsConfig::default()
+            .trust_store("/certs/ca.pem", Some("secret"))
+            .key_store("/certs/client.p12", None);
+        assert_eq!(
+            config.address("demo.dxfeed.com:7300"),
+            "tls[keyStore=/certs/client.p12,trustStore=/certs/ca.pem,trustStorePassword=secret]+demo.dxfeed.com:7300"
+        );
+    }
+}
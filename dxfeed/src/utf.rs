@@ -0,0 +1,206 @@
+//! How invalid wide strings from the C API are converted, and a count of how often it happens.
+
+use crate::{dxf_char_t, dxf_const_string_t, Error};
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use widestring::WideCStr;
+
+/// What to do with a symbol or string field that isn't valid UTF-32 (UTF-16 on Windows). Every
+/// occurrence is counted by `utf_error_count`, whatever the strategy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UtfStrategy {
+    /// Replace invalid characters with U+FFFD.
+    #[default]
+    Lossy,
+    /// Fail the event's conversion with `Error::UtfError`.
+    Strict,
+    /// Convert the invalid string to an empty one.
+    SkipField,
+}
+
+static STRATEGY: AtomicU8 = AtomicU8::new(0);
+static UTF_ERRORS: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    // The first error under `Strict` while converting a payload through an infallible `From`
+    static STRICT_ERROR: RefCell<Option<Error>> = const { RefCell::new(None) };
+}
+
+/// Set the strategy used by every subsequent conversion, process-wide.
+pub fn set_utf_strategy(strategy: UtfStrategy) {
+    STRATEGY.store(strategy as u8, Ordering::Relaxed);
+}
+
+pub fn utf_strategy() -> UtfStrategy {
+    match STRATEGY.load(Ordering::Relaxed) {
+        1 => UtfStrategy::Strict,
+        2 => UtfStrategy::SkipField,
+        _ => UtfStrategy::Lossy,
+    }
+}
+
+/// Total invalid strings encountered by conversions since the process started.
+pub fn utf_error_count() -> u64 {
+    UTF_ERRORS.load(Ordering::Relaxed)
+}
+
+/// Convert `s` according to the current strategy, or fail under `Strict`. Fails with
+/// `Error::NullPointer` if `s` is null.
+///
+/// # Safety
+/// `s` must be null or a valid nul-terminated wide string.
+pub(crate) unsafe fn decode(s: dxf_const_string_t) -> Result<String, Error> {
+    let mut buf = String::new();
+    decode_into(&mut buf, s)?;
+    Ok(buf)
+}
+
+/// Convert `s` as `decode` does, replacing the contents of `buf` and reusing its allocation.
+/// Valid strings, nearly all of them, are decoded in one pass with at most one allocation.
+///
+/// # Safety
+/// As for `decode`.
+pub(crate) unsafe fn decode_into(buf: &mut String, s: dxf_const_string_t) -> Result<(), Error> {
+    if s.is_null() {
+        return Err(Error::NullPointer);
+    }
+    let s = WideCStr::from_ptr_str(s as *const _);
+    buf.clear();
+    // Exact for ASCII, the usual case
+    buf.reserve(s.len());
+    for c in s.chars() {
+        match c {
+            Ok(c) => buf.push(c),
+            Err(_) => {
+                buf.clear();
+                UTF_ERRORS.fetch_add(1, Ordering::Relaxed);
+                return match utf_strategy() {
+                    UtfStrategy::Lossy => {
+                        buf.extend(s.chars_lossy());
+                        Ok(())
+                    }
+                    // Validate again for the error type the iterator doesn't give
+                    UtfStrategy::Strict => Err(s.to_string().expect_err("invalid").into()),
+                    UtfStrategy::SkipField => Ok(()),
+                };
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Convert a fixed-size, nul-padded character array, e.g. an order source, with invalid
+/// characters replaced as by `decode_char`.
+pub(crate) fn decode_chars(chars: &[dxf_char_t]) -> String {
+    let len = chars.iter().position(|c| *c == 0).unwrap_or(chars.len());
+    let mut s = String::with_capacity(len);
+    s.extend(chars[..len].iter().map(|c| decode_char(*c)));
+    s
+}
+
+/// Convert a payload field for an infallible `From` conversion, a null field to an empty
+/// string. Under `Strict`, an invalid field is converted lossily and the error kept for
+/// `take_strict_error`.
+///
+/// # Safety
+/// As for `decode`.
+pub(crate) unsafe fn decode_field(s: dxf_const_string_t) -> String {
+    if s.is_null() {
+        return String::new();
+    }
+    decode(s).unwrap_or_else(|e| {
+        STRICT_ERROR.with(|error| {
+            error.borrow_mut().get_or_insert(e);
+        });
+        WideCStr::from_ptr_str(s as *const _).to_string_lossy()
+    })
+}
+
+/// Convert a single character field, e.g. an exchange code, with invalid ones counted and
+/// replaced by U+FFFD.
+pub(crate) fn decode_char(c: dxf_char_t) -> char {
+    char::from_u32(c as u32).unwrap_or_else(|| {
+        UTF_ERRORS.fetch_add(1, Ordering::Relaxed);
+        char::REPLACEMENT_CHARACTER
+    })
+}
+
+/// The error kept by `decode_field` on this thread since the last call, if any.
+pub(crate) fn take_strict_error() -> Option<Error> {
+    STRICT_ERROR.with(|error| error.borrow_mut().take())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dxf_char_t, dxf_profile_t, EventData, DXF_ET_PROFILE};
+    use widestring::WideCString;
+
+    // Tests sharing the process-wide strategy run in one function
+    #[test]
+    fn strategies() {
+        let valid = WideCString::from_str("AAPL").unwrap();
+        // A lone surrogate, invalid in both UTF-32 and UTF-16
+        let invalid: Vec<dxf_char_t> = vec![0x41, 0xD800, 0];
+        let mut profile: dxf_profile_t = unsafe { std::mem::zeroed() };
+        profile.description = invalid.as_ptr() as *const _;
+        profile.status_reason = valid.as_ptr() as *const _;
+        let data = &profile as *const dxf_profile_t as *const _;
+
+        let before = utf_error_count();
+        let description = |strategy| {
+            set_utf_strategy(strategy);
+            match EventData::try_get_event_data(DXF_ET_PROFILE, data) {
+                Ok(EventData::Profile(profile)) => Some(profile.description),
+                _ => None,
+            }
+        };
+        assert_eq!(description(UtfStrategy::Lossy).unwrap(), "A\u{FFFD}");
+        assert_eq!(description(UtfStrategy::SkipField).unwrap(), "");
+        assert!(description(UtfStrategy::Strict).is_none());
+        assert!(take_strict_error().is_none());
+        assert!(unsafe { decode(invalid.as_ptr()) }.is_err());
+        set_utf_strategy(UtfStrategy::Lossy);
+        assert_eq!(unsafe { decode(invalid.as_ptr()) }.unwrap(), "A\u{FFFD}");
+        assert_eq!(utf_error_count() - before, 5);
+    }
+
+    #[test]
+    fn null_strings() {
+        // Every string field null
+        let profile: dxf_profile_t = unsafe { std::mem::zeroed() };
+        let data = &profile as *const dxf_profile_t as *const _;
+        match EventData::try_get_event_data(DXF_ET_PROFILE, data) {
+            Ok(EventData::Profile(profile)) => {
+                assert_eq!(profile.description, "");
+                assert_eq!(profile.status_reason, "");
+            }
+            other => panic!("Unexpected {:?}", other),
+        }
+        assert!(matches!(
+            unsafe { decode(std::ptr::null()) },
+            Err(Error::NullPointer)
+        ));
+        assert!(matches!(
+            EventData::try_get_event_data(DXF_ET_PROFILE, std::ptr::null()),
+            Err(Error::NullPointer)
+        ));
+    }
+
+    #[test]
+    fn reuses_buffers() {
+        let long = WideCString::from_str(".AAPL240621C190").unwrap();
+        let short = WideCString::from_str("SPY").unwrap();
+        let mut buf = String::new();
+        unsafe { decode_into(&mut buf, long.as_ptr() as *const _) }.unwrap();
+        assert_eq!(buf, ".AAPL240621C190");
+        let capacity = buf.capacity();
+        unsafe { decode_into(&mut buf, short.as_ptr() as *const _) }.unwrap();
+        assert_eq!(buf, "SPY");
+        assert_eq!(buf.capacity(), capacity);
+
+        let source: [dxf_char_t; 5] = [0x4E, 0x54, 0x56, 0, 0];
+        assert_eq!(decode_chars(&source), "NTV");
+        assert_eq!(decode_chars(&[0x41, 0x42]), "AB");
+    }
+}
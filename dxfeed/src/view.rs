@@ -0,0 +1,325 @@
+use crate::{
+    dxf_const_string_t, dxf_event_data_t, utf, Error, Event, EventData, EventFlags, EventType,
+    RawEvent, RawEventData, Side, DXF_ET_CANDLE, DXF_ET_CONFIGURATION, DXF_ET_GREEKS, DXF_ET_ORDER,
+    DXF_ET_PROFILE, DXF_ET_QUOTE, DXF_ET_SERIES, DXF_ET_SPREAD_ORDER, DXF_ET_SUMMARY,
+    DXF_ET_THEO_PRICE, DXF_ET_TIME_AND_SALE, DXF_ET_TRADE, DXF_ET_TRADE_ETH, DXF_ET_UNDERLYING,
+};
+use std::os::raw::c_int;
+use widestring::WideCStr;
+
+/// A view of an event inside a `Subscription::attach_ref` listener, with typed accessors read
+/// straight from the C struct. Nothing is allocated or copied until `to_owned`.
+///
+/// Unlike a `RawEvent`, an `EventRef` is only made from structs the C API delivered, so its
+/// string accessors can safely borrow the C strings.
+#[derive(Clone, Copy)]
+pub struct EventRef<'a> {
+    sym: &'a str,
+    data: RawEventData<'a>,
+}
+
+/// Borrow a C string field, `None` if null.
+///
+/// # Safety
+/// `s` must be null or a valid nul-terminated wide string that outlives `'a`.
+unsafe fn wide_str<'a>(s: dxf_const_string_t) -> Option<&'a WideCStr> {
+    if s.is_null() {
+        None
+    } else {
+        Some(WideCStr::from_ptr_str(s as *const _))
+    }
+}
+
+impl<'a> EventRef<'a> {
+    /// # Safety
+    /// `raw` must borrow a struct delivered by the C API, whose strings outlive `'a`.
+    pub(crate) unsafe fn new(raw: RawEvent<'a>) -> Self {
+        EventRef {
+            sym: raw.sym,
+            data: raw.data,
+        }
+    }
+
+    pub fn sym(&self) -> &'a str {
+        self.sym
+    }
+
+    /// The borrowed C struct, for fields without an accessor.
+    pub fn raw(&self) -> RawEventData<'a> {
+        self.data
+    }
+
+    fn c_event_type(&self) -> c_int {
+        match self.data {
+            RawEventData::Trade(_) => DXF_ET_TRADE,
+            RawEventData::Quote(_) => DXF_ET_QUOTE,
+            RawEventData::Summary(_) => DXF_ET_SUMMARY,
+            RawEventData::Profile(_) => DXF_ET_PROFILE,
+            RawEventData::Order(_) => DXF_ET_ORDER,
+            RawEventData::TimeAndSale(_) => DXF_ET_TIME_AND_SALE,
+            RawEventData::Candle(_) => DXF_ET_CANDLE,
+            RawEventData::TradeETH(_) => DXF_ET_TRADE_ETH,
+            RawEventData::SpreadOrder(_) => DXF_ET_SPREAD_ORDER,
+            RawEventData::Greeks(_) => DXF_ET_GREEKS,
+            RawEventData::TheoPrice(_) => DXF_ET_THEO_PRICE,
+            RawEventData::Underlying(_) => DXF_ET_UNDERLYING,
+            RawEventData::Series(_) => DXF_ET_SERIES,
+            RawEventData::Configuration(_) => DXF_ET_CONFIGURATION,
+        }
+    }
+
+    pub fn event_type(&self) -> EventType {
+        match self.data {
+            RawEventData::Trade(_) => EventType::Trade,
+            RawEventData::Quote(_) => EventType::Quote,
+            RawEventData::Summary(_) => EventType::Summary,
+            RawEventData::Profile(_) => EventType::Profile,
+            RawEventData::Order(_) => EventType::Order,
+            RawEventData::TimeAndSale(_) => EventType::TimeAndSale,
+            RawEventData::Candle(_) => EventType::Candle,
+            RawEventData::TradeETH(_) => EventType::TradeETH,
+            RawEventData::SpreadOrder(_) => EventType::SpreadOrder,
+            RawEventData::Greeks(_) => EventType::Greeks,
+            RawEventData::TheoPrice(_) => EventType::TheoPrice,
+            RawEventData::Underlying(_) => EventType::Underlying,
+            RawEventData::Series(_) => EventType::Series,
+            RawEventData::Configuration(_) => EventType::Configuration,
+        }
+    }
+
+    /// Milliseconds since the unix epoch, for types with a timestamp, as
+    /// `EventData::time_millis`.
+    pub fn time_millis(&self) -> Option<i64> {
+        Some(match self.data {
+            RawEventData::Trade(trade) | RawEventData::TradeETH(trade) => trade.time,
+            RawEventData::Quote(quote) => quote.time,
+            RawEventData::Order(order) => order.time,
+            RawEventData::TimeAndSale(time_and_sale) => time_and_sale.time,
+            RawEventData::Candle(candle) => candle.time,
+            RawEventData::SpreadOrder(spread_order) => spread_order.time as i64,
+            RawEventData::Greeks(greeks) => greeks.time,
+            RawEventData::TheoPrice(theo_price) => theo_price.time,
+            RawEventData::Series(series) => series.time,
+            RawEventData::Summary(_)
+            | RawEventData::Profile(_)
+            | RawEventData::Underlying(_)
+            | RawEventData::Configuration(_) => return None,
+        })
+    }
+
+    /// Nanoseconds since the unix epoch, for types with a timestamp, as `EventData::time_nanos`.
+    pub fn time_nanos(&self) -> Option<i64> {
+        let nanos = match self.data {
+            RawEventData::Trade(trade) | RawEventData::TradeETH(trade) => trade.time_nanos,
+            RawEventData::Quote(quote) => quote.time_nanos,
+            RawEventData::Order(order) => order.time_nanos,
+            RawEventData::SpreadOrder(spread_order) => spread_order.time_nanos,
+            _ => 0,
+        };
+        Some(
+            self.time_millis()?
+                .saturating_mul(1_000_000)
+                .saturating_add(nanos as i64),
+        )
+    }
+
+    /// The event flags, for types that take part in snapshots and transactions.
+    pub fn event_flags(&self) -> Option<EventFlags> {
+        let flags = match self.data {
+            RawEventData::Order(order) => order.event_flags,
+            RawEventData::TimeAndSale(time_and_sale) => time_and_sale.event_flags,
+            RawEventData::Candle(candle) => candle.event_flags,
+            RawEventData::Greeks(greeks) => greeks.event_flags,
+            RawEventData::Series(series) => series.event_flags,
+            _ => return None,
+        };
+        Some(EventFlags::from(flags))
+    }
+
+    /// The price of a Trade, TradeETH, Order, TimeAndSale, SpreadOrder, Greeks or TheoPrice.
+    pub fn price(&self) -> Option<f64> {
+        Some(match self.data {
+            RawEventData::Trade(trade) | RawEventData::TradeETH(trade) => trade.price,
+            RawEventData::Order(order) => order.price,
+            RawEventData::TimeAndSale(time_and_sale) => time_and_sale.price,
+            RawEventData::SpreadOrder(spread_order) => spread_order.price,
+            RawEventData::Greeks(greeks) => greeks.price,
+            RawEventData::TheoPrice(theo_price) => theo_price.price,
+            _ => return None,
+        })
+    }
+
+    /// The size of a Trade, TradeETH, Order, TimeAndSale or SpreadOrder.
+    pub fn size(&self) -> Option<f64> {
+        Some(match self.data {
+            RawEventData::Trade(trade) | RawEventData::TradeETH(trade) => trade.size,
+            RawEventData::Order(order) => order.size,
+            RawEventData::TimeAndSale(time_and_sale) => time_and_sale.size,
+            RawEventData::SpreadOrder(spread_order) => spread_order.size,
+            _ => return None,
+        })
+    }
+
+    /// The exchange code of a Trade, TradeETH, Summary, Order or TimeAndSale.
+    pub fn exchange_code(&self) -> Option<char> {
+        let code = match self.data {
+            RawEventData::Trade(trade) | RawEventData::TradeETH(trade) => trade.exchange_code,
+            RawEventData::Summary(summary) => summary.exchange_code,
+            RawEventData::Order(order) => order.exchange_code,
+            RawEventData::TimeAndSale(time_and_sale) => time_and_sale.exchange_code,
+            _ => return None,
+        };
+        Some(utf::decode_char(code))
+    }
+
+    /// The side of an Order or TimeAndSale.
+    pub fn side(&self) -> Option<Side> {
+        let side = match self.data {
+            RawEventData::Order(order) => order.side,
+            RawEventData::TimeAndSale(time_and_sale) => time_and_sale.side,
+            _ => return None,
+        };
+        Some(side.try_into().unwrap_or_default())
+    }
+
+    /// The market maker or spread symbol of an Order, as `OrderEventData::mm_or_spread`.
+    pub fn mm_or_spread(&self) -> Option<&'a WideCStr> {
+        match self.data {
+            RawEventData::Order(order) => unsafe { wide_str(order.__bindgen_anon_1.market_maker) },
+            _ => None,
+        }
+    }
+
+    /// The spread symbol of a SpreadOrder.
+    pub fn spread_symbol(&self) -> Option<&'a WideCStr> {
+        match self.data {
+            RawEventData::SpreadOrder(spread_order) => unsafe {
+                wide_str(spread_order.spread_symbol)
+            },
+            _ => None,
+        }
+    }
+
+    /// The description of a Profile.
+    pub fn description(&self) -> Option<&'a WideCStr> {
+        match self.data {
+            RawEventData::Profile(profile) => unsafe { wide_str(profile.description) },
+            _ => None,
+        }
+    }
+
+    /// The trading status reason of a Profile.
+    pub fn status_reason(&self) -> Option<&'a WideCStr> {
+        match self.data {
+            RawEventData::Profile(profile) => unsafe { wide_str(profile.status_reason) },
+            _ => None,
+        }
+    }
+
+    /// The sale conditions of a TimeAndSale.
+    pub fn exchange_sale_conditions(&self) -> Option<&'a WideCStr> {
+        match self.data {
+            RawEventData::TimeAndSale(time_and_sale) => unsafe {
+                wide_str(time_and_sale.exchange_sale_conditions)
+            },
+            _ => None,
+        }
+    }
+
+    /// The buyer of a TimeAndSale.
+    pub fn buyer(&self) -> Option<&'a WideCStr> {
+        match self.data {
+            RawEventData::TimeAndSale(time_and_sale) => unsafe { wide_str(time_and_sale.buyer) },
+            _ => None,
+        }
+    }
+
+    /// The seller of a TimeAndSale.
+    pub fn seller(&self) -> Option<&'a WideCStr> {
+        match self.data {
+            RawEventData::TimeAndSale(time_and_sale) => unsafe { wide_str(time_and_sale.seller) },
+            _ => None,
+        }
+    }
+
+    /// Convert to an owned `Event`, decoding its strings as `Subscription::attach` would.
+    pub fn to_owned(&self) -> Result<Event, Error> {
+        let data = match self.data {
+            RawEventData::Trade(data) | RawEventData::TradeETH(data) => {
+                data as *const _ as *const _
+            }
+            RawEventData::Quote(data) => data as *const _ as *const _,
+            RawEventData::Summary(data) => data as *const _ as *const _,
+            RawEventData::Profile(data) => data as *const _ as *const _,
+            RawEventData::Order(data) => data as *const _ as *const _,
+            RawEventData::TimeAndSale(data) => data as *const _ as *const _,
+            RawEventData::Candle(data) => data as *const _ as *const _,
+            RawEventData::SpreadOrder(data) => data as *const _ as *const _,
+            RawEventData::Greeks(data) => data as *const _ as *const _,
+            RawEventData::TheoPrice(data) => data as *const _ as *const _,
+            RawEventData::Underlying(data) => data as *const _ as *const _,
+            RawEventData::Series(data) => data as *const _ as *const _,
+            RawEventData::Configuration(data) => data as *const _ as *const _,
+        };
+        Ok(Event {
+            sym: self.sym.to_string(),
+            data: EventData::try_get_event_data(
+                self.c_event_type(),
+                data as *const dxf_event_data_t,
+            )?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dxf_time_and_sale_t, raw};
+    use widestring::WideCString;
+
+    #[test]
+    fn time_and_sale_view() {
+        let sym = WideCString::from_str("AAPL").unwrap();
+        let buyer = WideCString::from_str("NSDQ").unwrap();
+        let empty = WideCString::default();
+        let time_and_sale = dxf_time_and_sale_t {
+            time: 1_700_000_000_000,
+            exchange_code: 'Q' as _,
+            price: 189.5,
+            size: 100.0,
+            side: 1,
+            buyer: buyer.as_ptr() as _,
+            seller: std::ptr::null(),
+            exchange_sale_conditions: empty.as_ptr() as _,
+            ..unsafe { std::mem::zeroed() }
+        };
+        let mut buf = String::new();
+        let event = unsafe {
+            EventRef::new(
+                raw::decode(
+                    &mut buf,
+                    DXF_ET_TIME_AND_SALE,
+                    sym.as_ptr() as dxf_const_string_t,
+                    &time_and_sale as *const _ as *const dxf_event_data_t,
+                )
+                .unwrap(),
+            )
+        };
+        assert_eq!(event.sym(), "AAPL");
+        assert_eq!(event.event_type(), EventType::TimeAndSale);
+        assert_eq!(event.time_nanos(), Some(1_700_000_000_000_000_000));
+        assert_eq!((event.price(), event.size()), (Some(189.5), Some(100.0)));
+        assert_eq!(event.exchange_code(), Some('Q'));
+        assert_eq!(event.side(), Some(Side::Buy));
+        assert_eq!(event.buyer().unwrap().to_string_lossy(), "NSDQ");
+        assert_eq!(event.seller(), None);
+        assert_eq!(event.description(), None);
+
+        let owned = event.to_owned().unwrap();
+        assert_eq!(owned.sym, "AAPL");
+        match owned.data {
+            EventData::TimeAndSale(data) => assert_eq!(data.buyer, "NSDQ"),
+            _ => panic!("Expected a TimeAndSale"),
+        }
+    }
+}
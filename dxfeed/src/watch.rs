@@ -0,0 +1,111 @@
+//! Latest-value `tokio::sync::watch` channels per symbol, enabled by the `tokio` feature.
+
+use crate::{Error, Event, EventData, QuoteData, Subscription, TradeData};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+
+/// The watched symbols of a subscription, updated by its listener.
+#[derive(Default)]
+pub(crate) struct Watches {
+    quotes: HashMap<String, watch::Sender<Option<QuoteData>>>,
+    trades: HashMap<String, watch::Sender<Option<TradeData>>>,
+}
+
+impl Watches {
+    fn update(&self, event: &Event) {
+        match &event.data {
+            EventData::Quote(quote) => {
+                if let Some(sender) = self.quotes.get(&event.sym) {
+                    sender.send_replace(Some(*quote));
+                }
+            }
+            EventData::Trade(trade) => {
+                if let Some(sender) = self.trades.get(&event.sym) {
+                    sender.send_replace(Some(*trade));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn watch_in<T>(
+    senders: &mut HashMap<String, watch::Sender<Option<T>>>,
+    symbol: &str,
+) -> watch::Receiver<Option<T>> {
+    senders
+        .entry(symbol.to_string())
+        .or_insert_with(|| watch::channel(None).0)
+        .subscribe()
+}
+
+impl Subscription {
+    /// A receiver always holding the latest quote for `symbol`, or None until the first arrives.
+    /// The subscription must include `DXF_ET_QUOTE`.
+    ///
+    /// The first watch attaches this subscription's listener, which is shared by later watches
+    /// and replaced by any call to `attach`, after which watches stop updating.
+    pub fn watch_quote(
+        &mut self,
+        symbol: &str,
+    ) -> Result<watch::Receiver<Option<QuoteData>>, Error> {
+        let watches = self.watches()?;
+        let receiver = watch_in(&mut watches.lock().unwrap().quotes, symbol);
+        self.add_symbol(symbol)?;
+        Ok(receiver)
+    }
+
+    /// As `watch_quote`, for the latest trade. The subscription must include `DXF_ET_TRADE`.
+    pub fn watch_trade(
+        &mut self,
+        symbol: &str,
+    ) -> Result<watch::Receiver<Option<TradeData>>, Error> {
+        let watches = self.watches()?;
+        let receiver = watch_in(&mut watches.lock().unwrap().trades, symbol);
+        self.add_symbol(symbol)?;
+        Ok(receiver)
+    }
+
+    fn watches(&mut self) -> Result<Arc<Mutex<Watches>>, Error> {
+        if let Some(watches) = &self.watches {
+            return Ok(watches.clone());
+        }
+        let watches = Arc::new(Mutex::new(Watches::default()));
+        self.attach(watch_listener(watches.clone()))?;
+        self.watches = Some(watches.clone());
+        Ok(watches)
+    }
+}
+
+fn watch_listener(
+    watches: Arc<Mutex<Watches>>,
+) -> impl FnMut(Result<Event, Error>) + Send + 'static {
+    move |event| {
+        if let Ok(event) = event {
+            watches.lock().unwrap().update(&event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latest_quote() {
+        let watches = Arc::new(Mutex::new(Watches::default()));
+        let mut aapl = watch_in(&mut watches.lock().unwrap().quotes, "AAPL");
+        let mut listener = watch_listener(watches.clone());
+        assert!(aapl.borrow().is_none());
+
+        let mut quote = QuoteData::default();
+        for (sym, bid) in [("AAPL", 1.0), ("MSFT", 2.0), ("AAPL", 3.0)] {
+            quote.bid.price = bid;
+            listener(Ok(Event::new(sym.to_string(), EventData::Quote(quote))));
+        }
+        assert!(aapl.has_changed().unwrap());
+        assert_eq!(aapl.borrow_and_update().unwrap().bid.price, 3.0);
+        assert!(!aapl.has_changed().unwrap());
+    }
+}
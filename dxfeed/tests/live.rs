@@ -0,0 +1,161 @@
+//! End-to-end tests against dxFeed's public demo feed, catching regressions in the conversion of
+//! the C API's events. They need network access, so they only run with `DXFEED_LIVE_TESTS=1`:
+//!
+//! ```text
+//! DXFEED_LIVE_TESTS=1 cargo test --test live -- --test-threads 1
+//! ```
+//!
+//! `DXFEED_ADDRESS` overrides the address, by default demo.dxfeed.com:7300. Event types that
+//! only stream during market hours are checked for what arrives, but not required to arrive.
+
+use dxfeed::{Connection, Error, Event, EventData, EventType, Subscription};
+use std::sync::mpsc::channel;
+use std::time::{Duration, Instant, SystemTime};
+
+const DEFAULT_ADDRESS: &str = "demo.dxfeed.com:7300";
+
+/// How long to collect events for.
+const COLLECT_FOR: Duration = Duration::from_secs(10);
+
+fn live_address() -> Option<String> {
+    if std::env::var("DXFEED_LIVE_TESTS").map_or(true, |flag| flag != "1") {
+        eprintln!("Skipping live test; set DXFEED_LIVE_TESTS=1 to run it");
+        return None;
+    }
+    Some(std::env::var("DXFEED_ADDRESS").unwrap_or_else(|_| DEFAULT_ADDRESS.to_string()))
+}
+
+/// The events of `event_type` for `symbols` received within `COLLECT_FOR`, failing on any
+/// conversion error. Time-series types are subscribed from an hour ago.
+fn collect(address: &str, event_type: EventType, symbols: &[&str]) -> Vec<Event> {
+    let connection = Connection::new(address).expect("connect");
+    let mut subscription = match event_type {
+        EventType::Candle | EventType::TimeAndSale | EventType::Greeks | EventType::Series => {
+            let from = SystemTime::now() - Duration::from_secs(3600);
+            Subscription::new_timed(&connection, event_type, from)
+        }
+        _ => Subscription::new(&connection, event_type),
+    }
+    .expect("subscribe");
+    let (sender, receiver) = channel::<Result<Event, Error>>();
+    subscription
+        .attach(move |event| {
+            let _ = sender.send(event);
+        })
+        .expect("attach");
+    subscription.add_symbols(symbols).expect("add symbols");
+
+    let deadline = Instant::now() + COLLECT_FOR;
+    let mut events = Vec::new();
+    while let Some(left) = deadline.checked_duration_since(Instant::now()) {
+        match receiver.recv_timeout(left) {
+            Ok(event) => events.push(event.expect("convert event")),
+            Err(_) => break,
+        }
+    }
+    for event in &events {
+        assert_eq!(EventType::from(event), event_type);
+        assert!(
+            symbols.contains(&event.sym.as_str()),
+            "Unexpected symbol {}",
+            event.sym
+        );
+    }
+    events
+}
+
+#[test]
+fn live_quotes() {
+    let Some(address) = live_address() else {
+        return;
+    };
+    let events = collect(&address, EventType::Quote, &["AAPL", "MSFT", "SPY"]);
+    assert!(!events.is_empty(), "No quotes received");
+    for event in events {
+        let quote = event.data.as_quote().unwrap();
+        assert!(quote.time > 0);
+        assert!(quote.bid.price.is_nan() || quote.bid.price >= 0.0);
+        assert!(quote.ask.price.is_nan() || quote.ask.price >= 0.0);
+    }
+}
+
+#[test]
+fn live_trades() {
+    let Some(address) = live_address() else {
+        return;
+    };
+    let events = collect(&address, EventType::Trade, &["AAPL", "MSFT", "SPY"]);
+    assert!(!events.is_empty(), "No trades received");
+    for event in events {
+        let trade = event.data.as_trade().unwrap();
+        assert!(trade.price > 0.0);
+        assert_eq!(trade.flags().direction, trade.direction);
+    }
+}
+
+#[test]
+fn live_profiles() {
+    let Some(address) = live_address() else {
+        return;
+    };
+    let events = collect(&address, EventType::Profile, &["AAPL", "MSFT"]);
+    assert!(!events.is_empty(), "No profiles received");
+    for event in events {
+        let profile = event.data.as_profile().unwrap();
+        assert!(!profile.description.is_empty());
+    }
+}
+
+#[test]
+fn live_summaries() {
+    let Some(address) = live_address() else {
+        return;
+    };
+    let events = collect(&address, EventType::Summary, &["AAPL", "MSFT"]);
+    assert!(!events.is_empty(), "No summaries received");
+    for event in events {
+        let summary = event.data.as_summary().unwrap();
+        assert!(summary.day_id > 0);
+    }
+}
+
+#[test]
+fn live_candles() {
+    let Some(address) = live_address() else {
+        return;
+    };
+    let events = collect(&address, EventType::Candle, &["AAPL{=1m}"]);
+    for event in events {
+        let candle = event.data.as_candle().unwrap();
+        let removed = event
+            .data
+            .event_flags()
+            .is_some_and(|f| f.is_remove_event());
+        if !removed {
+            let unset = candle.low.is_nan() || candle.high.is_nan();
+            assert!(unset || candle.low <= candle.high, "{:?}", candle);
+        }
+    }
+}
+
+#[test]
+fn live_market_hours_types() {
+    let Some(address) = live_address() else {
+        return;
+    };
+    for event_type in [
+        EventType::Order,
+        EventType::TimeAndSale,
+        EventType::TradeETH,
+        EventType::Greeks,
+        EventType::TheoPrice,
+        EventType::Underlying,
+        EventType::Series,
+    ] {
+        let events = collect(&address, event_type, &["AAPL", "SPY"]);
+        if let Some(EventData::TimeAndSale(tns)) = events.first().map(|event| &event.data) {
+            assert_eq!(tns.flags().side, tns.side);
+        }
+        eprintln!("{}: {} events", event_type, events.len());
+    }
+}
@@ -0,0 +1,41 @@
+extern crate bindgen;
+
+use std::env;
+use std::path::PathBuf;
+
+const SDK_DIR_VAR: &str = "DXFEED_GRAAL_NATIVE_SDK_DIR";
+
+fn main() {
+    let sdk_dir = PathBuf::from(env::var(SDK_DIR_VAR).unwrap_or_else(|_| {
+        panic!(
+            "{} must point to an unpacked dxfeed-graal-native-sdk release",
+            SDK_DIR_VAR
+        )
+    }));
+
+    println!("cargo:rerun-if-env-changed={}", SDK_DIR_VAR);
+    println!("cargo:rerun-if-changed=wrapper.h");
+    println!(
+        "cargo:rustc-link-search=native={}",
+        sdk_dir.join("lib").display()
+    );
+    println!("cargo:rustc-link-lib=dylib=DxFeedGraalNativeSdk");
+
+    let bindings = bindgen::Builder::default()
+        .header("wrapper.h")
+        .clang_arg(format!("-I{}", sdk_dir.join("include").display()))
+        .allowlist_function("(dxfg|graal)_.*")
+        .allowlist_type("(dxfg|graal)_.*")
+        .allowlist_var("DXFG_.*")
+        // `DXFG_EVENT_QUOTE` rather than `dxfg_event_clazz_t_DXFG_EVENT_QUOTE`: the SDK's
+        // enumerators are already prefixed
+        .prepend_enum_name(false)
+        .derive_partialeq(true)
+        .generate()
+        .expect("Unable to generate bindings");
+
+    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
+    bindings
+        .write_to_file(out_path.join("bindings.rs"))
+        .expect("Couldn't write bindings!");
+}
@@ -0,0 +1,2 @@
+#include "graal_isolate.h"
+#include "dxfg_api.h"
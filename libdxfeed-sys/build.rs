@@ -70,8 +70,9 @@ impl CustomParser {
 }
 
 fn main() {
+    let tls = env::var("CARGO_FEATURE_TLS").is_ok();
     let dst = Config::new("dxfeed-c-api")
-        .define("DISABLE_TLS", "ON")
+        .define("DISABLE_TLS", if tls { "OFF" } else { "ON" })
         .define("BUILD_STATIC_LIBS", "ON")
         .build();
 
@@ -103,6 +104,13 @@ fn main() {
     let suffix = if profile == "debug" { "d" } else { "" };
     println!("cargo:rustc-link-lib=static={}{}", "DXFeed", suffix);
 
+    // The C API's TLS support is built on LibreSSL
+    if tls {
+        for lib in ["tls", "ssl", "crypto"] {
+            println!("cargo:rustc-link-lib={}", lib);
+        }
+    }
+
     // Tell cargo to invalidate the built crate whenever the wrapper changes
     println!("cargo:rerun-if-changed=wrapper.h");
 